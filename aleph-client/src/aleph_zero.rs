@@ -9483,6 +9483,24 @@ pub mod api {
                     const PALLET: &'static str = "Aleph";
                     const CALL: &'static str = "unsigned_submit_abft_score";
                 }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct CancelScheduledFinalityVersionChange;
+                impl ::subxt::blocks::StaticExtrinsic for CancelScheduledFinalityVersionChange {
+                    const PALLET: &'static str = "Aleph";
+                    const CALL: &'static str = "cancel_scheduled_finality_version_change";
+                }
             }
             pub struct TransactionApi;
             impl TransactionApi {
@@ -9645,6 +9663,47 @@ pub mod api {
                 const PALLET: &'static str = "Aleph";
                 const EVENT: &'static str = "InflationParametersChange";
             }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            pub struct CancelFinalityVersionChange(pub runtime_types::primitives::VersionChange);
+            impl ::subxt::events::StaticEvent for CancelFinalityVersionChange {
+                const PALLET: &'static str = "Aleph";
+                const EVENT: &'static str = "CancelFinalityVersionChange";
+            }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            #[doc = "Finality committee of the `session` has been rotated in, and consists of `count`"]
+            #[doc = "authorities."]
+            pub struct AuthoritiesRotated {
+                pub session: ::core::primitive::u32,
+                pub count: ::core::primitive::u32,
+            }
+            impl ::subxt::events::StaticEvent for AuthoritiesRotated {
+                const PALLET: &'static str = "Aleph";
+                const EVENT: &'static str = "AuthoritiesRotated";
+            }
         }
         pub mod storage {
             use super::runtime_types;
@@ -9990,6 +10049,68 @@ pub mod api {
                     const PALLET: &'static str = "Elections";
                     const CALL: &'static str = "set_elections_openness";
                 }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct AddToAllowlist {
+                    pub validators: ::std::vec::Vec<
+                        ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    >,
+                }
+                impl ::subxt::blocks::StaticExtrinsic for AddToAllowlist {
+                    const PALLET: &'static str = "Elections";
+                    const CALL: &'static str = "add_to_allowlist";
+                }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct RemoveFromAllowlist {
+                    pub validators: ::std::vec::Vec<
+                        ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    >,
+                }
+                impl ::subxt::blocks::StaticExtrinsic for RemoveFromAllowlist {
+                    const PALLET: &'static str = "Elections";
+                    const CALL: &'static str = "remove_from_allowlist";
+                }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct ForceRotateCommittee;
+                impl ::subxt::blocks::StaticExtrinsic for ForceRotateCommittee {
+                    const PALLET: &'static str = "Elections";
+                    const CALL: &'static str = "force_rotate_committee";
+                }
             }
             pub struct TransactionApi;
             impl TransactionApi {
@@ -10075,6 +10196,56 @@ pub mod api {
                 const PALLET: &'static str = "Elections";
                 const EVENT: &'static str = "ChangeValidators";
             }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            #[doc = "Banned validators that would be elected otherwise, but were excluded from the elections"]
+            pub struct ExcludedBannedValidators(
+                pub  ::std::vec::Vec<
+                    ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                >,
+            );
+            impl ::subxt::events::StaticEvent for ExcludedBannedValidators {
+                const PALLET: &'static str = "Elections";
+                const EVENT: &'static str = "ExcludedBannedValidators";
+            }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            #[doc = "Committee for the next era was put in place before the end of the current era"]
+            pub struct CommitteeRotationForced(
+                pub  ::std::vec::Vec<
+                    ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                >,
+                pub  ::std::vec::Vec<
+                    ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                >,
+                pub runtime_types::primitives::CommitteeSeats,
+            );
+            impl ::subxt::events::StaticEvent for CommitteeRotationForced {
+                const PALLET: &'static str = "Elections";
+                const EVENT: &'static str = "CommitteeRotationForced";
+            }
         }
         pub mod storage {
             use super::runtime_types;
@@ -18345,6 +18516,7 @@ pub mod api {
                         ::core::option::Option<::core::primitive::u32>,
                     pub clean_session_counter_delay: ::core::option::Option<::core::primitive::u32>,
                     pub ban_period: ::core::option::Option<::core::primitive::u32>,
+                    pub grace_sessions: ::core::option::Option<::core::primitive::u32>,
                 }
                 impl ::subxt::blocks::StaticExtrinsic for SetBanConfig {
                     const PALLET: &'static str = "CommitteeManagement";
@@ -18391,6 +18563,28 @@ pub mod api {
                     const PALLET: &'static str = "CommitteeManagement";
                     const CALL: &'static str = "cancel_ban";
                 }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct CancelBanBatch {
+                    pub banned: ::std::vec::Vec<
+                        ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    >,
+                }
+                impl ::subxt::blocks::StaticExtrinsic for CancelBanBatch {
+                    const PALLET: &'static str = "CommitteeManagement";
+                    const CALL: &'static str = "cancel_ban_batch";
+                }
                 #[derive(
                     :: subxt :: ext :: codec :: CompactAs,
                     :: subxt :: ext :: codec :: Decode,
@@ -18448,6 +18642,7 @@ pub mod api {
                     >,
                     clean_session_counter_delay: ::core::option::Option<::core::primitive::u32>,
                     ban_period: ::core::option::Option<::core::primitive::u32>,
+                    grace_sessions: ::core::option::Option<::core::primitive::u32>,
                 ) -> ::subxt::tx::Payload<types::SetBanConfig> {
                     ::subxt::tx::Payload::new_static(
                         "CommitteeManagement",
@@ -18457,6 +18652,7 @@ pub mod api {
                             underperformed_session_count_threshold,
                             clean_session_counter_delay,
                             ban_period,
+                            grace_sessions,
                         },
                         [
                             229u8, 110u8, 5u8, 251u8, 46u8, 236u8, 211u8, 8u8, 214u8, 252u8, 141u8,
@@ -18609,27 +18805,6 @@ pub mod api {
                 const PALLET: &'static str = "CommitteeManagement";
                 const EVENT: &'static str = "BanValidators";
             }
-            #[derive(
-                :: subxt :: ext :: codec :: Decode,
-                :: subxt :: ext :: codec :: Encode,
-                :: subxt :: ext :: scale_decode :: DecodeAsType,
-                :: subxt :: ext :: scale_encode :: EncodeAsType,
-                Clone,
-                Debug,
-                Eq,
-                PartialEq,
-            )]
-            # [codec (crate = :: subxt :: ext :: codec)]
-            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
-            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
-            #[doc = "Validator is underperforimg in finality committee"]
-            pub struct ValidatorUnderperforming(
-                pub ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
-            );
-            impl ::subxt::events::StaticEvent for ValidatorUnderperforming {
-                const PALLET: &'static str = "CommitteeManagement";
-                const EVENT: &'static str = "ValidatorUnderperforming";
-            }
         }
         pub mod storage {
             use super::runtime_types;
@@ -20774,6 +20949,26 @@ pub mod api {
                     const PALLET: &'static str = "Operations";
                     const CALL: &'static str = "fix_accounts_consumers_counter";
                 }
+                #[derive(
+                    :: subxt :: ext :: codec :: Decode,
+                    :: subxt :: ext :: codec :: Encode,
+                    :: subxt :: ext :: scale_decode :: DecodeAsType,
+                    :: subxt :: ext :: scale_encode :: EncodeAsType,
+                    Clone,
+                    Debug,
+                    Eq,
+                    PartialEq,
+                )]
+                # [codec (crate = :: subxt :: ext :: codec)]
+                #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+                #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+                pub struct FixAccountsProvidersCounter {
+                    pub who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                }
+                impl ::subxt::blocks::StaticExtrinsic for FixAccountsProvidersCounter {
+                    const PALLET: &'static str = "Operations";
+                    const CALL: &'static str = "fix_accounts_providers_counter";
+                }
             }
             pub struct TransactionApi;
             impl TransactionApi {
@@ -20842,6 +21037,48 @@ pub mod api {
                 const PALLET: &'static str = "Operations";
                 const EVENT: &'static str = "ConsumersCounterDecremented";
             }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            #[doc = "A providers counter was incremented for an account"]
+            pub struct ProvidersCounterIncremented {
+                pub who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+            }
+            impl ::subxt::events::StaticEvent for ProvidersCounterIncremented {
+                const PALLET: &'static str = "Operations";
+                const EVENT: &'static str = "ProvidersCounterIncremented";
+            }
+            #[derive(
+                :: subxt :: ext :: codec :: Decode,
+                :: subxt :: ext :: codec :: Encode,
+                :: subxt :: ext :: scale_decode :: DecodeAsType,
+                :: subxt :: ext :: scale_encode :: EncodeAsType,
+                Clone,
+                Debug,
+                Eq,
+                PartialEq,
+            )]
+            # [codec (crate = :: subxt :: ext :: codec)]
+            #[decode_as_type(crate_path = ":: subxt :: ext :: scale_decode")]
+            #[encode_as_type(crate_path = ":: subxt :: ext :: scale_encode")]
+            #[doc = "A providers counter was decremented for an account"]
+            pub struct ProvidersCounterDecremented {
+                pub who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+            }
+            impl ::subxt::events::StaticEvent for ProvidersCounterDecremented {
+                const PALLET: &'static str = "Operations";
+                const EVENT: &'static str = "ProvidersCounterDecremented";
+            }
         }
     }
     pub mod runtime_types {
@@ -21837,6 +22074,9 @@ pub mod api {
                             runtime_types::primitives::app::Signature,
                         >,
                     },
+                    #[codec(index = 4)]
+                    #[doc = "See [`Pallet::cancel_scheduled_finality_version_change`]."]
+                    cancel_scheduled_finality_version_change,
                 }
                 #[derive(
                     :: subxt :: ext :: codec :: Decode,
@@ -21861,6 +22101,15 @@ pub mod api {
                     FinalityVersionChange(runtime_types::primitives::VersionChange),
                     #[codec(index = 3)]
                     InflationParametersChange(::core::primitive::u128, ::core::primitive::u64),
+                    #[codec(index = 4)]
+                    CancelFinalityVersionChange(runtime_types::primitives::VersionChange),
+                    #[codec(index = 5)]
+                    #[doc = "Finality committee of the `session` has been rotated in, and consists of `count`"]
+                    #[doc = "authorities."]
+                    AuthoritiesRotated {
+                        session: ::core::primitive::u32,
+                        count: ::core::primitive::u32,
+                    },
                 }
             }
         }
@@ -22279,6 +22528,7 @@ pub mod api {
                             ::core::option::Option<::core::primitive::u32>,
                         clean_session_counter_delay: ::core::option::Option<::core::primitive::u32>,
                         ban_period: ::core::option::Option<::core::primitive::u32>,
+                        grace_sessions: ::core::option::Option<::core::primitive::u32>,
                     },
                     #[codec(index = 2)]
                     #[doc = "See [`Pallet::ban_from_committee`]."]
@@ -22305,6 +22555,13 @@ pub mod api {
                             ::core::option::Option<::core::primitive::u32>,
                         ban_period: ::core::option::Option<::core::primitive::u32>,
                     },
+                    #[codec(index = 6)]
+                    #[doc = "See [`Pallet::cancel_ban_batch`]."]
+                    cancel_ban_batch {
+                        banned: ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                    },
                 }
                 #[derive(
                     :: subxt :: ext :: codec :: Decode,
@@ -22334,6 +22591,9 @@ pub mod api {
                     #[codec(index = 2)]
                     #[doc = "Lenient threshold not in [0-100] range"]
                     InvalidLenientThreshold,
+                    #[codec(index = 3)]
+                    #[doc = "Too many validators given at once, ie more than [`Config::MaxBanBatchSize`]"]
+                    BanBatchTooBig,
                 }
                 #[derive(
                     :: subxt :: ext :: codec :: Decode,
@@ -22364,11 +22624,6 @@ pub mod api {
                             runtime_types::primitives::BanInfo,
                         )>,
                     ),
-                    #[codec(index = 3)]
-                    #[doc = "Validator is underperforimg in finality committee"]
-                    ValidatorUnderperforming(
-                        ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
-                    ),
                 }
             }
             #[derive(
@@ -23304,6 +23559,23 @@ pub mod api {
                     set_elections_openness {
                         openness: runtime_types::primitives::ElectionOpenness,
                     },
+                    #[codec(index = 5)]
+                    #[doc = "See [`Pallet::add_to_allowlist`]."]
+                    add_to_allowlist {
+                        validators: ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                    },
+                    #[codec(index = 6)]
+                    #[doc = "See [`Pallet::remove_from_allowlist`]."]
+                    remove_from_allowlist {
+                        validators: ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                    },
+                    #[codec(index = 7)]
+                    #[doc = "See [`Pallet::force_rotate_committee`]."]
+                    force_rotate_committee,
                 }
                 #[derive(
                     :: subxt :: ext :: codec :: Decode,
@@ -23357,6 +23629,24 @@ pub mod api {
                         >,
                         runtime_types::primitives::CommitteeSeats,
                     ),
+                    #[codec(index = 1)]
+                    #[doc = "Banned validators that would be elected otherwise, but were excluded from the elections"]
+                    ExcludedBannedValidators(
+                        ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                    ),
+                    #[codec(index = 2)]
+                    #[doc = "Committee for the next era was put in place before the end of the current era"]
+                    CommitteeRotationForced(
+                        ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                        ::std::vec::Vec<
+                            ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                        >,
+                        runtime_types::primitives::CommitteeSeats,
+                    ),
                 }
             }
         }
@@ -25050,6 +25340,11 @@ pub mod api {
                     fix_accounts_consumers_counter {
                         who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
                     },
+                    #[codec(index = 2)]
+                    #[doc = "See [`Pallet::fix_accounts_providers_counter`]."]
+                    fix_accounts_providers_counter {
+                        who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    },
                 }
                 #[derive(
                     :: subxt :: ext :: codec :: Decode,
@@ -25076,6 +25371,16 @@ pub mod api {
                     ConsumersCounterDecremented {
                         who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
                     },
+                    #[codec(index = 2)]
+                    #[doc = "A providers counter was incremented for an account"]
+                    ProvidersCounterIncremented {
+                        who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    },
+                    #[codec(index = 3)]
+                    #[doc = "A providers counter was decremented for an account"]
+                    ProvidersCounterDecremented {
+                        who: ::subxt::utils::Static<::subxt::ext::sp_core::crypto::AccountId32>,
+                    },
                 }
             }
         }
@@ -27585,6 +27890,8 @@ pub mod api {
                         ::core::primitive::u8,
                     >,
                 ),
+                #[codec(index = 2)]
+                InsufficientFinalityParticipation(::core::primitive::u32),
             }
             #[derive(
                 :: subxt :: ext :: codec :: Decode,
@@ -27622,6 +27929,8 @@ pub mod api {
                 Permissioned,
                 #[codec(index = 1)]
                 Permissionless,
+                #[codec(index = 2)]
+                PermissionedWithAllowlist,
             }
             #[derive(
                 :: subxt :: ext :: codec :: Decode,
@@ -27676,6 +27985,7 @@ pub mod api {
                 pub underperformed_session_count_threshold: ::core::primitive::u32,
                 pub clean_session_counter_delay: ::core::primitive::u32,
                 pub ban_period: ::core::primitive::u32,
+                pub grace_sessions: ::core::primitive::u32,
             }
             #[derive(
                 :: subxt :: ext :: codec :: Decode,
@@ -0,0 +1,76 @@
+use anyhow::anyhow;
+
+use crate::{
+    connections::AsConnection,
+    pallets::{committee_management::CommitteeManagementApi, session::SessionApi},
+    utility::BlocksApi,
+    BlockNumber, SessionIndex,
+};
+
+/// Returns how many blocks, counting `block` itself, are left until the end of its session.
+/// * `block` - number of the block
+/// * `session_period` - number of blocks in a session, must be positive
+pub fn blocks_until_session_end(
+    block: BlockNumber,
+    session_period: u32,
+) -> anyhow::Result<BlockNumber> {
+    if session_period == 0 {
+        return Err(anyhow!("Session period must be positive"));
+    }
+    Ok(session_period - block % session_period)
+}
+
+/// Position of the best block with respect to session boundaries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionTiming {
+    /// Session of the best block.
+    pub session: SessionIndex,
+    /// Number of blocks left until the session ends, including the best block.
+    pub blocks_left: BlockNumber,
+    /// Number of blocks in a session.
+    pub session_period: u32,
+}
+
+/// Chain timing API.
+#[async_trait::async_trait]
+pub trait ChainTimingApi {
+    /// Returns the position of the best block with respect to session boundaries.
+    async fn session_timing(&self) -> anyhow::Result<SessionTiming>;
+}
+
+#[async_trait::async_trait]
+impl<C: AsConnection + Sync> ChainTimingApi for C {
+    async fn session_timing(&self) -> anyhow::Result<SessionTiming> {
+        let session_period = self.get_session_period().await?;
+        let best = self
+            .get_best_block()
+            .await?
+            .ok_or_else(|| anyhow!("Failed to obtain best block number"))?;
+        let at = self.get_block_hash(best).await?;
+        let session = self.get_session(at).await;
+
+        Ok(SessionTiming {
+            session,
+            blocks_left: blocks_until_session_end(best, session_period)?,
+            session_period,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blocks_until_session_end;
+
+    #[test]
+    fn counts_blocks_until_session_end() {
+        assert_eq!(blocks_until_session_end(0, 30).unwrap(), 30);
+        assert_eq!(blocks_until_session_end(29, 30).unwrap(), 1);
+        assert_eq!(blocks_until_session_end(30, 30).unwrap(), 30);
+        assert_eq!(blocks_until_session_end(45, 30).unwrap(), 15);
+    }
+
+    #[test]
+    fn rejects_empty_session_period() {
+        assert!(blocks_until_session_end(45, 0).is_err());
+    }
+}
@@ -1,15 +1,25 @@
-use std::{thread::sleep, time::Duration};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::sleep,
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use codec::Decode;
-use log::{debug, info};
+use log::{debug, info, warn};
 use primitives::Nonce;
 use serde::{Deserialize, Serialize};
 use subxt::{
     blocks::ExtrinsicEvents,
-    ext::sp_core::Bytes,
+    error::DispatchError,
+    ext::sp_core::{blake2_256, Bytes, H256},
     metadata::DecodeWithMetadata,
-    rpc::RpcParams,
+    rpc::{types::DryRunResult, RpcParams},
+    rpc_params,
     storage::{
         address::{Address, StaticStorageMapKey, Yes},
         StorageAddress,
@@ -18,8 +28,8 @@ use subxt::{
 };
 
 use crate::{
-    api, runtime_types::sp_weights::weight_v2::Weight, AccountId, AlephConfig, BlockHash, Call,
-    KeyPair, ParamsBuilder, SubxtClient, TxHash, TxStatus,
+    api, pallets::system::SystemApi, runtime_types::sp_weights::weight_v2::Weight, AccountId,
+    AlephConfig, BlockHash, Call, KeyPair, ParamsBuilder, SubxtClient, TxHash, TxStatus,
 };
 
 /// Capable of communicating with a live Aleph chain.
@@ -36,6 +46,14 @@ pub struct SignedConnection {
     pub connection: Connection,
     /// signing authority
     pub signer: KeyPair,
+    nonce_manager: Option<NonceManager>,
+}
+
+/// Hands out consecutive nonces of a single account, so that many transactions can be submitted
+/// back-to-back without querying the chain for each of them.
+#[derive(Clone, Default)]
+pub struct NonceManager {
+    next: Arc<Mutex<Option<Nonce>>>,
 }
 
 /// Specific connection that is signed by the sudo key.
@@ -44,6 +62,32 @@ pub struct RootConnection {
     connection: SignedConnection,
 }
 
+/// Connection to several RPC endpoints of the same chain.
+///
+/// Reads are spread over the healthy endpoints in a round-robin manner, both when the pool is used
+/// as a plain connection and with [`PooledConnection::read`], which additionally retries failed
+/// queries against the remaining healthy endpoints. Signed connections created
+/// from the pool stick to the first healthy endpoint, so that nonces of a signer stay consistent,
+/// and move to the next healthy one once it goes down.
+#[derive(Clone)]
+pub struct PooledConnection {
+    endpoints: Arc<Vec<PoolEndpoint>>,
+    next: Arc<AtomicUsize>,
+}
+
+/// Signed connection created from a [`PooledConnection`], see [`PooledConnection::signed`].
+#[derive(Clone)]
+pub struct PooledSignedConnection {
+    endpoints: Arc<Vec<PoolEndpoint>>,
+    connections: Vec<SignedConnection>,
+}
+
+struct PoolEndpoint {
+    address: String,
+    connection: Connection,
+    healthy: AtomicBool,
+}
+
 /// Castability to a plain connection.
 pub trait AsConnection {
     /// Allows cast to [`Connection`] reference
@@ -118,6 +162,22 @@ pub trait ConnectionApi: Sync {
 
     /// Same as [rpc_call] but used for rpc endpoint that does not return values.
     async fn rpc_call_no_return(&self, func_name: String, params: RpcParams) -> anyhow::Result<()>;
+
+    /// Returns a hash of the current runtime metadata of the chain. Tools can compare it with the hash
+    /// of the metadata they were generated from, to detect incompatible runtime changes early.
+    async fn metadata_hash(&self) -> anyhow::Result<H256>;
+
+    /// Checks whether a transaction would be successfully dispatched, without submitting it.
+    /// * `tx` - encoded transaction payload
+    /// * `signer` - a [`KeyPair`] the transaction would be signed with
+    /// # Returns
+    /// `Ok(Err(_))` with the decoded dispatch error if the call would fail, or error if the
+    /// transaction is invalid or the dry run could not be performed.
+    async fn dry_run<Call: TxPayload + Send + Sync>(
+        &self,
+        tx: &Call,
+        signer: &KeyPair,
+    ) -> anyhow::Result<Result<(), DispatchError>>;
 }
 
 /// Data regarding submitted transaction.
@@ -249,6 +309,12 @@ impl AsConnection for Connection {
     }
 }
 
+impl AsConnection for PooledConnection {
+    fn as_connection(&self) -> &Connection {
+        self.next_connection()
+    }
+}
+
 impl<S: AsSigned> AsConnection for S {
     fn as_connection(&self) -> &Connection {
         &self.as_signed().connection
@@ -267,6 +333,12 @@ impl AsSigned for RootConnection {
     }
 }
 
+impl AsSigned for PooledSignedConnection {
+    fn as_signed(&self) -> &SignedConnection {
+        &self.connections[first_healthy(&self.endpoints)]
+    }
+}
+
 #[async_trait::async_trait]
 impl<C: AsConnection + Sync> ConnectionApi for C {
     async fn get_storage_entry<T: DecodeWithMetadata + Sync, Defaultable: Sync, Iterable: Sync>(
@@ -322,6 +394,42 @@ impl<C: AsConnection + Sync> ConnectionApi for C {
 
         Ok(())
     }
+
+    async fn metadata_hash(&self) -> anyhow::Result<H256> {
+        let metadata: Bytes = self
+            .as_connection()
+            .as_client()
+            .rpc()
+            .request("state_getMetadata", rpc_params![])
+            .await?;
+
+        Ok(hash_metadata(&metadata))
+    }
+
+    async fn dry_run<Call: TxPayload + Send + Sync>(
+        &self,
+        tx: &Call,
+        signer: &KeyPair,
+    ) -> anyhow::Result<Result<(), DispatchError>> {
+        let signed = self
+            .as_connection()
+            .as_client()
+            .tx()
+            .create_signed(tx, &signer.inner, Default::default())
+            .await?;
+
+        match signed.dry_run(None).await? {
+            DryRunResult::Success => Ok(Ok(())),
+            DryRunResult::DispatchError(e) => Ok(Err(e)),
+            DryRunResult::TransactionValidityError => Err(anyhow!(
+                "Transaction is invalid and would not be included in a block"
+            )),
+        }
+    }
+}
+
+fn hash_metadata(metadata: &[u8]) -> H256 {
+    H256(blake2_256(metadata))
 }
 
 impl SubmittableExtrinsic {
@@ -474,10 +582,240 @@ impl SignedConnection {
     /// * `connection` - existing connection
     /// * `signer` - a [`KeyPair`] of signing account
     pub fn from_connection(connection: Connection, signer: KeyPair) -> Self {
-        Self { connection, signer }
+        Self {
+            connection,
+            signer,
+            nonce_manager: None,
+        }
+    }
+
+    /// Makes this connection (and all its clones) track the nonce of the signer, which is
+    /// required by [`SignedConnection::submit_with_auto_nonce`].
+    pub fn with_nonce_manager(mut self) -> Self {
+        self.nonce_manager = Some(NonceManager::default());
+        self
+    }
+
+    /// Send a transaction to a chain, using the next nonce handed out by the nonce manager of
+    /// this connection. In case of a failure, the nonce is resynchronized with the chain before
+    /// the next submission, so a nonce that was not consumed gets reused.
+    /// * `tx` - encoded transaction payload
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    /// # Returns
+    /// Block hash of block where transaction was put together with transaction hash, or error.
+    pub async fn submit_with_auto_nonce<Call: TxPayload + Send + Sync>(
+        &self,
+        tx: Call,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let manager = self
+            .nonce_manager
+            .as_ref()
+            .ok_or_else(|| anyhow!("This connection does not manage nonces"))?;
+
+        let nonce = match manager.reserve() {
+            Some(nonce) => nonce,
+            None => {
+                let nonce = self.account_next_nonce(self.account_id()).await?;
+                manager.sync_and_reserve(nonce)
+            }
+        };
+
+        let result = match self.sign_with_params(tx, Default::default(), nonce) {
+            Ok(signed) => signed.submit(status).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            debug!(target: "aleph-client", "submission with nonce {nonce} failed, resyncing nonce: {e:?}");
+            manager.invalidate();
+        }
+
+        result
+    }
+}
+
+impl NonceManager {
+    /// Returns the next nonce to use and advances the counter, or `None` if the manager does
+    /// not know the current nonce of the account.
+    pub fn reserve(&self) -> Option<Nonce> {
+        let mut next = self.next.lock().expect("nonce lock is not poisoned");
+        let nonce = (*next)?;
+        *next = Some(nonce + 1);
+        Some(nonce)
+    }
+
+    /// Sets the next nonce to the given value (usually read from chain) and reserves it.
+    pub fn sync_and_reserve(&self, nonce: Nonce) -> Nonce {
+        *self.next.lock().expect("nonce lock is not poisoned") = Some(nonce + 1);
+        nonce
+    }
+
+    /// Forgets the cached nonce, so that it is read from chain again before the next reservation.
+    pub fn invalidate(&self) {
+        *self.next.lock().expect("nonce lock is not poisoned") = None;
     }
 }
 
+impl PooledConnection {
+    /// Creates a pool of connections to the given endpoints. Endpoints that cannot be reached
+    /// are skipped, it fails only if none of them can be reached.
+    /// * `endpoints` - addresses in websocket format, e.g. `ws://127.0.0.1:9943`
+    pub async fn new(endpoints: Vec<String>) -> anyhow::Result<Self> {
+        let mut pool = Vec::with_capacity(endpoints.len());
+        for address in endpoints {
+            match SubxtClient::from_url(&address).await {
+                Ok(client) => pool.push(PoolEndpoint {
+                    address,
+                    connection: Connection { client },
+                    healthy: AtomicBool::new(true),
+                }),
+                Err(e) => {
+                    warn!(target: "aleph-client", "skipping unreachable endpoint {address}: {e:?}")
+                }
+            }
+        }
+
+        if pool.is_empty() {
+            return Err(anyhow!("None of the given endpoints could be reached"));
+        }
+
+        Ok(Self {
+            endpoints: Arc::new(pool),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Returns addresses of the endpoints that are currently considered healthy.
+    pub fn healthy_endpoints(&self) -> Vec<String> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.healthy.load(Ordering::Relaxed))
+            .map(|endpoint| endpoint.address.clone())
+            .collect()
+    }
+
+    /// Returns a connection to the next healthy endpoint, advancing the round-robin cursor. If no
+    /// endpoint is healthy, the first one is returned.
+    pub fn next_connection(&self) -> &Connection {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        &self.endpoints[next_healthy(&health(&self.endpoints), start)].connection
+    }
+
+    /// Runs a read query against the next healthy endpoint. If the query fails, the endpoint is
+    /// marked as unhealthy and the query is retried against the remaining healthy endpoints.
+    /// * `query` - a query to run, given a connection to a single endpoint
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let session = pool
+    ///     .read(|connection| async move { Ok(connection.get_session(None).await) })
+    ///     .await?;
+    /// ```
+    pub async fn read<'a, R, F, Fut>(&'a self, query: F) -> anyhow::Result<R>
+    where
+        F: Fn(&'a Connection) -> Fut,
+        Fut: Future<Output = anyhow::Result<R>>,
+    {
+        let mut last_error = anyhow!("There are no healthy endpoints in the pool");
+        for endpoint in self.healthy_in_rotation() {
+            match query(&endpoint.connection).await {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    warn!(target: "aleph-client", "read from {} failed, marking it as unhealthy: {e:?}", endpoint.address);
+                    endpoint.healthy.store(false, Ordering::Relaxed);
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Probes every endpoint of the pool and updates its health accordingly, so that endpoints
+    /// which came back are used again.
+    pub async fn refresh_health(&self) {
+        for endpoint in self.endpoints.iter() {
+            let healthy = endpoint
+                .connection
+                .as_client()
+                .rpc()
+                .system_health()
+                .await
+                .is_ok();
+            endpoint.healthy.store(healthy, Ordering::Relaxed);
+        }
+    }
+
+    /// Creates a signed connection using the first healthy endpoint of the pool. It keeps using
+    /// that endpoint as long as it is healthy, and fails over to the next healthy one otherwise.
+    /// * `signer` - a [`KeyPair`] of signing account
+    pub fn signed(&self, signer: KeyPair) -> PooledSignedConnection {
+        let connections = self
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                SignedConnection::from_connection(endpoint.connection.clone(), signer.clone())
+            })
+            .collect();
+
+        PooledSignedConnection {
+            endpoints: self.endpoints.clone(),
+            connections,
+        }
+    }
+
+    fn healthy_in_rotation(&self) -> Vec<&PoolEndpoint> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        rotated_healthy(&health(&self.endpoints), start)
+            .into_iter()
+            .map(|i| &self.endpoints[i])
+            .collect()
+    }
+}
+
+impl PooledSignedConnection {
+    /// Makes this connection (and all its clones) track the nonce of the signer, regardless of
+    /// the endpoint used, see [`SignedConnection::with_nonce_manager`].
+    pub fn with_nonce_manager(mut self) -> Self {
+        let manager = NonceManager::default();
+        for connection in self.connections.iter_mut() {
+            connection.nonce_manager = Some(manager.clone());
+        }
+        self
+    }
+}
+
+fn health(endpoints: &[PoolEndpoint]) -> Vec<bool> {
+    endpoints
+        .iter()
+        .map(|endpoint| endpoint.healthy.load(Ordering::Relaxed))
+        .collect()
+}
+
+/// Index of the first healthy endpoint, or of the first endpoint if none is healthy.
+fn first_healthy(endpoints: &[PoolEndpoint]) -> usize {
+    next_healthy(&health(endpoints), 0)
+}
+
+/// Index of the first healthy endpoint starting from the `start`-th one (modulo the number of
+/// endpoints), or of the first endpoint if none is healthy.
+fn next_healthy(health: &[bool], start: usize) -> usize {
+    rotated_healthy(health, start).first().copied().unwrap_or(0)
+}
+
+/// Indices of the healthy endpoints, in the order starting from the `start`-th one (modulo the
+/// number of endpoints).
+fn rotated_healthy(health: &[bool], start: usize) -> Vec<usize> {
+    let len = health.len();
+
+    (0..len)
+        .map(|i| (start + i) % len)
+        .filter(|&i| health[i])
+        .collect()
+}
+
 impl RootConnection {
     /// Creates new root connection from a given url.
     /// It tries to connect 10 times, waiting 1 second between each unsuccessful attempt.
@@ -519,7 +857,60 @@ impl RootConnection {
         }
 
         Ok(Self {
-            connection: SignedConnection { connection, signer },
+            connection: SignedConnection::from_connection(connection, signer),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{next_healthy, rotated_healthy, NonceManager};
+
+    #[test]
+    fn nonce_manager_hands_out_consecutive_nonces_after_sync() {
+        let manager = NonceManager::default();
+
+        assert_eq!(manager.reserve(), None);
+        assert_eq!(manager.sync_and_reserve(7), 7);
+        assert_eq!(manager.reserve(), Some(8));
+        assert_eq!(manager.clone().reserve(), Some(9));
+    }
+
+    #[test]
+    fn invalidated_nonce_manager_requires_resync() {
+        let manager = NonceManager::default();
+        manager.sync_and_reserve(7);
+
+        manager.invalidate();
+
+        assert_eq!(manager.reserve(), None);
+        assert_eq!(manager.sync_and_reserve(8), 8);
+        assert_eq!(manager.reserve(), Some(9));
+    }
+
+    #[test]
+    fn rotation_starts_from_cursor_and_skips_unhealthy_endpoints() {
+        let health = [true, false, true, true];
+
+        assert_eq!(rotated_healthy(&health, 0), vec![0, 2, 3]);
+        assert_eq!(rotated_healthy(&health, 1), vec![2, 3, 0]);
+        assert_eq!(rotated_healthy(&health, 7), vec![3, 0, 2]);
+    }
+
+    #[test]
+    fn next_endpoint_skips_failed_ones() {
+        let health = [true, false, true];
+
+        assert_eq!(next_healthy(&health, 0), 0);
+        assert_eq!(next_healthy(&health, 1), 2);
+        assert_eq!(next_healthy(&health, 2), 2);
+        assert_eq!(next_healthy(&health, 3), 0);
+        assert_eq!(next_healthy(&[false, false], 1), 0);
+    }
+
+    #[test]
+    fn rotation_is_empty_without_healthy_endpoints() {
+        assert!(rotated_healthy(&[false, false], 3).is_empty());
+        assert!(rotated_healthy(&[], 0).is_empty());
+    }
+}
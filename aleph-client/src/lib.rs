@@ -36,8 +36,12 @@ use crate::api::runtime_types::aleph_runtime::RuntimeCall as Call;
 #[doc(hidden)]
 mod aleph_zero;
 
+/// Timing of actions with respect to session boundaries.
+pub mod chain_timing;
 mod connections;
 pub mod contract;
+/// Sending transactions at a given rate, e.g. for load tests.
+pub mod load;
 /// API for pallets.
 pub mod pallets;
 mod runtime_types;
@@ -66,8 +70,9 @@ pub type TxHash = H256;
 pub type SubxtClient = OnlineClient<AlephConfig>;
 
 pub use connections::{
-    AsConnection, AsSigned, Connection, ConnectionApi, RootConnection, SignedConnection,
-    SignedConnectionApi, SignedConnectionApiExt, SubmittableExtrinsic, SudoCall, TxInfo,
+    AsConnection, AsSigned, Connection, ConnectionApi, NonceManager, PooledConnection,
+    PooledSignedConnection, RootConnection, SignedConnection, SignedConnectionApi,
+    SignedConnectionApiExt, SubmittableExtrinsic, SudoCall, TxInfo,
 };
 
 /// An alias for a configuration of live chain, e.g. block index type, hash type.
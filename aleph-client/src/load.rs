@@ -0,0 +1,168 @@
+use core::cmp::min;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use futures::future::join_all;
+use log::{debug, info};
+use primitives::Nonce;
+use subxt::{config::substrate::Era, tx::TxPayload};
+use tokio::time::interval;
+
+use crate::{
+    pallets::{author::AuthorRpc, system::SystemApi},
+    utility::BlocksApi,
+    ParamsBuilder, SignedConnection, SignedConnectionApi, SignedConnectionApiExt, TxStatus,
+};
+
+/// How many transactions should be sent and when.
+#[derive(Clone, Copy, Debug)]
+pub struct Schedule {
+    /// Number of intervals to send transactions in.
+    pub intervals: u64,
+    /// Length of a single interval.
+    pub interval_duration: Duration,
+    /// Number of transactions to send in each interval, in total over all connections.
+    pub transactions_in_interval: u64,
+}
+
+/// Sends transactions at a given rate, splitting them evenly over many connections.
+pub struct RateScheduler {
+    status: TxStatus,
+}
+
+impl RateScheduler {
+    /// Creates a new scheduler.
+    /// * `status` - a [`TxStatus`] to wait for after submitting each transaction
+    pub fn new(status: TxStatus) -> Self {
+        Self { status }
+    }
+
+    /// Sends transactions produced by `call_factory` according to `schedule`. In each interval it
+    /// never sends more transactions than needed to fill the transaction pool up to `pool_limit`,
+    /// and the transactions that did not fit are sent in the following intervals.
+    /// * `connections` - connections to send transactions from
+    /// * `call_factory` - produces a transaction to send, given the number of transactions that were
+    ///   already sent from the same connection
+    /// * `schedule` - how many transactions should be sent and when
+    /// * `pool_limit` - maximal number of pending transactions in the pool
+    /// # Returns
+    /// Connections together with their next unused nonce, or the last error of any connection.
+    /// Fails immediately if no connections are given.
+    pub async fn run<Call, F>(
+        &self,
+        connections: Vec<SignedConnection>,
+        call_factory: F,
+        schedule: Schedule,
+        pool_limit: u64,
+    ) -> anyhow::Result<Vec<(SignedConnection, Nonce)>>
+    where
+        Call: TxPayload + Send + Sync + 'static,
+        F: Fn(u64) -> Call + Clone + Send + 'static,
+    {
+        let first_connection = connections
+            .first()
+            .ok_or_else(|| anyhow!("At least one connection is needed to send transactions"))?;
+        let start = Instant::now();
+        let total_duration = schedule.interval_duration * (schedule.intervals as u32);
+
+        let start_finalized_hash = first_connection.get_finalized_block_hash().await?;
+        let start_finalized_number = first_connection
+            .get_block_number(start_finalized_hash)
+            .await?
+            .ok_or_else(|| anyhow!("Finalized block should have a number"))?
+            as u64;
+
+        // Set mortality roughly to flooding length
+        let params = ParamsBuilder::default().era(
+            Era::mortal(total_duration.as_secs() + 30, start_finalized_number),
+            start_finalized_hash,
+        );
+
+        let n_connections = connections.len();
+        let mut start_nonces = vec![0; n_connections];
+        for (conn_id, conn) in connections.iter().enumerate() {
+            start_nonces[conn_id] = conn.account_nonce(conn.account_id()).await?;
+        }
+
+        let split_per_connections = move |total, conn_id| {
+            let mut part = total / n_connections as u64;
+            if conn_id < (total as usize) % n_connections {
+                part += 1;
+            }
+            part
+        };
+
+        let status = self.status;
+        let senders = connections
+            .into_iter()
+            .enumerate()
+            .map(|(conn_id, conn)| {
+                let start_nonce = start_nonces[conn_id];
+                let mut nonce = start_nonce;
+                let call_factory = call_factory.clone();
+                tokio::spawn(async move {
+                    let mut interval = interval(schedule.interval_duration);
+                    let mut overdue_transactions = 0;
+                    for i in 0..schedule.intervals {
+                        interval.tick().await;
+                        overdue_transactions +=
+                            split_per_connections(schedule.transactions_in_interval, conn_id);
+
+                        let pending_in_pool = conn.pending_extrinsics_len().await?;
+                        let transactions_to_pool_limit = pool_limit.saturating_sub(pending_in_pool);
+                        let my_limit_part =
+                            split_per_connections(transactions_to_pool_limit, conn_id);
+
+                        let my_transactions = min(overdue_transactions, my_limit_part);
+                        overdue_transactions -= my_transactions;
+                        debug!(
+                            target: "aleph-client",
+                            "Interval {}, sending {my_transactions} transaction from connection {conn_id}. \
+                             In the pool, there are pending {pending_in_pool} transactions. \
+                             Overdue transactions: {overdue_transactions}.",
+                            i + 1
+                        );
+
+                        for _ in 0..my_transactions {
+                            conn.sign_with_params(call_factory((nonce - start_nonce).into()), params, nonce)?
+                                .submit(status)
+                                .await?;
+                            nonce += 1;
+                            if Instant::now().saturating_duration_since(start) > total_duration {
+                                return anyhow::Ok((conn, nonce));
+                            }
+                        }
+                    }
+                    anyhow::Ok((conn, nonce))
+                })
+            });
+
+        let mut total_submitted = 0;
+        let mut last_error = None;
+        let mut res = vec![];
+        for (conn_id, result) in join_all(senders).await.into_iter().enumerate() {
+            match result? {
+                Ok((conn, nonce)) => {
+                    total_submitted += nonce - start_nonces[conn_id];
+                    res.push((conn, nonce));
+                }
+                Err(e) => {
+                    info!(target: "aleph-client", "Sender subtask finished with an error: {e:?}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let target_transactions = schedule.intervals * schedule.transactions_in_interval;
+        info!(
+            target: "aleph-client",
+            "Submitted {total_submitted} txns out of {target_transactions} that should be sent ({:.2}%)",
+            total_submitted as f64 / target_transactions as f64 * 100.0
+        );
+
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(res),
+        }
+    }
+}
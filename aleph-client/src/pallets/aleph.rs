@@ -1,4 +1,5 @@
-use codec::Encode;
+use codec::{Decode, Encode};
+use primitives::{crypto::SignatureSet, AuthoritySignature, Perbill, Score, VersionChange};
 use subxt::rpc_params;
 
 use crate::{
@@ -8,11 +9,14 @@ use crate::{
         sp_core::ed25519::Public as EdPublic,
     },
     connections::TxInfo,
-    pallet_aleph::pallet::Call::schedule_finality_version_change,
+    pallet_aleph::pallet::Call::{
+        cancel_scheduled_finality_version_change, schedule_finality_version_change,
+    },
     sp_core::Bytes,
-    AccountId, AlephKeyPair, BlockHash, BlockNumber,
+    AccountId, AlephKeyPair, AsConnection, Balance, BlockHash, BlockNumber,
     Call::Aleph,
-    ConnectionApi, Pair, RootConnection, SessionIndex, SudoCall, TxStatus, Version,
+    ConnectionApi, Pair, RootConnection, SessionIndex, SubmittableExtrinsic, SudoCall, TxStatus,
+    Version,
 };
 
 // TODO replace docs with link to pallet aleph docs, once they are published
@@ -23,8 +27,21 @@ pub trait AlephApi {
     async fn finality_version(&self, at: Option<BlockHash>) -> Version;
     /// Gets the finality version for the next session.
     async fn next_session_finality_version(&self, at: Option<BlockHash>) -> Version;
+    /// Gets the scheduled finality version change, if it has not taken effect yet.
+    async fn scheduled_finality_version_change(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Option<VersionChange>;
     /// Gets the emergency finalizer
     async fn emergency_finalizer(&self, at: Option<BlockHash>) -> Option<[u8; 32]>;
+    /// Gets the payout of the current era, as a pair of validators' payout and the rest that
+    /// goes to the treasury.
+    /// * `at` - optional hash of a block to query state from
+    async fn current_era_payout(&self, at: Option<BlockHash>)
+        -> anyhow::Result<(Balance, Balance)>;
+    /// Gets the yearly inflation.
+    /// * `at` - optional hash of a block to query state from
+    async fn yearly_inflation(&self, at: Option<BlockHash>) -> anyhow::Result<Perbill>;
 }
 
 /// Pallet aleph API that requires sudo.
@@ -53,6 +70,32 @@ pub trait AlephSudoApi {
         session: SessionIndex,
         status: TxStatus,
     ) -> anyhow::Result<TxInfo>;
+
+    /// Cancels the scheduled finality version change.
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    /// # Returns
+    /// Block hash of block where transaction was put or error
+    async fn cancel_scheduled_finality_version_change(
+        &self,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+/// Pallet aleph API for unsigned transactions.
+#[async_trait::async_trait]
+pub trait AlephUnsignedApi {
+    /// Submits a score of the finality committee performance in a session.
+    /// * `score` - a score for a nonce in a session
+    /// * `signature` - signatures of the finality committee members under the score
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    /// # Returns
+    /// Block hash of block where transaction was put or error
+    async fn submit_abft_score(
+        &self,
+        score: Score,
+        signature: SignatureSet<AuthoritySignature>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
 }
 
 /// Pallet aleph RPC api.
@@ -70,7 +113,7 @@ pub trait AlephRpc {
 }
 
 #[async_trait::async_trait]
-impl<C: ConnectionApi> AlephApi for C {
+impl<C: ConnectionApi + AsConnection> AlephApi for C {
     async fn finality_version(&self, at: Option<BlockHash>) -> Version {
         let addrs = api::storage().aleph().finality_version();
 
@@ -85,6 +128,17 @@ impl<C: ConnectionApi> AlephApi for C {
         self.rpc_call(method.to_string(), params).await.unwrap()
     }
 
+    async fn scheduled_finality_version_change(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Option<VersionChange> {
+        let method = "state_call";
+        let api_method = "AlephSessionApi_scheduled_finality_version_change";
+        let params = rpc_params![api_method, "0x", at];
+
+        self.rpc_call(method.to_string(), params).await.unwrap()
+    }
+
     async fn emergency_finalizer(&self, at: Option<BlockHash>) -> Option<[u8; 32]> {
         let addrs = api::storage().aleph().emergency_finalizer();
 
@@ -92,6 +146,33 @@ impl<C: ConnectionApi> AlephApi for C {
             .await
             .map(|public| public.0 .0)
     }
+
+    async fn current_era_payout(
+        &self,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<(Balance, Balance)> {
+        let call = api::apis().aleph_session_api().current_era_payout();
+
+        let runtime_api = self.as_connection().as_client().runtime_api();
+        let runtime_api = match at {
+            Some(block_hash) => runtime_api.at(block_hash),
+            None => runtime_api.at_latest().await?,
+        };
+
+        Ok(runtime_api.call(call).await?)
+    }
+
+    async fn yearly_inflation(&self, at: Option<BlockHash>) -> anyhow::Result<Perbill> {
+        let call = api::apis().aleph_session_api().yearly_inflation();
+
+        let runtime_api = self.as_connection().as_client().runtime_api();
+        let runtime_api = match at {
+            Some(block_hash) => runtime_api.at(block_hash),
+            None => runtime_api.at_latest().await?,
+        };
+
+        Ok(Perbill::from_parts(runtime_api.call(call).await?.0))
+    }
 }
 
 #[async_trait::async_trait]
@@ -120,6 +201,38 @@ impl AlephSudoApi for RootConnection {
 
         self.sudo_unchecked(call, status).await
     }
+
+    async fn cancel_scheduled_finality_version_change(
+        &self,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = Aleph(cancel_scheduled_finality_version_change);
+
+        self.sudo_unchecked(call, status).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: AsConnection + Sync> AlephUnsignedApi for C {
+    async fn submit_abft_score(
+        &self,
+        score: Score,
+        signature: SignatureSet<AuthoritySignature>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().aleph().unsigned_submit_abft_score(
+            Decode::decode(&mut &score.encode()[..])?,
+            Decode::decode(&mut &signature.encode()[..])?,
+        );
+        let unsigned: SubmittableExtrinsic = self
+            .as_connection()
+            .as_client()
+            .tx()
+            .create_unsigned(&tx)?
+            .into();
+
+        unsigned.submit(status).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -11,6 +11,11 @@ pub trait AuthorRpc {
     /// Returns the number of extrinsics pending in RPC node's transaction pool.
     /// See [`pending_extrinsics`](https://paritytech.github.io/substrate/master/sc_rpc/author/struct.Author.html#method.pending_extrinsics).
     async fn pending_extrinsics_len(&self) -> anyhow::Result<u64>;
+    /// Checks whether RPC node's keystore contains a private key for the given public key.
+    /// See [`has_key`](https://paritytech.github.io/substrate/master/sc_rpc/author/struct.Author.html#method.has_key).
+    /// * `public_key` - raw public key
+    /// * `key_type` - key type identifier, e.g. `aura`
+    async fn author_has_key(&self, public_key: Vec<u8>, key_type: &str) -> anyhow::Result<bool>;
 }
 
 #[async_trait::async_trait]
@@ -30,4 +35,16 @@ impl<C: AsConnection + Sync> AuthorRpc for C {
             .len()
             .try_into()?)
     }
+
+    async fn author_has_key(&self, public_key: Vec<u8>, key_type: &str) -> anyhow::Result<bool> {
+        Ok(self
+            .as_connection()
+            .as_client()
+            .rpc()
+            .request::<bool>(
+                "author_hasKey",
+                rpc_params![Bytes(public_key), key_type.to_string()],
+            )
+            .await?)
+    }
 }
@@ -1,7 +1,13 @@
 use codec::{DecodeAll, Encode};
-use primitives::{SessionCommittee, SessionValidatorError};
+use primitives::{
+    BlockCount, SessionCommittee, SessionRangeError, SessionValidatorError,
+    VersionedSessionCommittee,
+};
 use subxt::{
-    ext::{sp_core::Bytes, sp_runtime::Perquintill},
+    ext::{
+        sp_core::{hashing::blake2_64, Bytes},
+        sp_runtime::Perquintill,
+    },
     rpc_params,
     utils::Static,
 };
@@ -10,9 +16,9 @@ use crate::{
     aleph_runtime::RuntimeCall::CommitteeManagement,
     api,
     pallet_committee_management::pallet::Call::{
-        ban_from_committee, set_ban_config, set_lenient_threshold,
+        ban_from_committee, cancel_ban_batch, set_ban_config, set_lenient_threshold,
     },
-    primitives::{ProductionBanConfig, BanInfo, BanReason},
+    primitives::{BanInfo, BanReason, ProductionBanConfig},
     AccountId, AsConnection, BlockHash, ConnectionApi, EraIndex, RootConnection, SessionCount,
     SessionIndex, SudoCall, TxInfo, TxStatus,
 };
@@ -72,8 +78,40 @@ pub trait CommitteeManagementApi {
         at: Option<BlockHash>,
     ) -> anyhow::Result<Result<SessionCommittee<AccountId>, SessionValidatorError>>;
 
+    /// Returns committees for all sessions from `from` to `to` (inclusive) in a single call. The
+    /// whole range has to satisfy the same conditions as in `get_session_committee`.
+    /// * `from` - first session of the range
+    /// * `to` - last session of the range
+    /// * `at` - optional hash of a block to query state from
+    async fn get_session_committee_range(
+        &self,
+        from: SessionIndex,
+        to: SessionIndex,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Result<Vec<(SessionIndex, SessionCommittee<AccountId>)>, SessionRangeError>>;
+
     /// Returns `committee-management.lenient_threshold` for the current era.
     async fn get_lenient_threshold_percentage(&self, at: Option<BlockHash>) -> Option<Perquintill>;
+
+    /// Returns the number of sessions in which a given validator underperformed as a block
+    /// producer, as reported by the runtime API.
+    /// * `validator` - a validator stash account id
+    /// * `at` - optional hash of a block to query state from
+    async fn get_underperformance(
+        &self,
+        validator: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<SessionCount>;
+
+    /// Returns the number of blocks produced by a given validator in the current session, as
+    /// reported by the runtime API.
+    /// * `validator` - a validator stash account id
+    /// * `at` - optional hash of a block to query state from
+    async fn get_blocks_produced_this_session(
+        &self,
+        validator: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<BlockCount>;
 }
 
 /// any object that implements pallet committee-management api that requires sudo
@@ -84,6 +122,7 @@ pub trait CommitteeManagementSudoApi {
     /// * `underperformed_session_count_threshold` - how many bad uptime sessions force validator to be removed from the committee
     /// * `clean_session_counter_delay` - underperformed session counter is cleared every subsequent `clean_session_counter_delay` sessions
     /// * `ban_period` - how many eras a validator is banned for
+    /// * `grace_sessions` - for how many sessions after first appearing in the committee a validator is not marked as underperforming
     /// * `status` - a [`TxStatus`] for a tx to wait for
     async fn set_ban_config(
         &self,
@@ -91,6 +130,7 @@ pub trait CommitteeManagementSudoApi {
         underperformed_session_count_threshold: Option<u32>,
         clean_session_counter_delay: Option<u32>,
         ban_period: Option<EraIndex>,
+        grace_sessions: Option<SessionCount>,
         status: TxStatus,
     ) -> anyhow::Result<TxInfo>;
 
@@ -105,6 +145,15 @@ pub trait CommitteeManagementSudoApi {
         status: TxStatus,
     ) -> anyhow::Result<TxInfo>;
 
+    /// Cancels the bans of multiple nodes at once. Nodes that are not banned are skipped.
+    /// * `accounts` - accounts to be unbanned, at most `MaxBanBatchSize` of them
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn cancel_ban_batch(
+        &self,
+        accounts: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
     /// Set lenient threshold. Effective from the next era.
     async fn set_lenient_threshold(
         &self,
@@ -116,7 +165,9 @@ pub trait CommitteeManagementSudoApi {
 #[async_trait::async_trait]
 impl<C: ConnectionApi + AsConnection> CommitteeManagementApi for C {
     async fn get_ban_config(&self, at: Option<BlockHash>) -> ProductionBanConfig {
-        let addrs = api::storage().committee_management().production_ban_config();
+        let addrs = api::storage()
+            .committee_management()
+            .production_ban_config();
 
         self.get_storage_entry(&addrs, at).await
     }
@@ -186,12 +237,51 @@ impl<C: ConnectionApi + AsConnection> CommitteeManagementApi for C {
         at: Option<BlockHash>,
     ) -> anyhow::Result<Result<SessionCommittee<AccountId>, SessionValidatorError>> {
         let method = "state_call";
+        if has_versioned_committee_api(self, at).await? {
+            let api_method = "AlephSessionApi_predict_session_committee_versioned";
+            let params = rpc_params![api_method, Bytes(session.encode()), at];
+            let committee: Result<VersionedSessionCommittee<AccountId>, SessionValidatorError> =
+                self.rpc_call(method.to_string(), params).await?;
+            return Ok(committee.map(VersionedSessionCommittee::into_committee));
+        }
+
+        // Runtimes without the versioned api return the bare committee.
         let api_method = "AlephSessionApi_predict_session_committee";
         let params = rpc_params![api_method, Bytes(session.encode()), at];
 
         self.rpc_call(method.to_string(), params).await
     }
 
+    async fn get_session_committee_range(
+        &self,
+        from: SessionIndex,
+        to: SessionIndex,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Result<Vec<(SessionIndex, SessionCommittee<AccountId>)>, SessionRangeError>>
+    {
+        let method = "state_call";
+        if has_versioned_committee_api(self, at).await? {
+            let api_method = "AlephSessionApi_predict_session_committee_range_versioned";
+            let params = rpc_params![api_method, Bytes((from, to).encode()), at];
+            let committees: Result<
+                Vec<(SessionIndex, VersionedSessionCommittee<AccountId>)>,
+                SessionRangeError,
+            > = self.rpc_call(method.to_string(), params).await?;
+            return Ok(committees.map(|committees| {
+                committees
+                    .into_iter()
+                    .map(|(session, committee)| (session, committee.into_committee()))
+                    .collect()
+            }));
+        }
+
+        // Runtimes without the versioned api return the bare committees.
+        let api_method = "AlephSessionApi_predict_session_committee_range";
+        let params = rpc_params![api_method, Bytes((from, to).encode()), at];
+
+        self.rpc_call(method.to_string(), params).await
+    }
+
     async fn get_lenient_threshold_percentage(&self, at: Option<BlockHash>) -> Option<Perquintill> {
         let addrs = api::storage().committee_management().lenient_threshold();
 
@@ -199,6 +289,60 @@ impl<C: ConnectionApi + AsConnection> CommitteeManagementApi for C {
             .await
             .map(|lt| Perquintill::decode_all(&mut &*lt.encode()).unwrap())
     }
+
+    async fn get_underperformance(
+        &self,
+        validator: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<SessionCount> {
+        let method = "state_call";
+        let api_method = "CommitteeManagementApi_underperformance";
+        let params = rpc_params![api_method, Bytes(validator.encode()), at];
+
+        self.rpc_call(method.to_string(), params).await
+    }
+
+    async fn get_blocks_produced_this_session(
+        &self,
+        validator: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<BlockCount> {
+        let method = "state_call";
+        let api_method = "CommitteeManagementApi_blocks_produced_this_session";
+        let params = rpc_params![api_method, Bytes(validator.encode()), at];
+
+        self.rpc_call(method.to_string(), params).await
+    }
+}
+
+/// Version of `AlephSessionApi` from which the committee predictions are also available prefixed
+/// with their version.
+const VERSIONED_COMMITTEE_API_VERSION: u64 = 4;
+
+/// Checks whether the runtime at `at` exposes the versioned committee predictions. Older runtimes
+/// only expose the bare committees.
+async fn has_versioned_committee_api<C: AsConnection + Sync>(
+    connection: &C,
+    at: Option<BlockHash>,
+) -> anyhow::Result<bool> {
+    let runtime_version = connection
+        .as_connection()
+        .as_client()
+        .rpc()
+        .runtime_version(at)
+        .await?;
+    let api_id = hex::encode(blake2_64(b"AlephSessionApi"));
+    let version = runtime_version
+        .other
+        .get("apis")
+        .and_then(|apis| apis.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|api| Some((api.get(0)?.as_str()?, api.get(1)?.as_u64()?)))
+        .find(|(id, _)| id.trim_start_matches("0x") == api_id)
+        .map(|(_, version)| version);
+
+    Ok(version.map_or(false, |version| version >= VERSIONED_COMMITTEE_API_VERSION))
 }
 
 #[async_trait::async_trait]
@@ -209,6 +353,7 @@ impl CommitteeManagementSudoApi for RootConnection {
         underperformed_session_count_threshold: Option<u32>,
         clean_session_counter_delay: Option<u32>,
         ban_period: Option<EraIndex>,
+        grace_sessions: Option<SessionCount>,
         status: TxStatus,
     ) -> anyhow::Result<TxInfo> {
         let call = CommitteeManagement(set_ban_config {
@@ -216,6 +361,7 @@ impl CommitteeManagementSudoApi for RootConnection {
             underperformed_session_count_threshold,
             clean_session_counter_delay,
             ban_period,
+            grace_sessions,
         });
 
         self.sudo_unchecked(call, status).await
@@ -234,6 +380,17 @@ impl CommitteeManagementSudoApi for RootConnection {
         self.sudo_unchecked(call, status).await
     }
 
+    async fn cancel_ban_batch(
+        &self,
+        accounts: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = CommitteeManagement(cancel_ban_batch {
+            banned: accounts.into_iter().map(Into::into).collect(),
+        });
+        self.sudo_unchecked(call, status).await
+    }
+
     async fn set_lenient_threshold(
         &self,
         threshold_percent: u8,
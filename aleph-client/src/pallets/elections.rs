@@ -4,13 +4,25 @@ use crate::{
     api,
     api::runtime_types::primitives::{CommitteeSeats, EraValidators},
     connections::{AsConnection, TxInfo},
-    pallet_elections::pallet::Call::{change_validators, set_elections_openness},
+    pallet_elections::pallet::Call::{
+        add_to_allowlist, change_validators, force_rotate_committee, remove_from_allowlist,
+        set_elections_openness,
+    },
     primitives::ElectionOpenness,
     AccountId, BlockHash,
     Call::Elections,
     ConnectionApi, RootConnection, SudoCall, TxStatus,
 };
 
+impl<A> From<EraValidators<A>> for primitives::EraValidators<A> {
+    fn from(era_validators: EraValidators<A>) -> Self {
+        primitives::EraValidators {
+            reserved: era_validators.reserved,
+            non_reserved: era_validators.non_reserved,
+        }
+    }
+}
+
 // TODO once pallet elections docs are published, replace api docs with links to public docs
 /// Pallet elections read-only api.
 #[async_trait::async_trait]
@@ -23,6 +35,10 @@ pub trait ElectionsApi {
     /// * `at` - optional hash of a block to query state from
     async fn get_next_era_committee_seats(&self, at: Option<BlockHash>) -> CommitteeSeats;
 
+    /// Returns `elections.openness` storage of the elections pallet.
+    /// * `at` - optional hash of a block to query state from
+    async fn get_election_openness(&self, at: Option<BlockHash>) -> ElectionOpenness;
+
     /// Returns `elections.current_era_validators` storage of the elections pallet.
     /// * `at` - optional hash of a block to query state from
     async fn get_current_era_validators(&self, at: Option<BlockHash>) -> EraValidators<AccountId>;
@@ -60,6 +76,30 @@ pub trait ElectionsSudoApi {
         mode: ElectionOpenness,
         status: TxStatus,
     ) -> anyhow::Result<TxInfo>;
+
+    /// Issues `elections.add_to_allowlist` that lets the given validators be elected in
+    /// `PermissionedWithAllowlist` elections openness.
+    /// * `validators` - validators to add to the allowlist
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn add_to_allowlist(
+        &self,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// Issues `elections.remove_from_allowlist`.
+    /// * `validators` - validators to remove from the allowlist
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn remove_from_allowlist(
+        &self,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// Issues `elections.force_rotate_committee` that puts the committee configured for the next
+    /// era in place without waiting for the era to end.
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn force_rotate_committee(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
 }
 
 #[async_trait::async_trait]
@@ -76,6 +116,12 @@ impl<C: ConnectionApi + AsConnection> ElectionsApi for C {
         self.get_storage_entry(&addrs, at).await
     }
 
+    async fn get_election_openness(&self, at: Option<BlockHash>) -> ElectionOpenness {
+        let addrs = api::storage().elections().openness();
+
+        self.get_storage_entry(&addrs, at).await
+    }
+
     async fn get_current_era_validators(&self, at: Option<BlockHash>) -> EraValidators<AccountId> {
         let addrs = api::storage().elections().current_era_validators();
         let era_validators_with_static_account_ids = self.get_storage_entry(&addrs, at).await;
@@ -145,4 +191,34 @@ impl ElectionsSudoApi for RootConnection {
 
         self.sudo_unchecked(call, status).await
     }
+
+    async fn add_to_allowlist(
+        &self,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = Elections(add_to_allowlist {
+            validators: validators.into_iter().map(Static).collect(),
+        });
+
+        self.sudo_unchecked(call, status).await
+    }
+
+    async fn remove_from_allowlist(
+        &self,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = Elections(remove_from_allowlist {
+            validators: validators.into_iter().map(Static).collect(),
+        });
+
+        self.sudo_unchecked(call, status).await
+    }
+
+    async fn force_rotate_committee(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let call = Elections(force_rotate_committee);
+
+        self.sudo_unchecked(call, status).await
+    }
 }
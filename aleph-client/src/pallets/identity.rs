@@ -0,0 +1,127 @@
+use codec::{Decode, Encode};
+use subxt::utils::Static;
+
+use crate::{
+    api,
+    bounded_collections::bounded_vec::BoundedVec,
+    connections::{AsConnection, TxInfo},
+    pallet_identity::{
+        legacy::IdentityInfo as RawIdentityInfo,
+        types::{Data, Judgement},
+    },
+    AccountId, Balance, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// Longest display name that fits in a raw identity field.
+const MAX_RAW_DATA_LEN: usize = 32;
+
+/// Identity of an account, as registered in pallet identity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentityInfo {
+    /// Display name of the account, `None` if not set or not stored as raw bytes.
+    pub display: Option<String>,
+    /// Judgements given by registrars, together with their indices.
+    pub judgements: Vec<(u32, Judgement<Balance>)>,
+    /// Sub-accounts of the account.
+    pub sub_accounts: Vec<AccountId>,
+}
+
+/// Pallet identity read-only api.
+#[async_trait::async_trait]
+pub trait IdentityApi {
+    /// Returns the identity of a given account, built from
+    /// [`identity_of`](https://paritytech.github.io/polkadot-sdk/master/pallet_identity/pallet/type.IdentityOf.html)
+    /// and [`subs_of`](https://paritytech.github.io/polkadot-sdk/master/pallet_identity/pallet/type.SubsOf.html).
+    /// * `account` - an account id
+    /// * `at` - optional hash of a block to query state from
+    async fn identity_of(&self, account: AccountId, at: Option<BlockHash>) -> Option<IdentityInfo>;
+}
+
+/// Pallet identity api.
+#[async_trait::async_trait]
+pub trait IdentityUserApi {
+    /// API for [`set_identity`](https://paritytech.github.io/polkadot-sdk/master/pallet_identity/pallet/struct.Pallet.html#method.set_identity) call.
+    /// Sets an identity consisting of a display name only.
+    /// * `display` - display name, at most 32 bytes long
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn set_identity(&self, display: &str, status: TxStatus) -> anyhow::Result<TxInfo>;
+}
+
+fn data_to_string(data: &Data) -> Option<String> {
+    // `Raw` variants are encoded as their length plus one, followed by the bytes themselves.
+    let encoded = data.encode();
+    match encoded[0] as usize {
+        1..=33 => String::from_utf8(encoded[1..].to_vec()).ok(),
+        _ => None,
+    }
+}
+
+fn string_to_data(value: &str) -> anyhow::Result<Data> {
+    let bytes = value.as_bytes();
+    if bytes.len() > MAX_RAW_DATA_LEN {
+        anyhow::bail!(
+            "Identity field is {} bytes long, the limit is {}",
+            bytes.len(),
+            MAX_RAW_DATA_LEN
+        );
+    }
+    let mut encoded = vec![bytes.len() as u8 + 1];
+    encoded.extend_from_slice(bytes);
+    Ok(Data::decode(&mut &encoded[..])?)
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi + AsConnection> IdentityApi for C {
+    async fn identity_of(&self, account: AccountId, at: Option<BlockHash>) -> Option<IdentityInfo> {
+        let addrs = api::storage()
+            .identity()
+            .identity_of(Static(account.clone()));
+        let (registration, _) = self.get_storage_entry_maybe(&addrs, at).await?;
+
+        let addrs = api::storage().identity().subs_of(Static(account));
+        let sub_accounts = self
+            .get_storage_entry_maybe(&addrs, at)
+            .await
+            .map(|(_, subs)| subs.0.into_iter().map(|x| x.0).collect())
+            .unwrap_or_default();
+
+        Some(IdentityInfo {
+            display: data_to_string(&registration.info.display),
+            judgements: registration.judgements.0,
+            sub_accounts,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> IdentityUserApi for S {
+    async fn set_identity(&self, display: &str, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let info = RawIdentityInfo {
+            additional: BoundedVec(vec![]),
+            display: string_to_data(display)?,
+            legal: Data::None,
+            web: Data::None,
+            riot: Data::None,
+            email: Data::None,
+            pgp_fingerprint: None,
+            image: Data::None,
+            twitter: Data::None,
+        };
+        let tx = api::tx().identity().set_identity(info);
+
+        self.send_tx(tx, status).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{data_to_string, string_to_data, Data};
+
+    #[test]
+    fn converts_display_name() {
+        let data = string_to_data("alice").expect("should fit");
+        assert_eq!(data_to_string(&data), Some(String::from("alice")));
+        assert_eq!(data_to_string(&Data::None), None);
+        assert!(string_to_data(&"a".repeat(33)).is_err());
+    }
+}
@@ -13,8 +13,14 @@ pub mod elections;
 
 /// Pallet transaction payment API
 pub mod fee;
+/// Pallet identity API
+pub mod identity;
 /// Pallet multisig API
 pub mod multisig;
+/// Pallet nomination pools API
+pub mod nomination_pools;
+/// Pallet operations API
+pub mod operations;
 /// Pallet Proxy API
 pub mod proxy;
 /// Pallet safe-mode API
@@ -0,0 +1,144 @@
+use futures::{stream, StreamExt, TryStreamExt};
+use subxt::utils::Static;
+
+use crate::{
+    api, connections::AsConnection, pallet_nomination_pools::PoolMember, AccountId, Balance,
+    BlockHash, ConnectionApi,
+};
+
+/// How many `pending_rewards` runtime calls may be in flight at once.
+const MAX_CONCURRENT_REWARD_QUERIES: usize = 16;
+
+/// How many storage entries are fetched in a single page while iterating over pool members.
+const POOL_MEMBERS_PAGE_SIZE: u32 = 256;
+
+/// Pallet nomination pools read-only api.
+#[async_trait::async_trait]
+pub trait NominationPoolsApi {
+    /// Returns [`pool_members`](https://paritytech.github.io/polkadot-sdk/master/pallet_nomination_pools/pallet/type.PoolMembers.html) for a given account.
+    /// * `member` - an account id of the pool member
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pool_member(&self, member: AccountId, at: Option<BlockHash>)
+        -> Option<PoolMember>;
+
+    /// Returns all members of a given pool, i.e. accounts whose
+    /// [`pool_members`](https://paritytech.github.io/polkadot-sdk/master/pallet_nomination_pools/pallet/type.PoolMembers.html)
+    /// entry points at `pool_id`.
+    /// * `pool_id` - an id of the pool
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pool_members(
+        &self,
+        pool_id: u32,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<AccountId>>;
+
+    /// Returns pending rewards of a given pool member, calculated by the `NominationPoolsApi::pending_rewards` runtime api.
+    /// * `member` - an account id of the pool member
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pending_rewards(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance>;
+
+    /// Returns pending rewards of all members of a given pool. All values are computed at
+    /// the same block, i.e. `at` or the latest block if `at` is `None`.
+    /// * `pool_id` - an id of the pool
+    /// * `at` - optional hash of a block to query state from
+    async fn pool_pending_rewards(
+        &self,
+        pool_id: u32,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<(AccountId, Balance)>>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi + AsConnection> NominationPoolsApi for C {
+    async fn get_pool_member(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<PoolMember> {
+        let addrs = api::storage()
+            .nomination_pools()
+            .pool_members(Static(member));
+
+        self.get_storage_entry_maybe(&addrs, at).await
+    }
+
+    async fn get_pool_members(
+        &self,
+        pool_id: u32,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<AccountId>> {
+        let addrs = api::storage().nomination_pools().pool_members_root();
+
+        let storage = self.as_connection().as_client().storage();
+        let block = match at {
+            Some(block_hash) => storage.at(block_hash),
+            None => storage.at_latest().await?,
+        };
+
+        let mut members = vec![];
+        let mut iter = block.iter(addrs, POOL_MEMBERS_PAGE_SIZE).await?;
+        while let Some((key, member)) = iter.next().await? {
+            if member.pool_id != pool_id {
+                continue;
+            }
+            // `PoolMembers` is a `Twox64Concat` map, so the account id is the key suffix.
+            let key = key.0;
+            let account: [u8; 32] = key[key.len() - 32..]
+                .try_into()
+                .expect("Storage key should end with an account id");
+            members.push(AccountId::from(account));
+        }
+
+        Ok(members)
+    }
+
+    async fn get_pending_rewards(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance> {
+        let call = api::apis()
+            .nomination_pools_api()
+            .pending_rewards(Static(member));
+
+        let runtime_api = self.as_connection().as_client().runtime_api();
+        let runtime_api = match at {
+            Some(block_hash) => runtime_api.at(block_hash),
+            None => runtime_api.at_latest().await?,
+        };
+
+        Ok(runtime_api.call(call).await?)
+    }
+
+    async fn pool_pending_rewards(
+        &self,
+        pool_id: u32,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<(AccountId, Balance)>> {
+        // Pin the block, so that all members are queried against the same state.
+        let at = match at {
+            Some(block_hash) => block_hash,
+            None => self
+                .as_connection()
+                .as_client()
+                .blocks()
+                .at_latest()
+                .await?
+                .hash(),
+        };
+        let members = self.get_pool_members(pool_id, Some(at)).await?;
+
+        stream::iter(members)
+            .map(|member| async move {
+                let rewards = self.get_pending_rewards(member.clone(), Some(at)).await?;
+                Ok::<_, anyhow::Error>((member, rewards))
+            })
+            .buffered(MAX_CONCURRENT_REWARD_QUERIES)
+            .try_collect()
+            .await
+    }
+}
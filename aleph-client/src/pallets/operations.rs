@@ -0,0 +1,34 @@
+use subxt::utils::Static;
+
+use crate::{
+    aleph_runtime::RuntimeCall::Operations, connections::TxInfo,
+    pallet_operations::pallet::Call::fix_accounts_providers_counter, AccountId, RootConnection,
+    SudoCall, TxStatus,
+};
+
+/// Pallet operations API that requires sudo.
+#[async_trait::async_trait]
+pub trait OperationsSudoApi {
+    /// Issues `operations.fix_accounts_providers_counter`, which moves the providers counter of
+    /// an account by one towards the value expected from its balances.
+    /// * `who` - account to fix
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn fix_accounts_providers_counter(
+        &self,
+        who: AccountId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl OperationsSudoApi for RootConnection {
+    async fn fix_accounts_providers_counter(
+        &self,
+        who: AccountId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = Operations(fix_accounts_providers_counter { who: Static(who) });
+
+        self.sudo_unchecked(call, status).await
+    }
+}
@@ -1,10 +1,19 @@
 use subxt::utils::Static;
 
 use crate::{
-    api, connections::TxInfo, primitives::AlephNodeSessionKeys as SessionKeys, AccountId,
-    BlockHash, ConnectionApi, SessionIndex, SignedConnectionApi, TxStatus,
+    api,
+    chain_timing::ChainTimingApi,
+    connections::{AsConnection, TxInfo},
+    pallets::author::AuthorRpc,
+    primitives::AlephNodeSessionKeys as SessionKeys,
+    waiting::{AlephWaiting, BlockStatus},
+    AccountId, BlockHash, BlockNumber, ConnectionApi, SessionIndex, SignedConnectionApi, TxStatus,
 };
 
+/// If fewer blocks than this are left in the current session, `set_keys` is postponed until the
+/// next one, so that it is not accidentally included in a later session than planned.
+const SET_KEYS_SESSION_MARGIN: BlockNumber = 5;
+
 /// Pallet session read-only api.
 #[async_trait::async_trait]
 pub trait SessionApi {
@@ -29,6 +38,16 @@ pub trait SessionUserApi {
     async fn set_keys(&self, new_keys: SessionKeys, status: TxStatus) -> anyhow::Result<TxInfo>;
 }
 
+/// Session keys rotation api.
+#[async_trait::async_trait]
+pub trait SessionKeysRotationApi {
+    /// Rotates session keys on the node behind this connection and sets them for the signer,
+    /// timing the `set_keys` call so that the new keys activate at a known session boundary.
+    /// Keys set in session `n` are queued when it ends and become active in session `n + 2`.
+    /// Returns the index of the session in which the new keys become active.
+    async fn safe_rotate_keys(&self) -> anyhow::Result<SessionIndex>;
+}
+
 #[async_trait::async_trait]
 impl<C: ConnectionApi> SessionApi for C {
     async fn get_next_session_keys(
@@ -68,3 +87,20 @@ impl<S: SignedConnectionApi> SessionUserApi for S {
         self.send_tx(tx, status).await
     }
 }
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi + AsConnection> SessionKeysRotationApi for S {
+    async fn safe_rotate_keys(&self) -> anyhow::Result<SessionIndex> {
+        let timing = self.session_timing().await?;
+        if timing.blocks_left <= SET_KEYS_SESSION_MARGIN {
+            self.wait_for_session(timing.session + 1, BlockStatus::Best)
+                .await;
+        }
+
+        let new_keys = self.author_rotate_keys().await?;
+        let tx_info = self.set_keys(new_keys, TxStatus::Finalized).await?;
+        let session = self.get_session(Some(tx_info.block_hash)).await;
+
+        Ok(session + 2)
+    }
+}
@@ -1,4 +1,5 @@
 use codec::Encode;
+use futures::{stream, StreamExt, TryStreamExt};
 use subxt::{
     ext::sp_runtime::Perbill as SPerbill,
     storage::StorageKey,
@@ -17,7 +18,7 @@ use crate::{
         EraRewardPoints, RewardDestination, StakingLedger, ValidatorPrefs,
     },
     pallet_sudo::pallet::Call::sudo_as,
-    pallets::utility::UtilityApi,
+    pallets::{elections::ElectionsApi, utility::UtilityApi},
     sp_arithmetic::per_things::Perbill,
     sp_staking::{Exposure, IndividualExposure},
     AccountId, Balance, BlockHash,
@@ -25,6 +26,27 @@ use crate::{
     ConnectionApi, EraIndex, RootConnection, SignedConnectionApi, SudoCall, TxStatus,
 };
 
+/// How many validators may be queried for their stake at once.
+const MAX_CONCURRENT_STAKE_QUERIES: usize = 16;
+
+/// How many storage entries are fetched in a single page while iterating over validators.
+const VALIDATORS_PAGE_SIZE: u32 = 256;
+
+/// A summary of a single validator, as shown by staking dashboards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorOverview {
+    /// Stash account of the validator.
+    pub stash: AccountId,
+    /// Commission taken by the validator.
+    pub commission: Perbill,
+    /// Whether the validator blocks new nominations.
+    pub blocked: bool,
+    /// Total stake bonded by the validator's stash.
+    pub total_stake: Balance,
+    /// Whether the validator is one of `elections.current_era_validators`.
+    pub in_current_era: bool,
+}
+
 /// Any object that implemnts pallet staking read-only api.
 #[async_trait::async_trait]
 pub trait StakingApi {
@@ -88,6 +110,15 @@ pub trait StakingApi {
         validator: AccountId,
         at: Option<BlockHash>,
     ) -> Vec<u32>;
+
+    /// Returns a [`ValidatorOverview`] for every account with an entry in
+    /// [`validators`](https://paritytech.github.io/polkadot-sdk/master/pallet_staking/type.Validators.html).
+    /// All values are read from the same block, i.e. `at` or the latest block if `at` is `None`.
+    /// * `at` - optional hash of a block to query state from
+    async fn validators_overview(
+        &self,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<ValidatorOverview>>;
 }
 
 /// Pallet staking api
@@ -413,6 +444,70 @@ impl<C: ConnectionApi + AsConnection> StakingApi for C {
             .claimed_rewards(era, Static(validator));
         self.get_storage_entry(&addrs, at).await
     }
+
+    async fn validators_overview(
+        &self,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Vec<ValidatorOverview>> {
+        // Pin the block, so that all validators are queried against the same state.
+        let at = match at {
+            Some(block_hash) => block_hash,
+            None => self
+                .as_connection()
+                .as_client()
+                .blocks()
+                .at_latest()
+                .await?
+                .hash(),
+        };
+
+        let era_validators: primitives::EraValidators<_> =
+            self.get_current_era_validators(Some(at)).await.into();
+        let storage = self.as_connection().as_client().storage().at(at);
+        let mut validators = vec![];
+        let mut iter = storage
+            .iter(
+                api::storage().staking().validators_root(),
+                VALIDATORS_PAGE_SIZE,
+            )
+            .await?;
+        while let Some((key, prefs)) = iter.next().await? {
+            // `Validators` is a `Twox64Concat` map, so the account id is the key suffix.
+            let key = key.0;
+            let stash: [u8; 32] = key[key.len() - 32..]
+                .try_into()
+                .expect("Storage key should end with an account id");
+            validators.push((AccountId::from(stash), prefs));
+        }
+
+        stream::iter(validators)
+            .map(|(stash, prefs)| {
+                let in_current_era = era_validators.contains(&stash);
+                async move {
+                    let total_stake = match self.get_bonded(stash.clone(), Some(at)).await {
+                        Some(controller) => self
+                            .get_storage_entry_maybe(
+                                &api::storage().staking().ledger(Static(controller)),
+                                Some(at),
+                            )
+                            .await
+                            .map(|ledger| ledger.total)
+                            .unwrap_or_default(),
+                        None => 0,
+                    };
+                    Ok::<_, anyhow::Error>(ValidatorOverview {
+                        stash,
+                        commission: prefs.commission,
+                        blocked: prefs.blocked,
+                        total_stake,
+                        in_current_era,
+                    })
+                }
+            })
+            .buffered(MAX_CONCURRENT_STAKE_QUERIES)
+            .try_collect()
+            .await
+    }
 }
 
 #[async_trait::async_trait]
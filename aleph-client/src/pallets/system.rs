@@ -2,8 +2,12 @@ use primitives::Nonce;
 use subxt::utils::Static;
 
 use crate::{
-    api, connections::TxInfo, frame_system::pallet::Call::set_code, AccountId, AsConnection,
-    Balance, BlockHash, Call::System, ConnectionApi, RootConnection, SudoCall, TxStatus,
+    api,
+    connections::TxInfo,
+    frame_system::pallet::Call::{set_code, set_code_without_checks},
+    AccountId, AsConnection, Balance, BlockHash,
+    Call::System,
+    ConnectionApi, RootConnection, SudoCall, TxStatus,
 };
 
 /// Pallet system read-only api.
@@ -19,6 +23,17 @@ pub trait SystemApi {
     /// returns account nonce of a given account
     /// * `account` - account id
     async fn account_nonce(&self, account: &AccountId) -> anyhow::Result<Nonce>;
+
+    /// returns the next nonce of a given account, taking into account its transactions
+    /// waiting in the transaction pool
+    /// * `account` - account id
+    ///
+    /// it uses `system_accountNextIndex` rpc
+    async fn account_next_nonce(&self, account: &AccountId) -> anyhow::Result<Nonce>;
+
+    /// returns the wasm code of the runtime
+    /// * `at` - optional hash of a block to query state from
+    async fn get_code(&self, at: Option<BlockHash>) -> anyhow::Result<Vec<u8>>;
 }
 
 /// Pallet system api.
@@ -26,6 +41,13 @@ pub trait SystemApi {
 pub trait SystemSudoApi {
     /// API for [`set_code`](https://paritytech.github.io/substrate/master/frame_system/pallet/struct.Pallet.html#method.set_code) call.
     async fn set_code(&self, code: Vec<u8>, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`set_code_without_checks`](https://paritytech.github.io/substrate/master/frame_system/pallet/struct.Pallet.html#method.set_code_without_checks) call.
+    async fn set_code_without_checks(
+        &self,
+        code: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
 }
 
 #[async_trait::async_trait]
@@ -35,6 +57,16 @@ impl SystemSudoApi for RootConnection {
 
         self.sudo_unchecked(call, status).await
     }
+
+    async fn set_code_without_checks(
+        &self,
+        code: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = System(set_code_without_checks { code });
+
+        self.sudo_unchecked(call, status).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -52,4 +84,27 @@ impl<C: AsConnection + Sync> SystemApi for C {
         let conn = self.as_connection();
         Ok(conn.client.tx().account_nonce(account).await?.try_into()?)
     }
+
+    async fn account_next_nonce(&self, account: &AccountId) -> anyhow::Result<Nonce> {
+        let nonce = self
+            .as_connection()
+            .as_client()
+            .rpc()
+            .system_account_next_index(account)
+            .await?;
+
+        Ok(nonce.try_into()?)
+    }
+
+    async fn get_code(&self, at: Option<BlockHash>) -> anyhow::Result<Vec<u8>> {
+        let code = self
+            .as_connection()
+            .as_client()
+            .rpc()
+            .storage(b":code", at)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Runtime code is missing from the state"))?;
+
+        Ok(code.0)
+    }
 }
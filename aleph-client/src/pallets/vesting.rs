@@ -1,8 +1,14 @@
+use anyhow::anyhow;
+use log::info;
 use subxt::utils::{MultiAddress, Static};
 
 use crate::{
-    api, connections::TxInfo, pallet_vesting::vesting_info::VestingInfo, AccountId, Balance,
-    BlockHash, BlockNumber, ConnectionApi, SignedConnectionApi, TxStatus,
+    api,
+    connections::TxInfo,
+    pallet_vesting::{pallet::Call::merge_schedules, vesting_info::VestingInfo},
+    AccountId, Balance, BlockHash, BlockNumber,
+    Call::Vesting,
+    ConnectionApi, SignedConnectionApi, TxStatus,
 };
 
 /// Read only pallet vesting API.
@@ -44,6 +50,25 @@ pub trait VestingUserApi {
     ) -> anyhow::Result<TxInfo>;
 }
 
+/// Pallet vesting logic, not directly related to any particular pallet call.
+#[async_trait::async_trait]
+pub trait VestingApiExt {
+    /// Merges all vesting schedules of the signer into a single one, unlocking already vested funds.
+    /// Merges are sent in `batch_all` extrinsics of at most `max_merges_per_batch` [`merge_schedules`](https://paritytech.github.io/substrate/master/pallet_vesting/pallet/enum.Call.html#variant.merge_schedules)
+    /// calls each, so that every extrinsic stays within block limits. Every batch is atomic, hence
+    /// the account is left with valid schedules even if the compaction is interrupted.
+    /// * `max_merges_per_batch` - maximal number of merges in a single extrinsic, must be positive
+    /// * `status` - a [`TxStatus`] for each tx to wait for, cannot be [`TxStatus::Submitted`] since
+    /// every batch is built based on the outcome of the previous one
+    /// # Returns
+    /// Info about all the submitted extrinsics, in the order of submission.
+    async fn compact_and_vest(
+        &self,
+        max_merges_per_batch: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<Vec<TxInfo>>;
+}
+
 #[async_trait::async_trait]
 impl<C: ConnectionApi> VestingApi for C {
     async fn get_vesting(
@@ -97,3 +122,53 @@ impl<S: SignedConnectionApi> VestingUserApi for S {
         self.send_tx(tx, status).await
     }
 }
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> VestingApiExt for S {
+    async fn compact_and_vest(
+        &self,
+        max_merges_per_batch: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<Vec<TxInfo>> {
+        if max_merges_per_batch == 0 {
+            return Err(anyhow!("At least one merge per batch is required"));
+        }
+        if let TxStatus::Submitted = status {
+            return Err(anyhow!(
+                "Compaction requires waiting for the batches to be included"
+            ));
+        }
+
+        // Vesting first gets rid of finished schedules, so that none of them disappears during merging.
+        let mut infos = vec![self.vest(status).await?];
+        loop {
+            let schedules = self
+                .get_vesting(self.account_id().clone(), None)
+                .await
+                .len() as u32;
+            if schedules <= 1 {
+                return Ok(infos);
+            }
+
+            // Merged schedule is appended at the end, so we can keep merging the first two ones.
+            let merges = (schedules - 1).min(max_merges_per_batch);
+            let calls = (0..merges)
+                .map(|_| {
+                    Vesting(merge_schedules {
+                        schedule1_index: 0,
+                        schedule2_index: 1,
+                    })
+                })
+                .collect();
+            let tx = api::tx().utility().batch_all(calls);
+            infos.push(self.send_tx(tx, status).await?);
+            info!(
+                target: "aleph-client",
+                "merged {} out of {} vesting schedules of {}",
+                merges,
+                schedules,
+                self.account_id()
+            );
+        }
+    }
+}
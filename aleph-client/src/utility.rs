@@ -1,15 +1,27 @@
 use anyhow::anyhow;
+use codec::{Decode, Encode};
+use futures::{stream::BoxStream, StreamExt};
 use log::debug;
 use primitives::Balance;
-use subxt::{blocks::ExtrinsicEvents, config::Hasher, Config};
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::{substrate::DigestItem, Hasher},
+    Config,
+};
 
 use crate::{
-    api::transaction_payment::events::TransactionFeePaid,
-    connections::{AsConnection, TxInfo},
+    api,
+    api::{
+        runtime_types::sp_core::crypto::KeyTypeId, transaction_payment::events::TransactionFeePaid,
+    },
+    connections::{AsConnection, ConnectionApi, TxInfo},
     pallets::{committee_management::CommitteeManagementApi, staking::StakingApi},
-    AlephConfig, BlockHash, BlockNumber, EraIndex, SessionIndex,
+    AccountId, AlephConfig, BlockHash, BlockNumber, EraIndex, SessionIndex,
 };
 
+const AURA_ENGINE_ID: [u8; 4] = *b"aura";
+const AURA_KEY_TYPE: [u8; 4] = *b"aura";
+
 /// Block info API.
 #[async_trait::async_trait]
 pub trait BlocksApi {
@@ -47,6 +59,12 @@ pub trait BlocksApi {
 
     /// Returns the fee that was paid for the transaction identified by `tx_info`.
     async fn get_tx_fee(&self, tx_info: TxInfo) -> anyhow::Result<Balance>;
+
+    /// Subscribes to new blocks of the best chain and yields their numbers together with their
+    /// authors. The author is the validator owning the Aura slot of the block.
+    async fn subscribe_block_authors(
+        &self,
+    ) -> anyhow::Result<BoxStream<'_, anyhow::Result<(BlockNumber, AccountId)>>>;
 }
 
 /// Interaction logic between pallet session and pallet staking.
@@ -141,6 +159,59 @@ impl<C: AsConnection + Sync> BlocksApi for C {
             .ok_or_else(|| anyhow!("TransactionFeePaid event not found"))
             .map(|tfp| tfp.actual_fee)
     }
+
+    async fn subscribe_block_authors(
+        &self,
+    ) -> anyhow::Result<BoxStream<'_, anyhow::Result<(BlockNumber, AccountId)>>> {
+        let block_sub = self
+            .as_connection()
+            .as_client()
+            .blocks()
+            .subscribe_best()
+            .await?;
+
+        Ok(block_sub
+            .then(move |block| async move {
+                let header = block?.header().clone();
+                let slot = aura_slot(&header.digest.logs)?;
+                // The slot is claimed by one of the Aura authorities of the session of the block,
+                // as seen by its parent.
+                let at = Some(header.parent_hash);
+                let authorities = self
+                    .get_storage_entry_maybe(&api::storage().aura().authorities(), at)
+                    .await
+                    .map(|authorities| authorities.0)
+                    .unwrap_or_default();
+                let authority = authorities
+                    .get(slot as usize % authorities.len().max(1))
+                    .ok_or_else(|| anyhow!("No Aura authorities at block {}", header.number))?;
+                let owner_address = api::storage()
+                    .session()
+                    .key_owner(KeyTypeId(AURA_KEY_TYPE), authority.encode());
+                let author = self
+                    .get_storage_entry_maybe(&owner_address, at)
+                    .await
+                    .ok_or_else(|| {
+                        anyhow!("Unknown owner of the Aura key of block {}", header.number)
+                    })?
+                    .0;
+
+                Ok((header.number, author))
+            })
+            .boxed())
+    }
+}
+
+fn aura_slot(logs: &[DigestItem]) -> anyhow::Result<u64> {
+    logs.iter()
+        .find_map(|item| match item {
+            DigestItem::PreRuntime(engine_id, data) if *engine_id == AURA_ENGINE_ID => {
+                Some(u64::decode(&mut &data[..]))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("Block does not contain an Aura pre-runtime digest"))?
+        .map_err(|e| anyhow!("Failed to decode Aura slot: {e:?}"))
 }
 
 #[async_trait::async_trait]
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
 use futures::StreamExt;
 use log::info;
 use subxt::events::StaticEvent;
@@ -6,8 +9,8 @@ use crate::{
     aleph_zero,
     api::session::events::NewSession,
     connections::AsConnection,
-    pallets::{session::SessionApi, staking::StakingApi},
-    EraIndex, SessionIndex,
+    pallets::{session::SessionApi, staking::StakingApi, system::SystemApi},
+    AccountId, Balance, EraIndex, SessionIndex,
 };
 
 /// When using waiting API, what kind of block status we should wait for.
@@ -63,6 +66,22 @@ pub trait AlephWaiting {
         status: BlockStatus,
     ) -> T;
 
+    /// Wait for a particular event to be emitted in the finalized chain.
+    /// Unlike [`AlephWaiting::wait_for_event`], it never resolves on an event from a block that
+    /// might still be reverted, and it reports subscription or decoding failures as errors.
+    /// * `predicate` - a predicate that has one argument (ref to an emitted event)
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let event = connection
+    ///     .wait_for_event_finalized(|event: &BanValidators| !event.0.is_empty())
+    ///     .await?;
+    /// ```
+    async fn wait_for_event_finalized<T: StaticEvent, P: Fn(&T) -> bool + Send>(
+        &self,
+        predicate: P,
+    ) -> anyhow::Result<T>;
+
     /// Wait for given era to happen.
     /// * `era` - number of the era to wait for
     /// * `status` - a [`BlockStatus`] of the era we wait for
@@ -72,6 +91,25 @@ pub trait AlephWaiting {
     /// * `session` - number of the session to wait for
     /// * `status` - a [`BlockStatus`] of the session we wait for
     async fn wait_for_session(&self, session: SessionIndex, status: BlockStatus);
+
+    /// Wait for a free balance of a given account to reach a threshold in the finalized chain.
+    /// Returns the free balance observed in the first finalized block that satisfies the threshold.
+    /// * `account` - account id to watch
+    /// * `at_least` - minimal free balance to wait for
+    /// * `timeout` - how long to wait before giving up
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let balance = connection
+    ///     .wait_for_balance(account, 1_000_000_000_000, Duration::from_secs(60))
+    ///     .await?;
+    /// ```
+    async fn wait_for_balance(
+        &self,
+        account: AccountId,
+        at_least: Balance,
+        timeout: Duration,
+    ) -> anyhow::Result<Balance>;
 }
 
 /// nWaiting _from_ the current moment of time API
@@ -158,6 +196,32 @@ impl<C: AsConnection + Sync> AlephWaiting for C {
         panic!("No more blocks");
     }
 
+    async fn wait_for_event_finalized<T: StaticEvent, P: Fn(&T) -> bool + Send>(
+        &self,
+        predicate: P,
+    ) -> anyhow::Result<T> {
+        let mut block_sub = self
+            .as_connection()
+            .as_client()
+            .blocks()
+            .subscribe_finalized()
+            .await?;
+
+        info!(target: "aleph-client", "waiting for finalized event {}.{}", T::PALLET, T::EVENT);
+
+        while let Some(block) = block_sub.next().await {
+            for event in block?.events().await?.iter() {
+                if let Some(ev) = event?.as_event::<T>()? {
+                    if predicate(&ev) {
+                        return Ok(ev);
+                    }
+                }
+            }
+        }
+
+        Err(anyhow!("No more blocks"))
+    }
+
     async fn wait_for_era(&self, era: EraIndex, status: BlockStatus) {
         let addrs = aleph_zero::api::constants().staking().sessions_per_era();
         let sessions_per_era = self
@@ -178,6 +242,43 @@ impl<C: AsConnection + Sync> AlephWaiting for C {
         }, status)
             .await;
     }
+
+    async fn wait_for_balance(
+        &self,
+        account: AccountId,
+        at_least: Balance,
+        timeout: Duration,
+    ) -> anyhow::Result<Balance> {
+        let mut block_sub = self
+            .as_connection()
+            .as_client()
+            .blocks()
+            .subscribe_finalized()
+            .await?;
+
+        info!(target: "aleph-client", "waiting for balance of {} to reach {}", account, at_least);
+
+        let wait = async {
+            while let Some(block) = block_sub.next().await {
+                let balance = self
+                    .get_free_balance(account.clone(), Some(block?.hash()))
+                    .await;
+                if balance >= at_least {
+                    return Ok(balance);
+                }
+            }
+            Err(anyhow!("No more blocks"))
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            anyhow!(
+                "Balance of {} did not reach {} within {:?}",
+                account,
+                at_least,
+                timeout
+            )
+        })?
+    }
 }
 
 #[async_trait::async_trait]
@@ -4,8 +4,8 @@ use aleph_runtime::WASM_BINARY;
 use pallet_staking::{Forcing, StakerStatus};
 use primitives::{
     staking::{MIN_NOMINATOR_BOND, MIN_VALIDATOR_BOND},
-    AccountId, AlephNodeSessionKeys, Version as FinalityVersion, ADDRESSES_ENCODING,
-    TOKEN_DECIMALS,
+    AccountId, AlephNodeSessionKeys, CommitteeSeats, Version as FinalityVersion,
+    ADDRESSES_ENCODING, TOKEN_DECIMALS,
 };
 use serde_json::{Number, Value};
 use sp_runtime::Perbill;
@@ -42,6 +42,9 @@ pub fn build_chain_spec(
     let sudo_account = chain_params.sudo_account_id();
     let rich_accounts = chain_params.rich_account_ids();
     let finality_version = chain_params.finality_version();
+    let committee_seats = chain_params
+        .committee_seats()
+        .map_err(|e| format!("Invalid committee seats: {:?}", e))?;
 
     Ok(AlephNodeChainSpec::builder(
         WASM_BINARY.ok_or("AlephNode development wasm not available")?,
@@ -55,6 +58,7 @@ pub fn build_chain_spec(
         sudo_account,
         rich_accounts,
         finality_version,
+        committee_seats,
     ))
     .with_properties(system_properties(token_symbol))
     .build())
@@ -72,6 +76,7 @@ fn generate_genesis_config(
     sudo_account: AccountId,
     rich_accounts: Option<Vec<AccountId>>,
     finality_version: FinalityVersion,
+    committee_seats: CommitteeSeats,
 ) -> serde_json::Value {
     let mut endowed_accounts = to_account_ids(&account_session_keys)
         .chain(
@@ -97,6 +102,7 @@ fn generate_genesis_config(
         },
         "elections": {
             "reservedValidators": to_account_ids(&account_session_keys).collect::<Vec<_>>(),
+            "committeeSeats": committee_seats,
         },
         "session": {
            "keys": account_session_keys
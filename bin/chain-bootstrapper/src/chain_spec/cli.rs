@@ -1,5 +1,6 @@
 use primitives::{
-    AccountId, Version as FinalityVersion, CURRENT_FINALITY_VERSION, LEGACY_FINALITY_VERSION,
+    AccountId, CommitteeSeats, CommitteeSeatsError, Version as FinalityVersion,
+    CURRENT_FINALITY_VERSION, DEFAULT_COMMITTEE_SIZE, LEGACY_FINALITY_VERSION,
 };
 use sc_chain_spec::ChainType;
 use sc_cli::clap::{self, Args};
@@ -47,6 +48,18 @@ pub struct ChainSpecParams {
     /// Finality version at chain inception.
     #[arg(long, default_value = "legacy")]
     finality_version: String,
+
+    /// Number of reserved seats in the committee at chain inception.
+    #[arg(long, default_value_t = DEFAULT_COMMITTEE_SIZE)]
+    reserved_seats: u32,
+
+    /// Number of non reserved seats in the committee at chain inception.
+    #[arg(long, default_value_t = 0)]
+    non_reserved_seats: u32,
+
+    /// Number of non reserved seats participating in the finality, at most `--non-reserved-seats`.
+    #[arg(long, default_value_t = 0)]
+    non_reserved_finality_seats: u32,
 }
 
 impl ChainSpecParams {
@@ -90,4 +103,12 @@ impl ChainSpecParams {
         }
         .into()
     }
+
+    pub fn committee_seats(&self) -> Result<CommitteeSeats, CommitteeSeatsError> {
+        CommitteeSeats::try_new(
+            self.reserved_seats,
+            self.non_reserved_seats,
+            self.non_reserved_finality_seats,
+        )
+    }
 }
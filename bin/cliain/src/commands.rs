@@ -201,6 +201,14 @@ pub enum Command {
     /// Call rotate_keys() RPC call and prints them to stdout
     RotateKeys,
 
+    /// Checks whether the node holds the private keys for the session keys set on chain for
+    /// the given account
+    CheckKeys {
+        /// SS58 id of the validator for which we want to check the keys
+        #[clap(long)]
+        account_id: String,
+    },
+
     /// Sets given keys for origin controller
     SetKeys {
         /// 64 byte hex encoded string in form 0xaabbcc..
@@ -345,8 +353,17 @@ pub enum Command {
     /// API signature: https://polkadot.js.org/docs/substrate/extrinsics/#removecodecode_hash-h256
     ContractRemoveCode(ContractRemoveCode),
 
-    /// Schedules a version upgrade of the network.
-    VersionUpgradeSchedule {
+    /// Manages finality version changes of the network. Requires sudo.
+    #[clap(subcommand)]
+    FinalityVersion(FinalityVersionCommand),
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum FinalityVersionCommand {
+    /// Schedules a finality version change of the network.
+    ///
+    /// The session has to be at least 2 sessions after the current one.
+    Schedule {
         #[clap(long)]
         version: Version,
 
@@ -356,4 +373,10 @@ pub enum Command {
         #[clap(long, value_enum, default_value_t=ExtrinsicState::Finalized)]
         expected_state: ExtrinsicState,
     },
+
+    /// Cancels the scheduled finality version change of the network.
+    Cancel {
+        #[clap(long, value_enum, default_value_t=ExtrinsicState::Finalized)]
+        expected_state: ExtrinsicState,
+    },
 }
@@ -10,7 +10,8 @@ use aleph_client::{
     pallets::contract::{ContractsApi, ContractsUserApi},
     sp_weights::weight_v2::Weight,
     waiting::{AlephWaiting, BlockStatus},
-    AccountId, Balance, CodeHash, Connection, SignedConnection, SignedConnectionApi, TxStatus,
+    AccountId, Balance, CodeHash, Connection, SignedConnection, SignedConnectionApi, TxInfo,
+    TxStatus,
 };
 use codec::{Compact, Decode};
 use contract_metadata::ContractMetadata;
@@ -209,7 +210,7 @@ pub async fn instantiate_with_code(
 pub async fn call(
     signed_connection: SignedConnection,
     command: ContractCall,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<TxInfo> {
     let ContractCall {
         destination,
         message,
@@ -230,7 +231,7 @@ pub async fn call(
 
     debug!("Encoded call data {:?}", data);
 
-    let _tx_info = signed_connection
+    signed_connection
         .call(
             destination,
             balance,
@@ -239,9 +240,7 @@ pub async fn call(
             data,
             TxStatus::InBlock,
         )
-        .await?;
-
-    Ok(())
+        .await
 }
 
 pub async fn code_info(connection: Connection, command: ContractCodeInfo) -> Option<CodeInfo> {
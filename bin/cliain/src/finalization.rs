@@ -2,18 +2,18 @@ use std::str::FromStr;
 
 use aleph_client::{
     pallets::aleph::{AlephRpc, AlephSudoApi},
-    AccountId, AlephKeyPair, Connection, TxStatus,
+    AccountId, AlephKeyPair, Connection, TxInfo, TxStatus,
 };
 use primitives::{BlockHash, BlockNumber};
 
 use crate::RootConnection;
 
 /// Sets the emergency finalized, the provided string should be the seed phrase of the desired finalizer.
-pub async fn set_emergency_finalizer(connection: RootConnection, finalizer: AccountId) {
+pub async fn set_emergency_finalizer(connection: RootConnection, finalizer: AccountId) -> TxInfo {
     connection
         .set_emergency_finalizer(finalizer, TxStatus::Finalized)
         .await
-        .unwrap();
+        .unwrap()
 }
 
 /// Finalizes the given block using the key pair from provided seed as emergency finalizer.
@@ -5,10 +5,11 @@ use aleph_client::{
         staking::StakingUserApi,
     },
     primitives::AlephNodeSessionKeys as SessionKeys,
-    AccountId, Connection, RootConnection, SignedConnection, Ss58Codec, TxStatus,
+    AccountId, Connection, RootConnection, SignedConnection, Ss58Codec, TxInfo, TxStatus,
 };
+use anyhow::{anyhow, bail, ensure, Context};
 use hex::ToHex;
-use log::{error, info};
+use log::info;
 use primitives::staking::MIN_VALIDATOR_BOND;
 use serde_json::json;
 
@@ -22,34 +23,64 @@ pub async fn prepare_keys(connection: RootConnection) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn set_keys(connection: SignedConnection, new_keys: String) {
+pub async fn set_keys(connection: SignedConnection, new_keys: String) -> TxInfo {
     connection
         .set_keys(SessionKeys::try_from(new_keys).unwrap(), TxStatus::InBlock)
         .await
-        .unwrap();
+        .unwrap()
 }
 
-pub async fn rotate_keys(connection: Connection) {
-    match connection.author_rotate_keys().await {
-        Ok(new_keys) => info!(
-            "Keys rotated, use the following in set_keys: {}{}",
-            new_keys.aura.0 .0.encode_hex::<String>(),
-            new_keys.aleph.0 .0.encode_hex::<String>()
-        ),
-        Err(e) => error!("Failed to rotate keys: {}.", e),
-    }
+/// Rotates the keys of the node and returns them in the format expected by `set_keys`.
+pub async fn rotate_keys(connection: Connection) -> anyhow::Result<String> {
+    let new_keys = connection
+        .author_rotate_keys()
+        .await
+        .context("Failed to rotate keys")?;
+    Ok(format!(
+        "{}{}",
+        new_keys.aura.0 .0.encode_hex::<String>(),
+        new_keys.aleph.0 .0.encode_hex::<String>()
+    ))
+}
+
+/// Returns the keys the account has set for the next session.
+pub async fn next_session_keys(
+    connection: Connection,
+    account_id: String,
+) -> anyhow::Result<serde_json::Value> {
+    let account_id = AccountId::from_ss58check(&account_id).expect("Address is valid");
+    let keys = connection
+        .get_next_session_keys(account_id, None)
+        .await
+        .ok_or_else(|| anyhow!("No keys set for the specified account."))?;
+    Ok(json!({
+        "aura": "0x".to_owned() + keys.aura.0.0.encode_hex::<String>().as_str(),
+        "aleph": "0x".to_owned() + keys.aleph.0.0.encode_hex::<String>().as_str(),
+    }))
 }
 
-pub async fn next_session_keys(connection: Connection, account_id: String) {
+pub async fn check_keys(connection: Connection, account_id: String) -> anyhow::Result<()> {
     let account_id = AccountId::from_ss58check(&account_id).expect("Address is valid");
-    match connection.get_next_session_keys(account_id, None).await {
-        Some(keys) => {
-            let keys_json = json!({
-                "aura": "0x".to_owned() + keys.aura.0.0.encode_hex::<String>().as_str(),
-                "aleph": "0x".to_owned() + keys.aleph.0.0.encode_hex::<String>().as_str(),
-            });
-            println!("{}", serde_json::to_string_pretty(&keys_json).unwrap());
-        }
-        None => error!("No keys set for the specified account."),
-    }
+    let keys = match connection
+        .get_next_session_keys(account_id.clone(), None)
+        .await
+    {
+        Some(keys) => keys,
+        None => bail!("No keys set for {account_id}, call set_keys first."),
+    };
+
+    let has_aura = connection
+        .author_has_key(keys.aura.0 .0.to_vec(), "aura")
+        .await?;
+    let has_aleph = connection
+        .author_has_key(keys.aleph.0 .0.to_vec(), "alp0")
+        .await?;
+
+    ensure!(
+        has_aura && has_aleph,
+        "Keys set for {account_id} do not match the keys of the node, the node will miss its \
+         blocks. Aura key matches: {has_aura}, Aleph key matches: {has_aleph}."
+    );
+    info!("Keys set for {account_id} match the keys of the node.");
+    Ok(())
 }
@@ -2,6 +2,7 @@ mod commands;
 mod contracts;
 mod finalization;
 mod keys;
+mod output;
 mod runtime;
 mod secret;
 mod staking;
@@ -12,12 +13,13 @@ mod version_upgrade;
 mod vesting;
 
 use aleph_client::{keypair_from_string, Connection, RootConnection, SignedConnection};
-pub use commands::{Command};
+pub use commands::{Command, FinalityVersionCommand};
 pub use contracts::{
     call, code_info, instantiate, instantiate_with_code, remove_code, upload_code,
 };
 pub use finalization::{finalize, set_emergency_finalizer};
-pub use keys::{next_session_keys, prepare_keys, rotate_keys, set_keys};
+pub use keys::{check_keys, next_session_keys, prepare_keys, rotate_keys, set_keys};
+pub use output::{print_json, print_output, print_tx_output, OutputFormat, ToJson};
 pub use runtime::update_runtime;
 pub use secret::prompt_password_hidden;
 pub use staking::{bond, force_new_era, nominate, set_staking_limits, validate};
@@ -26,7 +28,7 @@ pub use treasury::{
     approve as treasury_approve, propose as treasury_propose, reject as treasury_reject,
 };
 pub use validators::change_validators;
-pub use version_upgrade::schedule_upgrade;
+pub use version_upgrade::{cancel_upgrade, schedule_upgrade};
 pub use vesting::{vest, vest_other, vested_transfer};
 
 pub struct ConnectionConfig {
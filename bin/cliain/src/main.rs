@@ -1,16 +1,19 @@
 use std::env;
 
 use aleph_client::{account_from_keypair, aleph_keypair_from_string, keypair_from_string, Pair};
+use anyhow::Context;
 use clap::Parser;
 use cliain::{
-    bond, call, change_validators, code_info, finalize, force_new_era, instantiate,
-    instantiate_with_code, next_session_keys, nominate, prepare_keys, prompt_password_hidden,
-    remove_code, rotate_keys, schedule_upgrade, set_emergency_finalizer, set_keys,
-    set_staking_limits, transfer_keep_alive, treasury_approve, treasury_propose,
-    treasury_reject, update_runtime, upload_code, validate, vest, vest_other, vested_transfer,
-    Command, ConnectionConfig,
+    bond, call, cancel_upgrade, change_validators, check_keys, code_info, finalize, force_new_era,
+    instantiate, instantiate_with_code, next_session_keys, nominate, prepare_keys, print_json,
+    print_output, print_tx_output, prompt_password_hidden, remove_code, rotate_keys,
+    schedule_upgrade, set_emergency_finalizer, set_keys, set_staking_limits, transfer_keep_alive,
+    treasury_approve, treasury_propose, treasury_reject, update_runtime, upload_code, validate,
+    vest, vest_other, vested_transfer, Command, ConnectionConfig, FinalityVersionCommand,
+    OutputFormat,
 };
 use log::{error, info};
+use serde_json::json;
 
 #[derive(Debug, Parser, Clone)]
 #[clap(version = "1.0")]
@@ -25,6 +28,11 @@ struct Config {
     #[clap(long)]
     pub seed: Option<String>,
 
+    /// Format of the printed results. With `json`, commands submitting a transaction print
+    /// its hashes and emitted events, and query commands print their results as JSON
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     /// Specific command that executes either a signed transaction or is an auxiliary command
     #[clap(subcommand)]
     pub command: Command,
@@ -39,6 +47,7 @@ fn read_seed(command: &Command, seed: Option<String>) -> String {
         }
         | Command::NextSessionKeys { .. }
         | Command::RotateKeys
+        | Command::CheckKeys { .. }
         | Command::SeedToSS58 { .. }
         | Command::ContractCodeInfo { .. } => String::new(),
         _ => read_secret(seed, "Provide seed for the signer account:"),
@@ -65,19 +74,23 @@ async fn main() -> anyhow::Result<()> {
     let Config {
         node,
         seed,
+        output,
         command,
     } = Config::parse();
 
     let seed = read_seed(&command, seed);
     let cfg = ConnectionConfig::new(node, seed.clone());
-    match command {
+    let tx_info = match command {
         Command::ChangeValidators {
             change_validators_args,
-        } => change_validators(cfg.get_root_connection().await, change_validators_args).await,
-        Command::PrepareKeys => prepare_keys(cfg.get_root_connection().await).await?,
+        } => Some(change_validators(cfg.get_root_connection().await, change_validators_args).await),
+        Command::PrepareKeys => {
+            prepare_keys(cfg.get_root_connection().await).await?;
+            None
+        }
         Command::Bond {
             initial_stake_tokens,
-        } => bond(cfg.get_signed_connection().await, initial_stake_tokens).await,
+        } => Some(bond(cfg.get_signed_connection().await, initial_stake_tokens).await),
         Command::Finalize {
             block,
             hash,
@@ -86,57 +99,77 @@ async fn main() -> anyhow::Result<()> {
             let finalizer_seed = read_secret(finalizer_seed, "Provide finalizer seed:");
             let finalizer = aleph_keypair_from_string(&finalizer_seed);
             finalize(cfg.get_connection().await, block, hash, finalizer).await;
+            None
         }
         Command::SetEmergencyFinalizer { finalizer_seed } => {
             let finalizer_seed = read_secret(finalizer_seed, "Provide finalizer seed:");
             let finalizer = aleph_keypair_from_string(&finalizer_seed);
             let finalizer = account_from_keypair(&finalizer);
-            set_emergency_finalizer(cfg.get_root_connection().await, finalizer).await;
+            Some(set_emergency_finalizer(cfg.get_root_connection().await, finalizer).await)
         }
         Command::SetKeys { new_keys } => {
-            set_keys(cfg.get_signed_connection().await, new_keys).await
+            Some(set_keys(cfg.get_signed_connection().await, new_keys).await)
         }
         Command::Validate {
             commission_percentage,
-        } => validate(cfg.get_signed_connection().await, commission_percentage).await,
+        } => Some(validate(cfg.get_signed_connection().await, commission_percentage).await),
         Command::TransferKeepAlive {
             amount_in_tokens,
             to_account,
-        } => {
+        } => Some(
             transfer_keep_alive(
                 cfg.get_signed_connection().await,
                 amount_in_tokens,
                 to_account,
             )
-            .await
-        }
+            .await,
+        ),
         Command::TreasuryPropose {
             amount_in_tokens,
             beneficiary,
-        } => {
+        } => Some(
             treasury_propose(
                 cfg.get_signed_connection().await,
                 amount_in_tokens,
                 beneficiary,
             )
-            .await
-        }
+            .await,
+        ),
         Command::TreasuryApprove { proposal_id } => {
-            treasury_approve(cfg.get_root_connection().await, proposal_id).await
+            Some(treasury_approve(cfg.get_root_connection().await, proposal_id).await)
         }
         Command::TreasuryReject { proposal_id } => {
-            treasury_reject(cfg.get_root_connection().await, proposal_id).await
+            Some(treasury_reject(cfg.get_root_connection().await, proposal_id).await)
+        }
+        Command::RotateKeys => {
+            let keys = rotate_keys(cfg.get_connection().await).await?;
+            match output {
+                OutputFormat::Text => info!("Keys rotated, use the following in set_keys: {keys}"),
+                OutputFormat::Json => print_json(&json!({ "keys": keys })),
+            }
+            None
+        }
+        Command::CheckKeys { account_id } => {
+            check_keys(cfg.get_connection().await, account_id).await?;
+            if let OutputFormat::Json = output {
+                print_json(&json!({ "keys_match": true }));
+            }
+            None
         }
-        Command::RotateKeys => rotate_keys(cfg.get_connection().await).await,
         Command::NextSessionKeys { account_id } => {
-            next_session_keys(cfg.get_connection().await, account_id).await
+            let keys = next_session_keys(cfg.get_connection().await, account_id).await?;
+            match output {
+                OutputFormat::Text => println!("{}", serde_json::to_string_pretty(&keys)?),
+                OutputFormat::Json => print_json(&keys),
+            }
+            None
         }
         Command::SetStakingLimits {
             minimal_nominator_stake,
             minimal_validator_stake,
             max_nominators_count,
             max_validators_count,
-        } => {
+        } => Some(
             set_staking_limits(
                 cfg.get_root_connection().await,
                 minimal_nominator_stake,
@@ -144,31 +177,31 @@ async fn main() -> anyhow::Result<()> {
                 max_nominators_count,
                 max_validators_count,
             )
-            .await
-        }
-        Command::ForceNewEra => {
-            force_new_era(cfg.get_root_connection().await).await;
-        }
+            .await,
+        ),
+        Command::ForceNewEra => Some(force_new_era(cfg.get_root_connection().await).await),
         Command::SeedToSS58 { input } => {
             let input = read_secret(input, "Provide seed:");
-            info!(
-                "SS58 Address: {}",
-                keypair_from_string(&input).signer().public().to_string()
-            )
+            let address = keypair_from_string(&input).signer().public().to_string();
+            match output {
+                OutputFormat::Text => info!("SS58 Address: {}", address),
+                OutputFormat::Json => print_json(&json!({ "address": address })),
+            }
+            None
         }
         Command::UpdateRuntime { runtime } => {
-            update_runtime(cfg.get_root_connection().await, runtime).await
+            Some(update_runtime(cfg.get_root_connection().await, runtime).await)
         }
-        Command::Vest => vest(cfg.get_signed_connection().await).await,
+        Command::Vest => Some(vest(cfg.get_signed_connection().await).await?),
         Command::VestOther { vesting_account } => {
-            vest_other(cfg.get_signed_connection().await, vesting_account).await
+            Some(vest_other(cfg.get_signed_connection().await, vesting_account).await?)
         }
         Command::VestedTransfer {
             to_account,
             amount_in_tokens,
             per_block,
             starting_block,
-        } => {
+        } => Some(
             vested_transfer(
                 cfg.get_signed_connection().await,
                 to_account,
@@ -176,63 +209,74 @@ async fn main() -> anyhow::Result<()> {
                 per_block,
                 starting_block,
             )
-            .await
+            .await?,
+        ),
+        Command::Nominate { nominee } => {
+            Some(nominate(cfg.get_signed_connection().await, nominee).await)
         }
-        Command::Nominate { nominee } => nominate(cfg.get_signed_connection().await, nominee).await,
         Command::ContractInstantiateWithCode(command) => {
-            match instantiate_with_code(cfg.get_signed_connection().await, command).await {
-                Ok(result) => println!(
-                    "{}",
-                    serde_json::to_string(&result).expect("Can't encode the result as JSON")
-                ),
-                Err(why) => error!("Contract deployment failed {:?}", why),
-            };
+            let result = instantiate_with_code(cfg.get_signed_connection().await, command)
+                .await
+                .context("Contract deployment failed")?;
+            print_json(&result);
+            None
         }
         Command::ContractUploadCode(command) => {
-            match upload_code(cfg.get_signed_connection().await, command).await {
-                Ok(result) => println!("{result:?}"),
-                Err(why) => error!("Contract upload failed {:?}", why),
-            }
-        }
-        Command::ContractCall(command) => {
-            match call(cfg.get_signed_connection().await, command).await {
-                Ok(result) => println!("{result:?}"),
-                Err(why) => error!("Contract call failed {:?}", why),
-            }
+            let result = upload_code(cfg.get_signed_connection().await, command)
+                .await
+                .context("Contract upload failed")?;
+            print_output(output, &result);
+            None
         }
+        Command::ContractCall(command) => Some(
+            call(cfg.get_signed_connection().await, command)
+                .await
+                .context("Contract call failed")?,
+        ),
         Command::ContractInstantiate(command) => {
-            match instantiate(cfg.get_signed_connection().await, command).await {
-                Ok(result) => println!("{result:?}"),
-                Err(why) => error!("Contract instantiate failed {:?}", why),
-            }
+            let result = instantiate(cfg.get_signed_connection().await, command)
+                .await
+                .context("Contract instantiate failed")?;
+            print_output(output, &result);
+            None
         }
         Command::ContractCodeInfo(command) => {
-            println!(
-                "{:#?}",
-                code_info(cfg.get_connection().await, command).await
-            )
+            print_output(
+                output,
+                &code_info(cfg.get_connection().await, command).await,
+            );
+            None
         }
         Command::ContractRemoveCode(command) => {
-            match remove_code(cfg.get_signed_connection().await, command).await {
-                Ok(result) => println!("{result:?}"),
-                Err(why) => error!("Contract remove code failed {:?}", why),
-            }
+            let result = remove_code(cfg.get_signed_connection().await, command)
+                .await
+                .context("Contract remove code failed")?;
+            print_output(output, &result);
+            None
         }
-        Command::VersionUpgradeSchedule {
+        Command::FinalityVersion(FinalityVersionCommand::Schedule {
             version,
             session: session_for_upgrade,
             expected_state,
-        } => match schedule_upgrade(
-            cfg.get_root_connection().await,
-            version,
-            session_for_upgrade,
-            expected_state,
-        )
-        .await
-        {
-            Ok(_) => {}
-            Err(why) => error!("Unable to schedule an upgrade {:?}", why),
-        },
+        }) => Some(
+            schedule_upgrade(
+                cfg.get_root_connection().await,
+                version,
+                session_for_upgrade,
+                expected_state,
+            )
+            .await
+            .context("Unable to schedule an upgrade")?,
+        ),
+        Command::FinalityVersion(FinalityVersionCommand::Cancel { expected_state }) => Some(
+            cancel_upgrade(cfg.get_root_connection().await, expected_state)
+                .await
+                .context("Unable to cancel an upgrade")?,
+        ),
+    };
+
+    if let (OutputFormat::Json, Some(tx_info)) = (output, tx_info) {
+        print_tx_output(&cfg.get_connection().await, tx_info).await?;
     }
     Ok(())
 }
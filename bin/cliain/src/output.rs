@@ -0,0 +1,123 @@
+use std::fmt::Debug;
+
+use aleph_client::{
+    api::contracts::events::{CodeRemoved, CodeStored, Instantiated},
+    pallet_contracts::wasm::CodeInfo,
+    utility::BlocksApi,
+    Connection, TxInfo,
+};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Format of the results printed by commands.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable logs
+    Text,
+    /// A single JSON document printed to stdout
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct EventOutput {
+    pallet: String,
+    variant: String,
+    /// Fields of the event, decoded with the chain metadata
+    fields: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct TxOutput {
+    #[serde(flatten)]
+    tx_info: TxInfo,
+    events: Vec<EventOutput>,
+}
+
+/// Prints `value` as JSON to stdout.
+pub fn print_json<T: Serialize>(value: &T) {
+    println!(
+        "{}",
+        serde_json::to_string(value).expect("Can't encode the result as JSON")
+    );
+}
+
+/// Prints hashes of a submitted transaction together with the events it emitted as JSON to stdout.
+pub async fn print_tx_output(connection: &Connection, tx_info: TxInfo) -> anyhow::Result<()> {
+    let events = connection
+        .get_tx_events(tx_info)
+        .await?
+        .iter()
+        .map(|event| {
+            let event = event?;
+            Ok(EventOutput {
+                pallet: event.pallet_name().to_string(),
+                variant: event.variant_name().to_string(),
+                fields: serde_json::to_value(event.field_values()?)?,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    print_json(&TxOutput { tx_info, events });
+    Ok(())
+}
+
+/// Results of commands which have a JSON representation.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for CodeStored {
+    fn to_json(&self) -> Value {
+        json!({
+            "code_hash": self.code_hash,
+            "deposit_held": self.deposit_held,
+            "uploader": self.uploader.0,
+        })
+    }
+}
+
+impl ToJson for Instantiated {
+    fn to_json(&self) -> Value {
+        json!({
+            "deployer": self.deployer.0,
+            "contract": self.contract.0,
+        })
+    }
+}
+
+impl ToJson for CodeRemoved {
+    fn to_json(&self) -> Value {
+        json!({
+            "code_hash": self.code_hash,
+            "deposit_released": self.deposit_released,
+            "remover": self.remover.0,
+        })
+    }
+}
+
+impl ToJson for CodeInfo {
+    fn to_json(&self) -> Value {
+        json!({
+            "owner": self.owner.0,
+            "deposit": self.deposit,
+            "refcount": self.refcount,
+            "determinism": format!("{:?}", self.determinism),
+            "code_len": self.code_len,
+        })
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+/// Prints the result of a command, in its debug representation or as JSON.
+pub fn print_output<T: Debug + ToJson>(format: OutputFormat, value: &T) {
+    match format {
+        OutputFormat::Text => println!("{value:#?}"),
+        OutputFormat::Json => print_json(&value.to_json()),
+    }
+}
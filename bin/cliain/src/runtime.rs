@@ -1,11 +1,11 @@
 use std::fs;
 
-use aleph_client::{pallets::system::SystemSudoApi, RootConnection, TxStatus};
+use aleph_client::{pallets::system::SystemSudoApi, RootConnection, TxInfo, TxStatus};
 
-pub async fn update_runtime(connection: RootConnection, runtime: String) {
+pub async fn update_runtime(connection: RootConnection, runtime: String) -> TxInfo {
     let runtime = fs::read(runtime).expect("Runtime file not found");
     connection
         .set_code(runtime, TxStatus::InBlock)
         .await
-        .unwrap();
+        .unwrap()
 }
@@ -1,30 +1,30 @@
 use aleph_client::{
     pallets::staking::{StakingSudoApi, StakingUserApi},
-    AccountId, Balance, RootConnection, SignedConnection, Ss58Codec, TxStatus,
+    AccountId, Balance, RootConnection, SignedConnection, Ss58Codec, TxInfo, TxStatus,
 };
 use primitives::TOKEN;
 
-pub async fn bond(stash_connection: SignedConnection, initial_stake_in_tokens: u32) {
+pub async fn bond(stash_connection: SignedConnection, initial_stake_in_tokens: u32) -> TxInfo {
     let initial_stake = initial_stake_in_tokens as Balance * TOKEN;
     stash_connection
         .bond(initial_stake, TxStatus::Finalized)
         .await
-        .unwrap();
+        .unwrap()
 }
 
-pub async fn validate(connection: SignedConnection, commission_percentage: u8) {
+pub async fn validate(connection: SignedConnection, commission_percentage: u8) -> TxInfo {
     connection
         .validate(commission_percentage, TxStatus::Finalized)
         .await
-        .unwrap();
+        .unwrap()
 }
 
-pub async fn nominate(connection: SignedConnection, nominee: String) {
+pub async fn nominate(connection: SignedConnection, nominee: String) -> TxInfo {
     let nominee_account = AccountId::from_ss58check(&nominee).expect("Address is valid");
     connection
         .nominate(nominee_account, TxStatus::InBlock)
         .await
-        .unwrap();
+        .unwrap()
 }
 
 pub async fn set_staking_limits(
@@ -33,7 +33,7 @@ pub async fn set_staking_limits(
     minimal_validator_stake_tokens: u64,
     max_nominators_count: Option<u32>,
     max_validators_count: Option<u32>,
-) {
+) -> TxInfo {
     root_connection
         .set_staking_config(
             Some(minimal_nominator_stake_tokens as Balance * TOKEN),
@@ -43,12 +43,12 @@ pub async fn set_staking_limits(
             TxStatus::Finalized,
         )
         .await
-        .unwrap();
+        .unwrap()
 }
 
-pub async fn force_new_era(root_connection: RootConnection) {
+pub async fn force_new_era(root_connection: RootConnection) -> TxInfo {
     root_connection
         .force_new_era(TxStatus::Finalized)
         .await
-        .unwrap();
+        .unwrap()
 }
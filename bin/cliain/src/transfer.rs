@@ -1,5 +1,6 @@
 use aleph_client::{
-    pallets::balances::BalanceUserApi, AccountId, Balance, SignedConnection, Ss58Codec, TxStatus,
+    pallets::balances::BalanceUserApi, AccountId, Balance, SignedConnection, Ss58Codec, TxInfo,
+    TxStatus,
 };
 use primitives::TOKEN;
 
@@ -7,7 +8,7 @@ pub async fn transfer_keep_alive(
     connection: SignedConnection,
     amount_in_tokens: u64,
     to_account: String,
-) {
+) -> TxInfo {
     let to_account = AccountId::from_ss58check(&to_account).expect("Address is valid");
     connection
         .transfer_keep_alive(
@@ -16,5 +17,5 @@ pub async fn transfer_keep_alive(
             TxStatus::Finalized,
         )
         .await
-        .unwrap();
+        .unwrap()
 }
@@ -1,5 +1,6 @@
 use aleph_client::{
-    pallets::elections::ElectionsSudoApi, primitives::CommitteeSeats, RootConnection, TxStatus,
+    pallets::elections::ElectionsSudoApi, primitives::CommitteeSeats, RootConnection, TxInfo,
+    TxStatus,
 };
 
 use crate::commands::ChangeValidatorArgs;
@@ -8,8 +9,8 @@ use crate::commands::ChangeValidatorArgs;
 pub async fn change_validators(
     root_connection: RootConnection,
     change_validator_args: ChangeValidatorArgs,
-) {
-    root_connection
+) -> TxInfo {
+    let tx_info = root_connection
         .change_validators(
             change_validator_args.reserved_validators,
             change_validator_args.non_reserved_validators,
@@ -27,4 +28,5 @@ pub async fn change_validators(
     // TODO we need to check state here whether change members actually succeed
     // not only here, but for all cliain commands
     // see https://cardinal-cryptography.atlassian.net/browse/AZ-699
+    tx_info
 }
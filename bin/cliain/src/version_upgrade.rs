@@ -1,17 +1,64 @@
-use aleph_client::{pallets::aleph::AlephSudoApi, RootConnection};
+use aleph_client::{
+    api::aleph::events::{CancelFinalityVersionChange, ScheduleFinalityVersionChange},
+    pallets::{aleph::AlephSudoApi, session::SessionApi},
+    utility::BlocksApi,
+    RootConnection, TxInfo,
+};
+use anyhow::anyhow;
+use log::info;
 use primitives::SessionIndex;
 
 use crate::commands::{ExtrinsicState, Version};
 
+/// Finality version changes have to be scheduled at least that many sessions in advance.
+const MIN_SESSIONS_AHEAD: SessionIndex = 2;
+
 pub async fn schedule_upgrade(
     connection: RootConnection,
     version: Version,
     session_for_upgrade: SessionIndex,
     expected_state: ExtrinsicState,
-) -> anyhow::Result<()> {
-    connection
+) -> anyhow::Result<TxInfo> {
+    let current_session = connection.get_session(None).await;
+    if session_for_upgrade < current_session + MIN_SESSIONS_AHEAD {
+        return Err(anyhow!(
+            "Cannot schedule a finality version change for session {session_for_upgrade}: \
+             it has to be at least {MIN_SESSIONS_AHEAD} sessions after the current session {current_session}"
+        ));
+    }
+
+    let tx_info = connection
         .schedule_finality_version_change(version, session_for_upgrade, expected_state.into())
         .await?;
 
-    Ok(())
+    let events = connection.get_tx_events(tx_info).await?;
+    match events.find_first::<ScheduleFinalityVersionChange>()? {
+        Some(event) => info!(
+            "Scheduled finality version change: version {} at session {}",
+            event.0.version_incoming, event.0.session
+        ),
+        None => return Err(anyhow!("Finality version change was not scheduled")),
+    }
+
+    Ok(tx_info)
+}
+
+pub async fn cancel_upgrade(
+    connection: RootConnection,
+    expected_state: ExtrinsicState,
+) -> anyhow::Result<TxInfo> {
+    let tx_info = connection
+        .cancel_scheduled_finality_version_change(expected_state.into())
+        .await?;
+
+    let events = connection.get_tx_events(tx_info).await?;
+    match events.find_first::<CancelFinalityVersionChange>()? {
+        Some(event) => info!(
+            "Cancelled finality version change: version {} at session {}",
+            event.0.version_incoming, event.0.session
+        ),
+        None => return Err(anyhow!("Finality version change was not cancelled")),
+    }
+
+    Ok(tx_info)
 }
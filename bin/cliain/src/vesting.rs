@@ -1,29 +1,37 @@
 use aleph_client::{
     account_from_keypair, keypair_from_string, pallet_vesting::vesting_info::VestingInfo,
-    pallets::vesting::VestingUserApi, SignedConnection, TxStatus,
+    pallets::vesting::VestingUserApi, SignedConnection, TxInfo, TxStatus,
 };
-use log::{error, info};
+use anyhow::Context;
+use log::info;
 use primitives::{Balance, BlockNumber, TOKEN};
 
 /// Delegates to `aleph_client::vest`.
 ///
 /// Vesting is performed for the signer of `connection`.
-pub async fn vest(connection: SignedConnection) {
-    match connection.vest(TxStatus::Finalized).await {
-        Ok(_) => info!("Vesting has succeeded"),
-        Err(e) => error!("Vesting has failed with:\n {:?}", e),
-    }
+pub async fn vest(connection: SignedConnection) -> anyhow::Result<TxInfo> {
+    let tx_info = connection
+        .vest(TxStatus::Finalized)
+        .await
+        .context("Vesting has failed")?;
+    info!("Vesting has succeeded");
+    Ok(tx_info)
 }
 
 /// Delegates to `aleph_client::vest_other`.
 ///
 /// Vesting is performed by the signer of `connection` for `vesting_account_seed`.
-pub async fn vest_other(connection: SignedConnection, vesting_account_seed: String) {
+pub async fn vest_other(
+    connection: SignedConnection,
+    vesting_account_seed: String,
+) -> anyhow::Result<TxInfo> {
     let vester = account_from_keypair(keypair_from_string(vesting_account_seed.as_str()).signer());
-    match connection.vest_other(TxStatus::Finalized, vester).await {
-        Ok(_) => info!("Vesting on behalf has succeeded"),
-        Err(e) => error!("Vesting on behalf has failed with:\n {:?}", e),
-    }
+    let tx_info = connection
+        .vest_other(TxStatus::Finalized, vester)
+        .await
+        .context("Vesting on behalf has failed")?;
+    info!("Vesting on behalf has succeeded");
+    Ok(tx_info)
 }
 
 /// Delegates to `aleph_client::vested_transfer`.
@@ -37,18 +45,17 @@ pub async fn vested_transfer(
     amount_in_tokens: u64,
     per_block: Balance,
     starting_block: BlockNumber,
-) {
+) -> anyhow::Result<TxInfo> {
     let receiver = account_from_keypair(keypair_from_string(target_seed.as_str()).signer());
     let schedule = VestingInfo {
         locked: amount_in_tokens as Balance * TOKEN,
         per_block,
         starting_block,
     };
-    match connection
+    let tx_info = connection
         .vested_transfer(receiver, schedule, TxStatus::Finalized)
         .await
-    {
-        Ok(_) => info!("Vested transfer has succeeded"),
-        Err(e) => error!("Vested transfer has failed with:\n {:?}", e),
-    }
+        .context("Vested transfer has failed")?;
+    info!("Vested transfer has succeeded");
+    Ok(tx_info)
 }
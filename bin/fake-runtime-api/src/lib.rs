@@ -9,8 +9,9 @@ use pallet_transaction_payment::FeeDetails;
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
 use primitives::{
     crypto::SignatureSet, AccountId, ApiError as AlephApiError, AuraId, AuthorityId as AlephId,
-    AuthoritySignature, Balance, Block, Nonce, Perbill, Score, SessionAuthorityData,
-    SessionCommittee, SessionIndex, SessionValidatorError, Version as FinalityVersion,
+    AuthoritySignature, Balance, Block, BlockCount, Nonce, Perbill, Score, SessionAuthorityData,
+    SessionCommittee, SessionCount, SessionIndex, SessionRangeError, SessionValidatorError,
+    Version as FinalityVersion, VersionChange, VersionedSessionCommittee,
 };
 use sp_consensus_aura::SlotDuration;
 use sp_core::OpaqueMetadata;
@@ -141,6 +142,7 @@ pub mod fake_runtime {
             }
         }
 
+         #[api_version(4)]
          impl crate::AlephSessionApi<Block> for Runtime {
             fn millisecs_per_block() -> u64 {
                 unimplemented!()
@@ -174,12 +176,36 @@ pub mod fake_runtime {
                 unimplemented!()
             }
 
+            fn scheduled_finality_version_change() -> Option<VersionChange> {
+                unimplemented!()
+            }
+
             fn predict_session_committee(
                 _session: SessionIndex,
             ) -> Result<SessionCommittee<AccountId>, SessionValidatorError> {
                 unimplemented!()
             }
 
+            fn predict_session_committee_range(
+                _from: SessionIndex,
+                _to: SessionIndex,
+            ) -> Result<Vec<(SessionIndex, SessionCommittee<AccountId>)>, SessionRangeError> {
+                unimplemented!()
+            }
+
+            fn predict_session_committee_versioned(
+                _session: SessionIndex,
+            ) -> Result<VersionedSessionCommittee<AccountId>, SessionValidatorError> {
+                unimplemented!()
+            }
+
+            fn predict_session_committee_range_versioned(
+                _from: SessionIndex,
+                _to: SessionIndex,
+            ) -> Result<Vec<(SessionIndex, VersionedSessionCommittee<AccountId>)>, SessionRangeError> {
+                unimplemented!()
+            }
+
             fn next_session_aura_authorities() -> Vec<(AccountId, AuraId)> {
                 unimplemented!()
             }
@@ -201,6 +227,22 @@ pub mod fake_runtime {
             }
         }
 
+        impl crate::CommitteeManagementApi<Block> for Runtime {
+            fn underperformance(_account: AccountId) -> SessionCount {
+                unimplemented!()
+            }
+
+            fn blocks_produced_this_session(_account: AccountId) -> BlockCount {
+                unimplemented!()
+            }
+        }
+
+        impl crate::OperationsApi<Block> for Runtime {
+            fn consumers_diff(_account: AccountId) -> i32 {
+                unimplemented!()
+            }
+        }
+
         /// There’s an important remark on how this fake runtime must be implemented - it does not need to
         /// have all the same entries like `impl_runtime_apis!` has - in particular, it does not need an
         /// implementation for
@@ -1,8 +1,12 @@
 use std::path::PathBuf;
 
-use finality_aleph::UnitCreationDelay;
+use finality_aleph::{AdaptiveUnitCreationDelay, UnitCreationDelay};
 use log::warn;
-use primitives::{DEFAULT_MAX_NON_FINALIZED_BLOCKS, DEFAULT_UNIT_CREATION_DELAY};
+use primitives::{
+    DEFAULT_BACKUP_RETAINED_SESSIONS, DEFAULT_MAX_IN_FLIGHT_AGGREGATIONS,
+    DEFAULT_MAX_NON_FINALIZED_BLOCKS, DEFAULT_MAX_UNIT_CREATION_DELAY,
+    DEFAULT_MIN_UNIT_CREATION_DELAY, DEFAULT_UNIT_CREATION_DELAY,
+};
 use sc_cli::clap::{self, ArgGroup, Parser};
 
 #[derive(Debug, Parser, Clone)]
@@ -11,6 +15,20 @@ pub struct AlephCli {
     #[clap(long, default_value_t = DEFAULT_UNIT_CREATION_DELAY)]
     unit_creation_delay: u64,
 
+    /// Adapt the unit creation delay to the round-trip time observed in the validator network
+    /// instead of using the fixed `--unit-creation-delay`. Sustained high latency widens the delay,
+    /// and it recovers towards `--min-unit-creation-delay` when the latency drops.
+    #[clap(long, default_value_t = false)]
+    adaptive_unit_creation_delay: bool,
+
+    /// The lower bound of the adaptive unit creation delay, in milliseconds.
+    #[clap(long, default_value_t = DEFAULT_MIN_UNIT_CREATION_DELAY)]
+    min_unit_creation_delay: u64,
+
+    /// The upper bound of the adaptive unit creation delay, in milliseconds.
+    #[clap(long, default_value_t = DEFAULT_MAX_UNIT_CREATION_DELAY)]
+    max_unit_creation_delay: u64,
+
     /// The addresses at which the node will be externally reachable for validator network
     /// purposes. Have to be provided for validators.
     #[clap(long)]
@@ -20,6 +38,11 @@ pub struct AlephCli {
     #[clap(long, default_value_t = 30343)]
     validator_port: u16,
 
+    /// Offer zstd compression of validator network traffic. Connections are only compressed when
+    /// both ends offer it, so nodes with and without this flag can still talk to each other.
+    #[clap(long, default_value_t = false)]
+    validator_network_compression: bool,
+
     /// Turn off backups, at the cost of limiting crash recoverability.
     ///
     /// If backups are turned off and the node crashes, it most likely will not be able to continue
@@ -35,12 +58,24 @@ pub struct AlephCli {
     #[clap(long, value_name = "PATH", group = "backup")]
     backup_path: Option<PathBuf>,
 
+    /// The number of sessions, including the current one, for which backups are kept. Backups of
+    /// older sessions are removed at the beginning of each session. Values lower than 1 are
+    /// treated as 1.
+    #[clap(long, default_value_t = DEFAULT_BACKUP_RETAINED_SESSIONS)]
+    backup_retained_sessions: u32,
+
     /// The maximum number of nonfinalized blocks, after which block production should be locally
     /// stopped. DO NOT CHANGE THIS, PRODUCING MORE OR FEWER BLOCKS MIGHT BE CONSIDERED MALICIOUS
     /// BEHAVIOUR AND PUNISHED ACCORDINGLY!
     #[clap(long, default_value_t = DEFAULT_MAX_NON_FINALIZED_BLOCKS)]
     max_nonfinalized_blocks: u32,
 
+    /// The maximum number of blocks for which signatures are aggregated at the same time. When
+    /// finalization lags behind, aggregation of newer blocks waits until older ones are finalized,
+    /// which bounds the memory used during long finality stalls.
+    #[clap(long, default_value_t = DEFAULT_MAX_IN_FLIGHT_AGGREGATIONS)]
+    max_in_flight_aggregations: u32,
+
     /// Enable database pruning. It removes older entries in the state-database. Pruning of blocks is not supported.
     /// Note that we only support pruning with ParityDB database backend.
     /// See also `--state-pruning` option for more details.
@@ -66,6 +101,25 @@ impl AlephCli {
         UnitCreationDelay(self.unit_creation_delay)
     }
 
+    pub fn adaptive_unit_creation_delay(&self) -> Option<AdaptiveUnitCreationDelay> {
+        if !self.adaptive_unit_creation_delay {
+            return None;
+        }
+        if self.min_unit_creation_delay > self.max_unit_creation_delay {
+            warn!(
+                "The minimal unit creation delay {} is greater than the maximal one {}, using the maximal one as both bounds.",
+                self.min_unit_creation_delay, self.max_unit_creation_delay
+            );
+        }
+        Some(AdaptiveUnitCreationDelay {
+            min: UnitCreationDelay(
+                self.min_unit_creation_delay
+                    .min(self.max_unit_creation_delay),
+            ),
+            max: UnitCreationDelay(self.max_unit_creation_delay),
+        })
+    }
+
     pub fn external_addresses(&self) -> Vec<String> {
         self.public_validator_addresses.clone().unwrap_or_default()
     }
@@ -78,6 +132,10 @@ impl AlephCli {
         self.validator_port
     }
 
+    pub fn validator_network_compression(&self) -> bool {
+        self.validator_network_compression
+    }
+
     pub fn backup_path(&self) -> Option<PathBuf> {
         self.backup_path.clone()
     }
@@ -86,6 +144,10 @@ impl AlephCli {
         self.no_backup
     }
 
+    pub fn backup_retained_sessions(&self) -> u32 {
+        self.backup_retained_sessions
+    }
+
     pub fn max_nonfinalized_blocks(&self) -> u32 {
         if self.max_nonfinalized_blocks != DEFAULT_MAX_NON_FINALIZED_BLOCKS {
             warn!("Running block production with a value of max-nonfinalized-blocks {}, which is not the default of 20. THIS MIGHT BE CONSIDERED MALICIOUS BEHAVIOUR AND RESULT IN PENALTIES!", self.max_nonfinalized_blocks);
@@ -93,6 +155,10 @@ impl AlephCli {
         self.max_nonfinalized_blocks
     }
 
+    pub fn max_in_flight_aggregations(&self) -> u32 {
+        self.max_in_flight_aggregations
+    }
+
     pub fn enable_pruning(&self) -> bool {
         self.enable_pruning
     }
@@ -1,7 +1,8 @@
 use std::{collections::HashMap, sync::Arc};
 
 use finality_aleph::{
-    AlephJustification, BlockId, Justification, JustificationTranslator, ValidatorAddressCache,
+    AlephJustification, BlockId, CurrentSessionInfo, CurrentSessionTracker,
+    EmergencyJustificationSigner, Justification, JustificationTranslator, ValidatorAddressCache,
     ValidatorAddressingInfo,
 };
 use futures::channel::mpsc;
@@ -11,8 +12,9 @@ use jsonrpsee::{
     types::error::{CallError, ErrorObject},
 };
 use parity_scale_codec::Decode;
-use primitives::{AccountId, Block, BlockHash, BlockNumber, Signature};
+use primitives::{AccountId, AuthorityId, Block, BlockHash, BlockNumber, Signature};
 use sc_client_api::StorageProvider;
+use sc_rpc_api::DenyUnsafe;
 use sp_arithmetic::traits::Zero;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
@@ -61,6 +63,12 @@ pub enum Error {
     /// Network info caching is not enabled.
     #[error("Unable to get any data, because network info caching is not enabled.")]
     NetworkInfoCachingNotEnabled,
+    /// The keystore of the node does not hold the emergency finalizer key.
+    #[error("The keystore does not hold the emergency finalizer key.")]
+    EmergencyFinalizerKeyMissing,
+    /// The block is not on the best chain above the last finalized block.
+    #[error("Block {0} is not on the best chain above the last finalized block.")]
+    BlockNotFinalizable(String),
 }
 
 // Base code for all system errors.
@@ -85,6 +93,10 @@ const FAILED_HEADER_DECODING_ERROR: i32 = BASE_ERROR + 8;
 const UNKNOWN_HASH_ERROR: i32 = BASE_ERROR + 9;
 /// Network info caching is not enabled.
 const NETWORK_INFO_CACHING_NOT_ENABLED_ERROR: i32 = BASE_ERROR + 10;
+/// The keystore does not hold the emergency finalizer key.
+const EMERGENCY_FINALIZER_KEY_MISSING_ERROR: i32 = BASE_ERROR + 11;
+/// The block is not on the best chain above the last finalized block.
+const BLOCK_NOT_FINALIZABLE_ERROR: i32 = BASE_ERROR + 12;
 
 impl From<Error> for JsonRpseeError {
     fn from(e: Error) -> Self {
@@ -145,6 +157,16 @@ impl From<Error> for JsonRpseeError {
                 "Unable to get any data, because network info caching is not enabled.",
                 None::<()>,
             )),
+            Error::EmergencyFinalizerKeyMissing => CallError::Custom(ErrorObject::owned(
+                EMERGENCY_FINALIZER_KEY_MISSING_ERROR,
+                "The keystore does not hold the emergency finalizer key.",
+                None::<()>,
+            )),
+            Error::BlockNotFinalizable(hash) => CallError::Custom(ErrorObject::owned(
+                BLOCK_NOT_FINALIZABLE_ERROR,
+                format!("Block {hash} is not on the best chain above the last finalized block."),
+                None::<()>,
+            )),
         }
         .into()
     }
@@ -162,6 +184,16 @@ pub trait AlephNodeApi<BE> {
         number: BlockNumber,
     ) -> RpcResult<()>;
 
+    /// Finalize the block with given hash and number, signing it with the emergency finalizer key
+    /// held in the keystore of this node. The block has to be on the best chain above the last
+    /// finalized block. Unsafe. Returns the empty string or an error.
+    #[method(name = "emergencyFinalizeWithOwnKey")]
+    fn emergency_finalize_with_own_key(
+        &self,
+        hash: BlockHash,
+        number: BlockNumber,
+    ) -> RpcResult<()>;
+
     /// Get the author of the block with given hash.
     #[method(name = "getBlockAuthor")]
     fn block_author(&self, hash: BlockHash) -> RpcResult<Option<AccountId>>;
@@ -170,6 +202,10 @@ pub trait AlephNodeApi<BE> {
     #[method(name = "ready")]
     fn ready(&self) -> RpcResult<bool>;
 
+    /// Get the ABFT session the node is currently running, or `None` if it has not started any.
+    #[method(name = "currentSession")]
+    fn current_session(&self) -> RpcResult<Option<CurrentSessionInfo>>;
+
     #[method(name = "unstable_validatorNetworkInfo")]
     fn validator_network_info(&self) -> RpcResult<HashMap<AccountId, ValidatorAddressingInfo>>;
 }
@@ -181,6 +217,9 @@ pub struct AlephNode<Client, SO> {
     client: Arc<Client>,
     sync_oracle: SO,
     validator_address_cache: Option<ValidatorAddressCache>,
+    emergency_signer: EmergencyJustificationSigner,
+    current_session: CurrentSessionTracker,
+    deny_unsafe: DenyUnsafe,
 }
 
 impl<Client, SO> AlephNode<Client, SO>
@@ -193,6 +232,9 @@ where
         client: Arc<Client>,
         sync_oracle: SO,
         validator_address_cache: Option<ValidatorAddressCache>,
+        emergency_signer: EmergencyJustificationSigner,
+        current_session: CurrentSessionTracker,
+        deny_unsafe: DenyUnsafe,
     ) -> Self {
         AlephNode {
             import_justification_tx,
@@ -200,8 +242,32 @@ where
             client,
             sync_oracle,
             validator_address_cache,
+            emergency_signer,
+            current_session,
+            deny_unsafe,
         }
     }
+
+    fn import_justification(
+        &self,
+        justification: AlephJustification,
+        hash: BlockHash,
+        number: BlockNumber,
+    ) -> RpcResult<()> {
+        let justification = self
+            .justification_translator
+            .translate(justification, BlockId::new(hash, number))
+            .map_err(|e| Error::FailedJustificationTranslation(format!("{e}")))?;
+        self.import_justification_tx
+            .unbounded_send(justification)
+            .map_err(|_| {
+                Error::FailedJustificationSend(
+                    "AlephNodeApiServer failed to send JustifictionNotification via its channel"
+                        .into(),
+                )
+            })?;
+        Ok(())
+    }
 }
 
 impl<Client, BE, SO> AlephNodeApiServer<BE> for AlephNode<Client, SO>
@@ -222,19 +288,29 @@ where
                     "Provided justification cannot be converted into correct type".into(),
                 )
             })?);
+        self.import_justification(justification, hash, number)
+    }
+
+    fn emergency_finalize_with_own_key(
+        &self,
+        hash: BlockHash,
+        number: BlockNumber,
+    ) -> RpcResult<()> {
+        let best_chain_hash = self.client.hash(number).ok().flatten();
+        ensure_own_key_finalization_allowed(
+            self.deny_unsafe,
+            hash,
+            number,
+            best_chain_hash,
+            self.client.info().finalized_number,
+        )?;
+        let emergency_finalizer: AuthorityId =
+            read_storage("Aleph", "EmergencyFinalizer", &self.client, hash)?;
         let justification = self
-            .justification_translator
-            .translate(justification, BlockId::new(hash, number))
-            .map_err(|e| Error::FailedJustificationTranslation(format!("{e}")))?;
-        self.import_justification_tx
-            .unbounded_send(justification)
-            .map_err(|_| {
-                Error::FailedJustificationSend(
-                    "AlephNodeApiServer failed to send JustifictionNotification via its channel"
-                        .into(),
-                )
-            })?;
-        Ok(())
+            .emergency_signer
+            .sign(emergency_finalizer, hash)
+            .ok_or(Error::EmergencyFinalizerKeyMissing)?;
+        self.import_justification(justification, hash, number)
     }
 
     fn block_author(&self, hash: BlockHash) -> RpcResult<Option<AccountId>> {
@@ -268,6 +344,10 @@ where
         Ok(!self.sync_oracle.is_offline() && !self.sync_oracle.is_major_syncing())
     }
 
+    fn current_session(&self) -> RpcResult<Option<CurrentSessionInfo>> {
+        Ok(self.current_session.current())
+    }
+
     fn validator_network_info(&self) -> RpcResult<HashMap<AccountId, ValidatorAddressingInfo>> {
         self.validator_address_cache
             .as_ref()
@@ -276,6 +356,23 @@ where
     }
 }
 
+/// Signing with the key of the node is only allowed over unsafe RPC, and only for a block on the
+/// best chain (`best_chain_hash` is the hash of the best chain block at `number`) above the last
+/// finalized block.
+fn ensure_own_key_finalization_allowed(
+    deny_unsafe: DenyUnsafe,
+    hash: BlockHash,
+    number: BlockNumber,
+    best_chain_hash: Option<BlockHash>,
+    finalized_number: BlockNumber,
+) -> RpcResult<()> {
+    deny_unsafe.check_if_safe()?;
+    if best_chain_hash != Some(hash) || number <= finalized_number {
+        return Err(Error::BlockNotFinalizable(hash.to_string()).into());
+    }
+    Ok(())
+}
+
 fn read_storage<
     T: Decode,
     Block: BlockT,
@@ -313,3 +410,53 @@ fn read_storage<
         Error::FailedStorageDecoding(pallet, pallet_item, block_hash.to_string(), e).into()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use primitives::BlockHash;
+    use sc_rpc_api::DenyUnsafe;
+
+    use super::ensure_own_key_finalization_allowed;
+
+    #[test]
+    fn allows_best_chain_block_above_finalized() {
+        let hash = BlockHash::repeat_byte(1);
+        assert!(
+            ensure_own_key_finalization_allowed(DenyUnsafe::No, hash, 5, Some(hash), 4).is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_over_safe_rpc() {
+        let hash = BlockHash::repeat_byte(1);
+        assert!(
+            ensure_own_key_finalization_allowed(DenyUnsafe::Yes, hash, 5, Some(hash), 4).is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_block_off_best_chain() {
+        let hash = BlockHash::repeat_byte(1);
+        let best_chain_hash = BlockHash::repeat_byte(2);
+        assert!(ensure_own_key_finalization_allowed(
+            DenyUnsafe::No,
+            hash,
+            5,
+            Some(best_chain_hash),
+            4
+        )
+        .is_err());
+        assert!(ensure_own_key_finalization_allowed(DenyUnsafe::No, hash, 5, None, 4).is_err());
+    }
+
+    #[test]
+    fn rejects_already_finalized_block() {
+        let hash = BlockHash::repeat_byte(1);
+        assert!(
+            ensure_own_key_finalization_allowed(DenyUnsafe::No, hash, 5, Some(hash), 5).is_err()
+        );
+        assert!(
+            ensure_own_key_finalization_allowed(DenyUnsafe::No, hash, 4, Some(hash), 5).is_err()
+        );
+    }
+}
@@ -7,7 +7,10 @@
 
 use std::sync::Arc;
 
-use finality_aleph::{Justification, JustificationTranslator, ValidatorAddressCache};
+use finality_aleph::{
+    CurrentSessionTracker, EmergencyJustificationSigner, Justification, JustificationTranslator,
+    ValidatorAddressCache,
+};
 use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
 use primitives::{AccountId, Balance, Block, Nonce};
@@ -31,6 +34,8 @@ pub struct FullDeps<C, P, SO> {
     pub justification_translator: JustificationTranslator,
     pub sync_oracle: SO,
     pub validator_address_cache: Option<ValidatorAddressCache>,
+    pub emergency_signer: EmergencyJustificationSigner,
+    pub current_session: CurrentSessionTracker,
 }
 
 /// Instantiate all full RPC extensions.
@@ -64,6 +69,8 @@ where
         justification_translator,
         sync_oracle,
         validator_address_cache,
+        emergency_signer,
+        current_session,
     } = deps;
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
@@ -78,6 +85,9 @@ where
             client,
             sync_oracle,
             validator_address_cache,
+            emergency_signer,
+            current_session,
+            deny_unsafe,
         )
         .into_rpc(),
     )?;
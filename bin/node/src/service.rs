@@ -8,9 +8,10 @@ use std::{
 use fake_runtime_api::fake_runtime::RuntimeApi;
 use finality_aleph::{
     build_network, get_aleph_block_import, run_validator_node, AlephConfig, BlockImporter,
-    BuildNetworkOutput, ChannelProvider, FavouriteSelectChainProvider, Justification,
-    JustificationTranslator, MillisecsPerBlock, RateLimiterConfig, RedirectingBlockImport,
-    SessionPeriod, SubstrateChainStatus, SyncOracle, ValidatorAddressCache,
+    BuildNetworkOutput, ChannelProvider, CurrentSessionTracker, EmergencyJustificationSigner,
+    FavouriteSelectChainProvider, Justification, JustificationTranslator, MillisecsPerBlock,
+    RateLimiterConfig, RedirectingBlockImport, SessionPeriod, SubstrateChainStatus, SyncOracle,
+    ValidatorAddressCache,
 };
 use log::warn;
 use pallet_aleph_runtime_api::AlephSessionApi;
@@ -323,15 +324,20 @@ pub fn new_authority(
     let chain_status = SubstrateChainStatus::new(service_components.backend.clone())
         .map_err(|e| ServiceError::Other(format!("failed to set up chain status: {e}")))?;
     let validator_address_cache = get_validator_address_cache(&aleph_config);
+    let current_session = CurrentSessionTracker::new();
     let rpc_builder = {
         let client = service_components.client.clone();
         let pool = service_components.transaction_pool.clone();
         let sync_oracle = sync_oracle.clone();
         let validator_address_cache = validator_address_cache.clone();
+        let current_session = current_session.clone();
         let import_justification_tx = service_components
             .justification_channel_provider
             .get_sender();
         let chain_status = chain_status.clone();
+        let emergency_signer = EmergencyJustificationSigner::new(
+            service_components.keystore_container.local_keystore(),
+        );
         Box::new(move |deny_unsafe, _| {
             let deps = RpcFullDeps {
                 client: client.clone(),
@@ -341,6 +347,8 @@ pub fn new_authority(
                 justification_translator: JustificationTranslator::new(chain_status.clone()),
                 sync_oracle: sync_oracle.clone(),
                 validator_address_cache: validator_address_cache.clone(),
+                emergency_signer: emergency_signer.clone(),
+                current_session: current_session.clone(),
             };
 
             Ok(create_full_rpc(deps)?)
@@ -393,12 +401,17 @@ pub fn new_authority(
         block_rx,
         registry: prometheus_registry,
         unit_creation_delay: aleph_config.unit_creation_delay(),
+        adaptive_unit_creation_delay: aleph_config.adaptive_unit_creation_delay(),
+        max_in_flight_aggregations: aleph_config.max_in_flight_aggregations(),
         backup_saving_path: backup_path,
+        backup_retained_sessions: aleph_config.backup_retained_sessions(),
         external_addresses: aleph_config.external_addresses(),
         validator_port: aleph_config.validator_port(),
+        validator_network_compression: aleph_config.validator_network_compression(),
         rate_limiter_config,
         sync_oracle,
         validator_address_cache,
+        current_session,
         transaction_pool: service_components.transaction_pool,
     };
 
@@ -46,10 +46,11 @@ use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{
     crypto::SignatureSet, staking::MAX_NOMINATORS_REWARDED_PER_VALIDATOR, wrap_methods, Address,
     AlephNodeSessionKeys as SessionKeys, ApiError as AlephApiError, AuraId, AuthorityId as AlephId,
-    AuthoritySignature, BlockNumber as AlephBlockNumber, Header as AlephHeader, Score,
-    SessionAuthorityData, SessionCommittee, SessionIndex, SessionInfoProvider,
-    SessionValidatorError, TotalIssuanceProvider as TotalIssuanceProviderT,
-    Version as FinalityVersion, ADDRESSES_ENCODING, DEFAULT_BAN_REASON_LENGTH, DEFAULT_MAX_WINNERS,
+    AuthoritySignature, BlockCount, BlockNumber as AlephBlockNumber, Header as AlephHeader, Score,
+    SessionAuthorityData, SessionCommittee, SessionCount, SessionIndex, SessionInfoProvider,
+    SessionRangeError, SessionValidatorError, TotalIssuanceProvider as TotalIssuanceProviderT,
+    Version as FinalityVersion, VersionChange, VersionedSessionCommittee, ADDRESSES_ENCODING,
+    DEFAULT_BAN_REASON_LENGTH, DEFAULT_MAX_WINNERS, DEFAULT_MIN_COMMITTEE_SIZE,
     DEFAULT_SESSIONS_PER_ERA, DEFAULT_SESSION_PERIOD, MAX_BLOCK_SIZE, MILLISECS_PER_BLOCK, TOKEN,
 };
 pub use primitives::{AccountId, AccountIndex, Balance, Hash, Nonce, Signature};
@@ -80,10 +81,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("aleph-node"),
     impl_name: create_runtime_str!("aleph-node"),
     authoring_version: 1,
-    spec_version: 16_000_000,
+    spec_version: 17_000_000,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 19,
+    transaction_version: 20,
     state_version: 0,
 };
 
@@ -348,6 +349,7 @@ parameter_types! {
     pub const SessionPeriod: u32 = DEFAULT_SESSION_PERIOD;
     pub const MaximumBanReasonLength: u32 = DEFAULT_BAN_REASON_LENGTH;
     pub const MaxWinners: u32 = DEFAULT_MAX_WINNERS;
+    pub const MinCommitteeSize: u32 = DEFAULT_MIN_COMMITTEE_SIZE;
 }
 
 impl pallet_elections::Config for Runtime {
@@ -356,6 +358,7 @@ impl pallet_elections::Config for Runtime {
     type ValidatorProvider = Staking;
     type MaxWinners = MaxWinners;
     type BannedValidators = CommitteeManagement;
+    type MinCommitteeSize = MinCommitteeSize;
 }
 
 impl pallet_operations::Config for Runtime {
@@ -365,6 +368,8 @@ impl pallet_operations::Config for Runtime {
     type NextKeysSessionProvider = Session;
     type BondedStashProvider = Staking;
     type ContractInfoProvider = Contracts;
+    type MaxFixBatch = ConstU32<100>;
+    type WeightInfo = ();
 }
 
 impl pallet_committee_management::Config for Runtime {
@@ -377,6 +382,7 @@ impl pallet_committee_management::Config for Runtime {
     type FinalityCommitteeManager = Aleph;
     type SessionPeriod = SessionPeriod;
     type AbftScoresProvider = Aleph;
+    type MaxBanBatchSize = ConstU32<100>;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
@@ -735,6 +741,17 @@ parameter_types! {
     pub const DeletionQueueDepth: u32 = 128;
     pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
     pub CodeHashLockupDepositPercent: Perbill = Perbill::from_percent(30);
+    // Fraction of the block weight used as the gas limit of calls made through `ContractsApi`
+    // when the caller does not provide one. It leaves room for other extrinsics in the block,
+    // so dry runs are closer to what can actually be executed.
+    pub ContractsApiGasLimitRatio: Perbill = NORMAL_DISPATCH_RATIO;
+    pub ContractsApiDefaultGasLimit: Weight = ContractsApiGasLimitRatio::get() * BlockWeights::get().max_block;
+}
+
+/// The gas limit used by the contracts runtime API, falling back to the default when the caller
+/// did not provide one.
+fn contracts_api_gas_limit(gas_limit: Option<Weight>) -> Weight {
+    gas_limit.unwrap_or(ContractsApiDefaultGasLimit::get())
 }
 
 // The filter for the runtime calls that are allowed to be executed by contracts.
@@ -1034,6 +1051,9 @@ pub type SignedBlock = generic::SignedBlock<Block>;
 /// BlockId type as expected by this runtime.
 pub type BlockId = generic::BlockId<Block>;
 
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (pallet_committee_management::migrations::v2::Migration<Runtime>,);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -1041,6 +1061,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -1165,6 +1186,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(4)]
     impl pallet_aleph_runtime_api::AlephSessionApi<Block> for Runtime {
         fn millisecs_per_block() -> u64 {
             MILLISECS_PER_BLOCK
@@ -1206,12 +1228,41 @@ impl_runtime_apis! {
             Aleph::next_session_finality_version()
         }
 
+        fn scheduled_finality_version_change() -> Option<VersionChange> {
+            Aleph::scheduled_finality_version_change()
+        }
+
         fn predict_session_committee(
             session: SessionIndex,
         ) -> Result<SessionCommittee<AccountId>, SessionValidatorError> {
             CommitteeManagement::predict_session_committee_for_session(session)
         }
 
+        fn predict_session_committee_range(
+            from: SessionIndex,
+            to: SessionIndex,
+        ) -> Result<Vec<(SessionIndex, SessionCommittee<AccountId>)>, SessionRangeError> {
+            CommitteeManagement::predict_session_committee_for_range(from, to)
+        }
+
+        fn predict_session_committee_versioned(
+            session: SessionIndex,
+        ) -> Result<VersionedSessionCommittee<AccountId>, SessionValidatorError> {
+            CommitteeManagement::predict_session_committee_for_session(session).map(Into::into)
+        }
+
+        fn predict_session_committee_range_versioned(
+            from: SessionIndex,
+            to: SessionIndex,
+        ) -> Result<Vec<(SessionIndex, VersionedSessionCommittee<AccountId>)>, SessionRangeError> {
+            CommitteeManagement::predict_session_committee_for_range(from, to).map(|committees| {
+                committees
+                    .into_iter()
+                    .map(|(session, committee)| (session, committee.into()))
+                    .collect()
+            })
+        }
+
         fn next_session_aura_authorities() -> Vec<(AccountId, AuraId)> {
             let queued_keys = QueuedKeys::<Runtime>::get();
 
@@ -1245,6 +1296,23 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_aleph_runtime_api::CommitteeManagementApi<Block> for Runtime {
+        fn underperformance(account: AccountId) -> SessionCount {
+            CommitteeManagement::underperformed_producer_session_count(account)
+        }
+
+        fn blocks_produced_this_session(account: AccountId) -> BlockCount {
+            pallet_committee_management::SessionValidatorBlockCount::<Runtime>::get(account)
+        }
+    }
+
+    impl pallet_aleph_runtime_api::OperationsApi<Block> for Runtime {
+        fn consumers_diff(account: AccountId) -> i32 {
+            let (current, expected) = Operations::diagnose_consumers(&account);
+            expected as i32 - current as i32
+        }
+    }
+
     impl pallet_nomination_pools_runtime_api::NominationPoolsApi<Block, AccountId, Balance> for Runtime {
         fn pending_rewards(member: AccountId) -> Balance {
             NominationPools::api_pending_rewards(member).unwrap_or_default()
@@ -1280,7 +1348,7 @@ impl_runtime_apis! {
             storage_deposit_limit: Option<Balance>,
             input_data: Vec<u8>,
         ) -> pallet_contracts::ContractExecResult<Balance, EventRecord> {
-            let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+            let gas_limit = contracts_api_gas_limit(gas_limit);
             Contracts::bare_call(
                 origin,
                 dest,
@@ -1304,7 +1372,7 @@ impl_runtime_apis! {
             salt: Vec<u8>,
         ) -> pallet_contracts::ContractInstantiateResult<AccountId, Balance, EventRecord>
         {
-            let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+            let gas_limit = contracts_api_gas_limit(gas_limit);
             Contracts::bare_instantiate(
                 origin,
                 value,
@@ -1420,6 +1488,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn contracts_api_default_gas_limit_is_fraction_of_block_weight() {
+        let max_block = BlockWeights::get().max_block;
+        let gas_limit = contracts_api_gas_limit(None);
+        assert_eq!(gas_limit, NORMAL_DISPATCH_RATIO * max_block);
+        assert!(gas_limit.all_lt(max_block));
+    }
+
+    #[test]
+    fn contracts_api_uses_provided_gas_limit() {
+        let gas_limit = Weight::from_parts(43, 43);
+        assert_eq!(contracts_api_gas_limit(Some(gas_limit)), gas_limit);
+    }
+
+    #[test]
+    fn contracts_api_call_without_gas_limit_uses_default() {
+        use pallet_contracts::runtime_decl_for_contracts_api::ContractsApiV2;
+        use sp_io::TestExternalities;
+
+        TestExternalities::default().execute_with(|| {
+            let origin = AccountId::from([1; 32]);
+            let dest = AccountId::from([2; 32]);
+            let through_api = <Runtime as ContractsApiV2<_, _, _, _, _, _>>::call(
+                origin.clone(),
+                dest.clone(),
+                0,
+                None,
+                None,
+                vec![],
+            );
+            let with_default = Contracts::bare_call(
+                origin,
+                dest,
+                0,
+                NORMAL_DISPATCH_RATIO * BlockWeights::get().max_block,
+                None,
+                vec![],
+                pallet_contracts::DebugInfo::UnsafeDebug,
+                pallet_contracts::CollectEvents::UnsafeCollect,
+                pallet_contracts::Determinism::Enforced,
+            );
+            assert_eq!(through_api.encode(), with_default.encode());
+        });
+    }
+
     #[test]
     // This test is to make sure that we don't break call-runtime.
     fn test_staking_pallet_index() {
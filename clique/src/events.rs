@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use futures::channel::mpsc;
+
+use crate::{PeerAddressInfo, PublicKey};
+
+/// A change in the state of the connection with a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent<PK: PublicKey> {
+    /// A connection with the peer was established, it is now reachable.
+    Connected(PK, PeerAddressInfo),
+    /// The connection with the peer was lost or closed.
+    Disconnected(PK),
+}
+
+/// Keeps track of the peers we are connected to and reports every change to the subscribers,
+/// exactly once per change.
+pub struct ConnectionEvents<PK: PublicKey> {
+    connected: HashSet<PK>,
+    subscribers: Vec<mpsc::UnboundedSender<ConnectionEvent<PK>>>,
+}
+
+impl<PK: PublicKey> ConnectionEvents<PK> {
+    pub fn new() -> Self {
+        ConnectionEvents {
+            connected: HashSet::new(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Returns a stream of all future connection events.
+    pub fn subscribe(&mut self) -> mpsc::UnboundedReceiver<ConnectionEvent<PK>> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Peers that are currently considered connected.
+    pub fn connected_peers(&self) -> impl Iterator<Item = &PK> {
+        self.connected.iter()
+    }
+
+    /// Marks the peer as connected, reporting it unless it already was.
+    pub fn connected(&mut self, peer: PK, address: PeerAddressInfo) {
+        if self.connected.insert(peer.clone()) {
+            self.report(ConnectionEvent::Connected(peer, address));
+        }
+    }
+
+    /// Marks the peer as disconnected, reporting it unless it already was.
+    pub fn disconnected(&mut self, peer: &PK) {
+        if self.connected.remove(peer) {
+            self.report(ConnectionEvent::Disconnected(peer.clone()));
+        }
+    }
+
+    fn report(&mut self, event: ConnectionEvent<PK>) {
+        self.subscribers
+            .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionEvent::*, ConnectionEvents};
+    use crate::mock::{key, MockPublicKey};
+
+    #[test]
+    fn reports_each_transition_once() {
+        let mut events = ConnectionEvents::<MockPublicKey>::new();
+        let mut receiver = events.subscribe();
+        let (peer, _) = key();
+        let address = String::from("43.43.43.43:43000");
+
+        events.connected(peer.clone(), address.clone());
+        events.connected(peer.clone(), address.clone());
+        events.disconnected(&peer);
+        events.disconnected(&peer);
+        events.connected(peer.clone(), address.clone());
+
+        assert_eq!(
+            receiver.try_next().unwrap(),
+            Some(Connected(peer.clone(), address.clone()))
+        );
+        assert_eq!(
+            receiver.try_next().unwrap(),
+            Some(Disconnected(peer.clone()))
+        );
+        assert_eq!(receiver.try_next().unwrap(), Some(Connected(peer, address)));
+        assert!(receiver.try_next().is_err());
+    }
+
+    #[test]
+    fn ignores_unknown_disconnects_and_dropped_subscribers() {
+        let mut events = ConnectionEvents::<MockPublicKey>::new();
+        let dropped = events.subscribe();
+        let mut receiver = events.subscribe();
+        drop(dropped);
+        let (peer, _) = key();
+
+        events.disconnected(&peer);
+        assert!(receiver.try_next().is_err());
+        events.connected(peer.clone(), String::from("43.43.43.43:43000"));
+        assert_eq!(events.subscribers.len(), 1);
+        assert!(matches!(receiver.try_next(), Ok(Some(Connected(_, _)))));
+    }
+}
@@ -4,8 +4,11 @@ use futures::channel::{mpsc, oneshot};
 use log::{debug, info};
 
 use crate::{
+    latency::LatencyTracker,
     metrics::Metrics,
-    protocols::{protocol, ProtocolError, ProtocolNegotiationError, ResultForService},
+    protocols::{
+        protocol, Compression, KeepAlive, ProtocolError, ProtocolNegotiationError, ResultForService,
+    },
     Data, PublicKey, SecretKey, Splittable, LOG_TARGET,
 };
 
@@ -36,6 +39,7 @@ impl<PK: PublicKey> From<ProtocolError<PK>> for IncomingError<PK> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
     secret_key: SK,
     stream: S,
@@ -43,6 +47,9 @@ async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
     data_for_user: mpsc::UnboundedSender<D>,
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
 ) -> Result<(), IncomingError<SK::PublicKey>> {
     debug!(
         target: LOG_TARGET,
@@ -58,6 +65,9 @@ async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
             data_for_user,
             authorization_requests_sender,
             metrics,
+            latency,
+            compression,
+            keep_alive,
         )
         .await?)
 }
@@ -67,6 +77,7 @@ async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
 /// process ends. Whenever data arrives on this connection it will be passed to the user. Any
 /// failures in receiving data result in the process stopping, we assume the other side will
 /// reestablish it if necessary.
+#[allow(clippy::too_many_arguments)]
 pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     secret_key: SK,
     stream: S,
@@ -74,6 +85,9 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     data_for_user: mpsc::UnboundedSender<D>,
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
 ) {
     let addr = stream.peer_address_info();
     if let Err(e) = manage_incoming(
@@ -83,6 +97,9 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
         data_for_user,
         authorization_requests_sender,
         metrics,
+        latency,
+        compression,
+        keep_alive,
     )
     .await
     {
@@ -80,13 +80,12 @@ pub async fn send_data<S: AsyncWriteExt + Unpin, D: Data>(
     if len > MAX_DATA_SIZE {
         return Err(Error::DataTooLong(len).into());
     }
-    let encoded_len = len.to_le_bytes().to_vec();
+    // Write the length and the data at once, so that streams processing every write
+    // separately (e.g. compressing ones) see the whole message.
+    let mut message = len.to_le_bytes().to_vec();
+    message.extend(encoded);
     stream
-        .write_all(&encoded_len)
-        .await
-        .map_err(Error::ConnectionClosed)?;
-    stream
-        .write_all(&encoded)
+        .write_all(&message)
         .await
         .map_err(Error::ConnectionClosed)?;
     Ok(stream)
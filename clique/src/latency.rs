@@ -0,0 +1,78 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Weight of a new sample in the smoothed round-trip time, the same as used for TCP.
+const NEW_SAMPLE_WEIGHT: f64 = 0.125;
+
+/// Keeps a smoothed estimate of the round-trip time to the peers, based on the time it took them
+/// to answer our handshake challenge and to echo our heartbeats. Clones share the estimate.
+#[derive(Clone, Default)]
+pub struct LatencyTracker {
+    smoothed_round_trip: Arc<Mutex<Option<Duration>>>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include a new round-trip time sample in the estimate.
+    pub fn report(&self, round_trip: Duration) {
+        let mut smoothed_round_trip = self
+            .smoothed_round_trip
+            .lock()
+            .expect("the lock is never poisoned");
+        *smoothed_round_trip = Some(match *smoothed_round_trip {
+            Some(smoothed) => {
+                smoothed.mul_f64(1.0 - NEW_SAMPLE_WEIGHT) + round_trip.mul_f64(NEW_SAMPLE_WEIGHT)
+            }
+            None => round_trip,
+        });
+    }
+
+    /// The current estimate of the round-trip time, `None` if there were no samples yet.
+    pub fn round_trip(&self) -> Option<Duration> {
+        *self
+            .smoothed_round_trip
+            .lock()
+            .expect("the lock is never poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::LatencyTracker;
+
+    #[test]
+    fn no_estimate_without_samples() {
+        assert_eq!(LatencyTracker::new().round_trip(), None);
+    }
+
+    #[test]
+    fn first_sample_is_the_estimate() {
+        let tracker = LatencyTracker::new();
+        tracker.report(Duration::from_millis(100));
+        assert_eq!(tracker.round_trip(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn sustained_latency_moves_the_estimate() {
+        let tracker = LatencyTracker::new();
+        let shared = tracker.clone();
+        tracker.report(Duration::from_millis(100));
+        for _ in 0..100 {
+            tracker.report(Duration::from_millis(500));
+        }
+        let high = shared.round_trip().expect("there were samples");
+        assert!(high > Duration::from_millis(490));
+        for _ in 0..100 {
+            tracker.report(Duration::from_millis(50));
+        }
+        let low = shared.round_trip().expect("there were samples");
+        assert!(low < Duration::from_millis(60));
+    }
+}
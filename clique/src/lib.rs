@@ -1,8 +1,9 @@
 //! A network for maintaining direct connections between all nodes.
 
 use std::{
-    fmt::{Debug, Display},
+    fmt::{Debug, Display, Error as FmtError, Formatter},
     hash::Hash,
+    io::{Error as IoError, ErrorKind},
     pin::Pin,
 };
 
@@ -10,21 +11,33 @@ use parity_scale_codec::Codec;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 mod crypto;
+mod events;
 mod incoming;
 mod io;
+mod latency;
 mod manager;
 pub mod metrics;
 pub mod mock;
 mod outgoing;
 mod protocols;
+mod queue;
 mod rate_limiting;
 mod service;
 #[cfg(test)]
 mod testing;
+#[cfg(feature = "tls")]
+mod tls;
 
 pub use crypto::{PublicKey, SecretKey};
+pub use events::ConnectionEvent;
+pub use latency::LatencyTracker;
+pub use protocols::{Compression, KeepAlive};
 pub use rate_limiting::{RateLimitingDialer, RateLimitingListener};
 pub use service::{Service, SpawnHandleExt, SpawnHandleT};
+#[cfg(feature = "tls")]
+pub use tls::{
+    TlsDialer, TlsDialerError, TlsError, TlsHalf, TlsIdentity, TlsListener, TlsListenerError,
+};
 
 const LOG_TARGET: &str = "network-clique";
 /// A basic alias for properties we expect basic data to satisfy.
@@ -32,20 +45,31 @@ pub trait Data: Clone + Codec + Send + Sync + 'static {}
 
 impl<D: Clone + Codec + Send + Sync + 'static> Data for D {}
 
+/// Separates the beginning and the end of shortened ids, a horizontal ellipsis.
+const SHORT_ID_ELLIPSIS: char = '\u{2026}';
+
 /// Represents the id of an arbitrary node.
 pub trait PeerId: PartialEq + Eq + Clone + Debug + Display + Hash + Codec + Send {
+    /// Number of characters from the beginning of the id kept by `to_short_string`.
+    const SHORT_ID_PREFIX: usize = 4;
+    /// Number of characters from the end of the id kept by `to_short_string`.
+    const SHORT_ID_SUFFIX: usize = 8;
+
     /// This function is used for logging. It implements a shorter version of `to_string` for ids implementing display.
+    /// Ids not longer than the kept prefix and suffix together are returned in full.
     fn to_short_string(&self) -> String {
         let id = format!("{self}");
-        if id.len() <= 12 {
+        let chars: Vec<char> = id.chars().collect();
+        if chars.len() <= Self::SHORT_ID_PREFIX + Self::SHORT_ID_SUFFIX {
             return id;
         }
 
-        let prefix: String = id.chars().take(4).collect();
+        let prefix: String = chars[..Self::SHORT_ID_PREFIX].iter().collect();
+        let suffix: String = chars[chars.len() - Self::SHORT_ID_SUFFIX..]
+            .iter()
+            .collect();
 
-        let suffix: String = id.chars().skip(id.len().saturating_sub(8)).collect();
-
-        format!("{}…{}", &prefix, &suffix)
+        format!("{prefix}{SHORT_ID_ELLIPSIS}{suffix}")
     }
 }
 
@@ -91,6 +115,20 @@ pub trait Network<PK: PublicKey, A: Data, D: Data>: Send + 'static {
     /// This function should be implemented in a non-blocking manner.
     fn send(&self, data: D, recipient: PK);
 
+    /// Send the same message to multiple peers. Nothing is sent if there are no recipients, and
+    /// failing to reach some of the recipients does not prevent sending to the others.
+    /// Implementations should override it if they can avoid handling the data separately for
+    /// every recipient.
+    fn send_to_multiple(&self, data: D, recipients: Vec<PK>) {
+        let mut recipients = recipients.into_iter().peekable();
+        while let Some(recipient) = recipients.next() {
+            match recipients.peek() {
+                Some(_) => self.send(data.clone(), recipient),
+                None => return self.send(data, recipient),
+            }
+        }
+    }
+
     /// Receive a message from the network.
     /// This method's implementation must be cancellation safe.
     async fn next(&mut self) -> Option<D>;
@@ -113,11 +151,49 @@ pub trait Splittable: AsyncWrite + AsyncRead + ConnectionInfo + Unpin + Send {
     fn split(self) -> (Self::Sender, Self::Receiver);
 }
 
+/// Reasons for failing to connect to a peer, shared by all dialers so that the failures can be
+/// handled differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialError {
+    /// None of the addresses of the peer could be resolved or connected to.
+    Unreachable,
+    /// The peer presented a different identity than the one we wanted to connect to.
+    InvalidIdentity,
+    /// Connecting took too long.
+    Timeout,
+    /// Any other failure.
+    Other(String),
+}
+
+impl Display for DialError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use DialError::*;
+        match self {
+            Unreachable => write!(f, "peer unreachable"),
+            InvalidIdentity => write!(f, "peer presented an invalid identity"),
+            Timeout => write!(f, "timed out"),
+            Other(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<IoError> for DialError {
+    fn from(e: IoError) -> Self {
+        use ErrorKind::*;
+        match e.kind() {
+            ConnectionRefused | ConnectionReset | ConnectionAborted | NotConnected
+            | AddrNotAvailable | NotFound => DialError::Unreachable,
+            TimedOut => DialError::Timeout,
+            _ => DialError::Other(e.to_string()),
+        }
+    }
+}
+
 /// Can use addresses to connect to a peer.
 #[async_trait::async_trait]
 pub trait Dialer<A: Data>: Clone + Send + 'static {
     type Connection: Splittable;
-    type Error: Display + Send;
+    type Error: Display + Send + Into<DialError>;
 
     /// Attempt to connect to a peer using the provided addressing information.
     async fn connect(&mut self, address: A) -> Result<Self::Connection, Self::Error>;
@@ -245,3 +321,78 @@ impl<
         (self.1, self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::{Display, Error as FmtError, Formatter},
+        io::{Error as IoError, ErrorKind},
+    };
+
+    use parity_scale_codec::{Decode, Encode};
+
+    use crate::{DialError, PeerId};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+    struct TestId(String);
+
+    impl Display for TestId {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl PeerId for TestId {}
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+    struct ShortTestId(String);
+
+    impl Display for ShortTestId {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl PeerId for ShortTestId {
+        const SHORT_ID_PREFIX: usize = 2;
+        const SHORT_ID_SUFFIX: usize = 3;
+    }
+
+    #[test]
+    fn shortens_long_ids() {
+        let id = TestId(String::from(
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        ));
+        assert_eq!(id.to_short_string(), "5Grw\u{2026}oHGKutQY");
+        let id = ShortTestId(String::from("abcdefgh"));
+        assert_eq!(id.to_short_string(), "ab\u{2026}fgh");
+    }
+
+    #[test]
+    fn keeps_short_ids_whole() {
+        let id = TestId(String::from("abcdefghijkl"));
+        assert_eq!(id.to_short_string(), "abcdefghijkl");
+        let id = ShortTestId(String::from("abcde"));
+        assert_eq!(id.to_short_string(), "abcde");
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let id = TestId(String::from("ąęśćżźńółĄĘŚĆ"));
+        assert_eq!(id.to_short_string(), "ąęść\u{2026}źńółĄĘŚĆ");
+        let id = TestId(String::from("ąęśćżźńółĄĘŚ"));
+        assert_eq!(id.to_short_string(), "ąęśćżźńółĄĘŚ");
+    }
+
+    #[test]
+    fn classifies_io_dial_errors() {
+        let error = |kind| DialError::from(IoError::from(kind));
+        assert_eq!(error(ErrorKind::ConnectionRefused), DialError::Unreachable);
+        assert_eq!(error(ErrorKind::AddrNotAvailable), DialError::Unreachable);
+        assert_eq!(error(ErrorKind::TimedOut), DialError::Timeout);
+        assert!(matches!(
+            error(ErrorKind::PermissionDenied),
+            DialError::Other(_)
+        ));
+    }
+}
@@ -3,9 +3,7 @@ use std::{
     fmt::{Display, Error as FmtError, Formatter},
 };
 
-use futures::channel::mpsc;
-
-use crate::{metrics::Metrics, Data, PeerId, PublicKey};
+use crate::{metrics::Metrics, queue::QueueSender, Data, PeerId, PublicKey};
 
 mod direction;
 use direction::DirectedPeers;
@@ -158,19 +156,31 @@ pub struct Manager<PK: PublicKey + PeerId, A: Data, D: Data> {
     // Which peers we want to be connected with, and which way.
     wanted: DirectedPeers<PK, A>,
     // This peers we are connected with. We ensure that this is always a subset of what we want.
-    have: HashMap<PK, mpsc::UnboundedSender<D>>,
+    have: HashMap<PK, QueueSender<D>>,
+    // How many messages can wait for a single peer before the oldest ones get dropped.
+    max_queue_depth: Option<usize>,
+    metrics: Metrics,
 }
 
 impl<PK: PublicKey + PeerId, A: Data, D: Data> Manager<PK, A, D> {
     /// Create a new Manager with empty list of peers.
     pub fn new(own_id: PK, metrics: Metrics) -> Self {
         Manager {
-            wanted: DirectedPeers::new(own_id, metrics),
+            wanted: DirectedPeers::new(own_id, metrics.clone()),
             have: HashMap::new(),
+            max_queue_depth: None,
+            metrics,
         }
     }
 
-    fn active_connection(&self, peer_id: &PK) -> bool {
+    /// Limit the number of messages waiting to be sent to a single peer, dropping the oldest
+    /// ones when a peer cannot keep up.
+    pub fn set_max_queue_depth(&mut self, max_queue_depth: usize) {
+        self.max_queue_depth = Some(max_queue_depth);
+    }
+
+    /// Whether we have a working connection with the peer.
+    pub fn active_connection(&self, peer_id: &PK) -> bool {
         self.have
             .get(peer_id)
             .map(|sender| !sender.is_closed())
@@ -192,11 +202,7 @@ impl<PK: PublicKey + PeerId, A: Data, D: Data> Manager<PK, A, D> {
     }
 
     /// Add an established connection with a known peer, but only if the peer is among the peers we want to be connected to.
-    pub fn add_connection(
-        &mut self,
-        peer_id: PK,
-        data_for_network: mpsc::UnboundedSender<D>,
-    ) -> AddResult {
+    pub fn add_connection(&mut self, peer_id: PK, data_for_network: QueueSender<D>) -> AddResult {
         use AddResult::*;
         if !self.wanted.interested(&peer_id) {
             return Uninterested;
@@ -218,11 +224,62 @@ impl<PK: PublicKey + PeerId, A: Data, D: Data> Manager<PK, A, D> {
     /// Returns error if there is no outgoing connection to the peer,
     /// or if the connection is dead.
     pub fn send_to(&mut self, peer_id: &PK, data: D) -> Result<(), SendError> {
+        let data_for_network = self.have.get(peer_id).ok_or(SendError::PeerNotFound)?;
+        Self::send_queued(data_for_network, data, self.max_queue_depth, &self.metrics)
+    }
+
+    fn send_queued(
+        data_for_network: &QueueSender<D>,
+        data: D,
+        max_queue_depth: Option<usize>,
+        metrics: &Metrics,
+    ) -> Result<(), SendError> {
+        let dropped = data_for_network
+            .send_dropping_oldest(data, max_queue_depth.unwrap_or(usize::MAX))
+            .map_err(|_| SendError::ConnectionClosed)?;
+        if dropped > 0 {
+            metrics.report_dropped_messages(dropped as u64);
+        }
+        Ok(())
+    }
+
+    /// Close all established connections, letting them send the messages still waiting in their
+    /// queues. Returns the queues, which report being closed once their connections finish.
+    pub fn close_connections(&mut self) -> Vec<QueueSender<D>> {
         self.have
-            .get(peer_id)
-            .ok_or(SendError::PeerNotFound)?
-            .unbounded_send(data)
-            .map_err(|_| SendError::ConnectionClosed)
+            .drain()
+            .map(|(_, data_for_network)| {
+                data_for_network.close();
+                data_for_network
+            })
+            .collect()
+    }
+
+    /// Number of messages waiting to be sent to the peer, None if we are not connected to it.
+    pub fn queue_depth(&self, peer_id: &PK) -> Option<usize> {
+        self.have.get(peer_id).map(|sender| sender.depth())
+    }
+
+    /// Send data to multiple peers, skipping the ones it cannot be sent to.
+    /// Returns the peers that could not be reached, together with the reasons.
+    pub fn send_to_multiple(&mut self, peer_ids: Vec<PK>, data: D) -> Vec<(PK, SendError)> {
+        let mut failures = Vec::new();
+        for peer_id in peer_ids {
+            match self.have.get(&peer_id) {
+                Some(data_for_network) => {
+                    if let Err(e) = Self::send_queued(
+                        data_for_network,
+                        data.clone(),
+                        self.max_queue_depth,
+                        &self.metrics,
+                    ) {
+                        failures.push((peer_id, e));
+                    }
+                }
+                None => failures.push((peer_id, SendError::PeerNotFound)),
+            }
+        }
+        failures
     }
 
     /// A status of the manager, to be displayed somewhere.
@@ -237,12 +294,13 @@ impl<PK: PublicKey + PeerId, A: Data, D: Data> Manager<PK, A, D> {
 
 #[cfg(test)]
 mod tests {
-    use futures::{channel::mpsc, StreamExt};
+    use futures::StreamExt;
 
     use super::{AddResult::*, Manager, SendError};
     use crate::{
         metrics::Metrics,
         mock::{key, MockPublicKey},
+        queue::queue,
     };
 
     type Data = String;
@@ -286,7 +344,7 @@ mod tests {
             Manager::<MockPublicKey, Address, Data>::new(listening_id.clone(), Metrics::noop());
         let data = String::from("DATA");
         let address = String::from("43.43.43.43:43000");
-        let (tx, _rx) = mpsc::unbounded();
+        let (tx, _rx) = queue();
         // try add unknown peer
         assert_eq!(
             connecting_manager.add_connection(listening_id.clone(), tx),
@@ -308,7 +366,7 @@ mod tests {
             assert!(connecting_manager.add_peer(listening_id.clone(), address.clone()));
         }
         // add outgoing to connecting
-        let (tx, mut rx) = mpsc::unbounded();
+        let (tx, mut rx) = queue();
         assert_eq!(
             connecting_manager.add_connection(listening_id.clone(), tx),
             Added
@@ -319,7 +377,7 @@ mod tests {
             .is_ok());
         assert_eq!(data, rx.next().await.expect("should receive"));
         // add incoming to listening
-        let (tx, mut rx) = mpsc::unbounded();
+        let (tx, mut rx) = queue();
         assert_eq!(
             listening_manager.add_connection(connecting_id.clone(), tx),
             Added
@@ -334,4 +392,57 @@ mod tests {
         // receiving should fail
         assert!(rx.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn send_to_multiple() {
+        let (own_id, _) = key();
+        let mut manager = Manager::<MockPublicKey, Address, Data>::new(own_id, Metrics::noop());
+        let data = String::from("DATA");
+        let address = String::from("43.43.43.43:43000");
+        let mut receivers = Vec::new();
+        let mut peer_ids = Vec::new();
+        for _ in 0..2 {
+            let (peer_id, _) = key();
+            manager.add_peer(peer_id.clone(), address.clone());
+            let (tx, rx) = queue();
+            assert_eq!(manager.add_connection(peer_id.clone(), tx), Added);
+            receivers.push(rx);
+            peer_ids.push(peer_id);
+        }
+        let (unknown_id, _) = key();
+        // no recipients, nothing sent
+        assert!(manager.send_to_multiple(vec![], data.clone()).is_empty());
+        for peer_id in &peer_ids {
+            assert_eq!(manager.queue_depth(peer_id), Some(0));
+        }
+        // unknown recipient does not prevent delivery to the known ones
+        let recipients = vec![peer_ids[0].clone(), unknown_id.clone(), peer_ids[1].clone()];
+        assert_eq!(
+            manager.send_to_multiple(recipients, data.clone()),
+            vec![(unknown_id, SendError::PeerNotFound)]
+        );
+        for rx in receivers.iter_mut() {
+            assert_eq!(data, rx.next().await.expect("should receive"));
+        }
+    }
+
+    #[tokio::test]
+    async fn bounded_queue_drops_oldest() {
+        let (own_id, _) = key();
+        let mut manager = Manager::<MockPublicKey, Address, Data>::new(own_id, Metrics::noop());
+        manager.set_max_queue_depth(2);
+        let (peer_id, _) = key();
+        let (unknown_id, _) = key();
+        manager.add_peer(peer_id.clone(), String::from("43.43.43.43:43000"));
+        let (tx, mut rx) = queue();
+        assert_eq!(manager.add_connection(peer_id.clone(), tx), Added);
+        assert_eq!(manager.queue_depth(&unknown_id), None);
+        for data in ["A", "B", "C"] {
+            assert!(manager.send_to(&peer_id, String::from(data)).is_ok());
+        }
+        assert_eq!(manager.queue_depth(&peer_id), Some(2));
+        assert_eq!(rx.next().await, Some(String::from("B")));
+        assert_eq!(rx.next().await, Some(String::from("C")));
+        assert_eq!(manager.queue_depth(&peer_id), Some(0));
+    }
 }
@@ -1,4 +1,6 @@
-use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, U64};
+use substrate_prometheus_endpoint::{
+    register, Counter, CounterVec, Gauge, Opts, PrometheusError, Registry, U64,
+};
 
 #[derive(Clone)]
 pub enum Metrics {
@@ -7,10 +9,31 @@ pub enum Metrics {
         missing_incoming_connections: Gauge<U64>,
         outgoing_connections: Gauge<U64>,
         missing_outgoing_connections: Gauge<U64>,
+        dropped_messages: Counter<U64>,
+        sent_bytes: CounterVec<U64>,
+        received_bytes: CounterVec<U64>,
+        connected_peers: Gauge<U64>,
+        failed_dials: Counter<U64>,
     },
     Noop,
 }
 
+/// Which side initiated a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+}
+
+impl Direction {
+    fn label(&self) -> &'static str {
+        match self {
+            Direction::Incoming => "incoming",
+            Direction::Outgoing => "outgoing",
+        }
+    }
+}
+
 pub enum Event {
     NewOutgoing,
     NewIncoming,
@@ -54,6 +77,47 @@ impl Metrics {
                     )?,
                     &registry,
                 )?,
+                dropped_messages: register(
+                    Counter::new(
+                        "clique_network_dropped_messages",
+                        "messages dropped because the queue for a peer was full",
+                    )?,
+                    &registry,
+                )?,
+                sent_bytes: register(
+                    CounterVec::new(
+                        Opts::new(
+                            "clique_network_sent_bytes",
+                            "encoded bytes of messages sent, by connection direction",
+                        ),
+                        &["direction"],
+                    )?,
+                    &registry,
+                )?,
+                received_bytes: register(
+                    CounterVec::new(
+                        Opts::new(
+                            "clique_network_received_bytes",
+                            "encoded bytes of messages received, by connection direction",
+                        ),
+                        &["direction"],
+                    )?,
+                    &registry,
+                )?,
+                connected_peers: register(
+                    Gauge::new(
+                        "clique_network_connected_peers",
+                        "peers with an established connection",
+                    )?,
+                    &registry,
+                )?,
+                failed_dials: register(
+                    Counter::new(
+                        "clique_network_failed_dials",
+                        "attempts to connect to a peer that failed before the handshake",
+                    )?,
+                    &registry,
+                )?,
             }),
             None => Ok(Metrics::Noop),
         }
@@ -70,6 +134,7 @@ impl Metrics {
             outgoing_connections,
             missing_incoming_connections,
             missing_outgoing_connections,
+            ..
         } = self
         {
             match event {
@@ -96,4 +161,106 @@ impl Metrics {
             }
         }
     }
+
+    pub fn report_dropped_messages(&self, count: u64) {
+        if let Metrics::Prometheus {
+            dropped_messages, ..
+        } = self
+        {
+            dropped_messages.inc_by(count);
+        }
+    }
+
+    pub fn report_sent_bytes(&self, direction: Direction, count: usize) {
+        if let Metrics::Prometheus { sent_bytes, .. } = self {
+            sent_bytes
+                .with_label_values(&[direction.label()])
+                .inc_by(count as u64);
+        }
+    }
+
+    pub fn report_received_bytes(&self, direction: Direction, count: usize) {
+        if let Metrics::Prometheus { received_bytes, .. } = self {
+            received_bytes
+                .with_label_values(&[direction.label()])
+                .inc_by(count as u64);
+        }
+    }
+
+    pub fn report_connected_peers(&self, count: usize) {
+        if let Metrics::Prometheus {
+            connected_peers, ..
+        } = self
+        {
+            connected_peers.set(count as u64);
+        }
+    }
+
+    pub fn report_failed_dial(&self) {
+        if let Metrics::Prometheus { failed_dials, .. } = self {
+            failed_dials.inc();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use substrate_prometheus_endpoint::Registry;
+
+    use super::{Direction, Metrics};
+
+    fn gathered(registry: &Registry, name: &str) -> Vec<(Vec<String>, f64)> {
+        registry
+            .gather()
+            .into_iter()
+            .filter(|family| family.get_name() == name)
+            .flat_map(|family| family.get_metric().to_vec())
+            .map(|metric| {
+                let labels = metric
+                    .get_label()
+                    .iter()
+                    .map(|label| label.get_value().to_string())
+                    .collect();
+                let value = match metric.has_counter() {
+                    true => metric.get_counter().get_value(),
+                    false => metric.get_gauge().get_value(),
+                };
+                (labels, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_traffic_per_direction() {
+        let registry = Registry::new();
+        let metrics = Metrics::new(Some(registry.clone())).expect("should register");
+        metrics.report_sent_bytes(Direction::Incoming, 10);
+        metrics.report_sent_bytes(Direction::Outgoing, 7);
+        metrics.report_sent_bytes(Direction::Outgoing, 3);
+        metrics.report_received_bytes(Direction::Incoming, 5);
+        metrics.report_connected_peers(4);
+        metrics.report_failed_dial();
+
+        let mut sent = gathered(&registry, "clique_network_sent_bytes");
+        sent.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            sent,
+            vec![
+                (vec![String::from("incoming")], 10.0),
+                (vec![String::from("outgoing")], 10.0),
+            ]
+        );
+        assert_eq!(
+            gathered(&registry, "clique_network_received_bytes"),
+            vec![(vec![String::from("incoming")], 5.0)]
+        );
+        assert_eq!(
+            gathered(&registry, "clique_network_connected_peers"),
+            vec![(vec![], 4.0)]
+        );
+        assert_eq!(
+            gathered(&registry, "clique_network_failed_dials"),
+            vec![(vec![], 1.0)]
+        );
+    }
 }
@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Display, Error as FmtError, Formatter},
     io::Result as IoResult,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
     time::Duration,
 };
@@ -14,10 +14,10 @@ use futures::{
 };
 use log::info;
 use parity_scale_codec::{Decode, Encode, Output};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use tokio::{
     io::{duplex, AsyncRead, AsyncWrite, DuplexStream, ReadBuf},
-    time::timeout,
+    time::{sleep_until, timeout, Instant},
 };
 
 use crate::{
@@ -412,6 +412,135 @@ impl<D: Data> Default for MockNetwork<D> {
     }
 }
 
+type DelayFn<PK> = Box<dyn Fn(&PK) -> Option<Duration> + Send + Sync>;
+
+struct DelayedMessages<PK, D> {
+    rng: StdRng,
+    // Keyed by the delivery time and a sequence number, so messages due at the same time keep
+    // the order in which they were sent.
+    pending: BTreeMap<(Instant, u64), (D, PK)>,
+    sequence: u64,
+}
+
+/// Wraps a network, delaying or dropping the messages sent through it. The delay of every
+/// message is decided by a function of its recipient, with `None` meaning immediate sending.
+/// Drops are decided by a random generator with a fixed seed, so tests are reproducible.
+/// Delayed messages are only passed to the wrapped network while `next` is being awaited.
+pub struct DelayedNetwork<PK, D, N> {
+    inner: N,
+    delay: DelayFn<PK>,
+    drop_probability: f64,
+    messages: Mutex<DelayedMessages<PK, D>>,
+}
+
+impl<PK, D, N> DelayedNetwork<PK, D, N> {
+    /// Create a wrapper around `inner` dropping messages with probability `drop_probability`,
+    /// using a random generator initialized with `seed`.
+    pub fn new(
+        inner: N,
+        delay: impl Fn(&PK) -> Option<Duration> + Send + Sync + 'static,
+        drop_probability: f64,
+        seed: u64,
+    ) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&drop_probability),
+            "drop probability must be between 0 and 1"
+        );
+        DelayedNetwork {
+            inner,
+            delay: Box::new(delay),
+            drop_probability,
+            messages: Mutex::new(DelayedMessages {
+                rng: StdRng::seed_from_u64(seed),
+                pending: BTreeMap::new(),
+                sequence: 0,
+            }),
+        }
+    }
+
+    /// The wrapped network.
+    pub fn inner(&self) -> &N {
+        &self.inner
+    }
+
+    /// Number of delayed messages not yet passed to the wrapped network.
+    pub fn pending(&self) -> usize {
+        self.messages
+            .lock()
+            .expect("lock not poisoned")
+            .pending
+            .len()
+    }
+}
+
+impl<PK: PublicKey, D: Data, N> DelayedNetwork<PK, D, N> {
+    fn next_due(&self) -> Option<Instant> {
+        self.messages
+            .lock()
+            .expect("lock not poisoned")
+            .pending
+            .keys()
+            .next()
+            .map(|(due, _)| *due)
+    }
+
+    fn take_due(&self) -> Vec<(D, PK)> {
+        let now = Instant::now();
+        let mut messages = self.messages.lock().expect("lock not poisoned");
+        let not_due = messages.pending.split_off(&(now, u64::MAX));
+        std::mem::replace(&mut messages.pending, not_due)
+            .into_values()
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl<PK: PublicKey, A: Data, D: Data, N: Network<PK, A, D> + Sync> Network<PK, A, D>
+    for DelayedNetwork<PK, D, N>
+{
+    fn add_connection(&mut self, peer: PK, address: A) {
+        self.inner.add_connection(peer, address);
+    }
+
+    fn remove_connection(&mut self, peer: PK) {
+        self.inner.remove_connection(peer);
+    }
+
+    fn send(&self, data: D, recipient: PK) {
+        let mut messages = self.messages.lock().expect("lock not poisoned");
+        if messages.rng.gen_bool(self.drop_probability) {
+            return;
+        }
+        match (self.delay)(&recipient) {
+            Some(delay) => {
+                let sequence = messages.sequence;
+                messages.sequence += 1;
+                messages
+                    .pending
+                    .insert((Instant::now() + delay, sequence), (data, recipient));
+            }
+            None => {
+                drop(messages);
+                self.inner.send(data, recipient);
+            }
+        }
+    }
+
+    async fn next(&mut self) -> Option<D> {
+        loop {
+            let next_due = self.next_due();
+            tokio::select! {
+                data = self.inner.next() => return data,
+                _ = sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                    for (data, recipient) in self.take_due() {
+                        self.inner.send(data, recipient);
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Bidirectional in-memory stream that closes abruptly after a specified
 /// number of poll_write calls.
 #[derive(Debug)]
@@ -671,3 +800,78 @@ pub struct MockPrelims<D> {
     pub result_from_outgoing: UnboundedReceiver<ResultForService<MockPublicKey, D>>,
     pub authorization_requests: mpsc::UnboundedReceiver<(MockPublicKey, oneshot::Sender<bool>)>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::timeout;
+
+    use super::{key, random_address, DelayedNetwork, MockData, MockNetwork};
+    use crate::Network;
+
+    const DELAY: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn delivers_delayed_messages_after_delay() {
+        let (slow_peer, _) = key();
+        let (fast_peer, _) = key();
+        let delayed_peer = slow_peer.clone();
+        let mut network = DelayedNetwork::new(
+            MockNetwork::new(),
+            move |peer| (peer == &delayed_peer).then_some(DELAY),
+            0.0,
+            0,
+        );
+        network.add_connection(slow_peer.clone(), random_address());
+        assert!(network.inner().add_connection.try_next().await.is_some());
+
+        network.send(MockData::new(1, 0), slow_peer.clone());
+        network.send(MockData::new(2, 0), fast_peer.clone());
+        assert_eq!(
+            network.inner().send.try_next().await,
+            Some((MockData::new(2, 0), fast_peer))
+        );
+        assert!(network.inner().send.try_next().await.is_none());
+        assert_eq!(network.pending(), 1);
+
+        // Nothing comes from the wrapped network, so this only delivers the delayed message.
+        assert!(timeout(4 * DELAY, network.next()).await.is_err());
+        assert_eq!(network.pending(), 0);
+        assert_eq!(
+            network.inner().send.try_next().await,
+            Some((MockData::new(1, 0), slow_peer))
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_messages_reproducibly() {
+        let (peer, _) = key();
+        let delivered = |seed| {
+            let peer = peer.clone();
+            async move {
+                let network = DelayedNetwork::new(MockNetwork::new(), |_| None, 0.5, seed);
+                for i in 0..100 {
+                    network.send(MockData::new(i, 0), peer.clone());
+                }
+                let mut delivered = Vec::new();
+                while let Some((data, _)) = network.inner().send.try_next().await {
+                    delivered.push(data.data());
+                }
+                delivered
+            }
+        };
+        let first = delivered(7).await;
+        assert!(!first.is_empty() && first.len() < 100);
+        assert_eq!(first, delivered(7).await);
+    }
+
+    #[tokio::test]
+    async fn drops_everything_with_certainty() {
+        let (peer, _) = key();
+        let network = DelayedNetwork::new(MockNetwork::new(), |_| Some(DELAY), 1.0, 0);
+        network.send(MockData::new(1, 0), peer);
+        assert_eq!(network.pending(), 0);
+        assert!(network.inner().send.try_next().await.is_none());
+    }
+}
@@ -5,19 +5,22 @@ use log::{debug, info};
 use tokio::time::{sleep, timeout, Duration};
 
 use crate::{
+    latency::LatencyTracker,
     metrics::Metrics,
-    protocols::{protocol, ProtocolError, ProtocolNegotiationError, ResultForService},
-    ConnectionInfo, Data, Dialer, PeerAddressInfo, PublicKey, SecretKey, LOG_TARGET,
+    protocols::{
+        protocol, Compression, HandshakeError, KeepAlive, ProtocolError, ProtocolNegotiationError,
+        ResultForService,
+    },
+    ConnectionInfo, Data, DialError, Dialer, PeerAddressInfo, PublicKey, SecretKey, LOG_TARGET,
 };
 
-enum OutgoingError<PK: PublicKey, A: Data, ND: Dialer<A>> {
-    Dial(ND::Error),
+enum OutgoingError<PK: PublicKey> {
+    Dial(DialError),
     ProtocolNegotiation(PeerAddressInfo, ProtocolNegotiationError),
     Protocol(PeerAddressInfo, ProtocolError<PK>),
-    TimedOut,
 }
 
-impl<PK: PublicKey, A: Data, ND: Dialer<A>> Display for OutgoingError<PK, A, ND> {
+impl<PK: PublicKey> Display for OutgoingError<PK> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         use OutgoingError::*;
         match self {
@@ -27,7 +30,21 @@ impl<PK: PublicKey, A: Data, ND: Dialer<A>> Display for OutgoingError<PK, A, ND>
                 "communication with {addr} failed, protocol negotiation error: {e}"
             ),
             Protocol(addr, e) => write!(f, "communication with {addr} failed, protocol error: {e}"),
-            TimedOut => write!(f, "dial timeout",),
+        }
+    }
+}
+
+impl<PK: PublicKey> OutgoingError<PK> {
+    /// How long to wait before trying to connect again. A peer with an unexpected identity is
+    /// most likely misconfigured, so there is little point in retrying it often.
+    fn retry_delay(&self) -> Duration {
+        use OutgoingError::*;
+        match self {
+            Dial(DialError::InvalidIdentity)
+            | Protocol(_, ProtocolError::HandshakeError(HandshakeError::ChallengeError(..))) => {
+                INVALID_IDENTITY_RETRY_DELAY
+            }
+            _ => RETRY_DELAY,
         }
     }
 }
@@ -35,6 +52,7 @@ impl<PK: PublicKey, A: Data, ND: Dialer<A>> Display for OutgoingError<PK, A, ND>
 /// Arbitrarily chosen timeout, should be more than enough.
 const DIAL_TIMEOUT: Duration = Duration::from_secs(60);
 
+#[allow(clippy::too_many_arguments)]
 async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
     secret_key: SK,
     public_key: SK::PublicKey,
@@ -43,12 +61,15 @@ async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
-) -> Result<(), OutgoingError<SK::PublicKey, A, ND>> {
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
+) -> Result<(), OutgoingError<SK::PublicKey>> {
     debug!(target: LOG_TARGET, "Trying to connect to {}.", public_key);
     let stream = timeout(DIAL_TIMEOUT, dialer.connect(address))
         .await
-        .map_err(|_| OutgoingError::TimedOut)?
-        .map_err(OutgoingError::Dial)?;
+        .map_err(|_| OutgoingError::Dial(DialError::Timeout))?
+        .map_err(|e| OutgoingError::Dial(e.into()))?;
     let peer_address_info = stream.peer_address_info();
     debug!(
         target: LOG_TARGET,
@@ -66,16 +87,21 @@ async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
             result_for_parent,
             data_for_user,
             metrics,
+            latency,
+            compression,
+            keep_alive,
         )
         .await
         .map_err(|e| OutgoingError::Protocol(peer_address_info.clone(), e))
 }
 
 const RETRY_DELAY: Duration = Duration::from_secs(10);
+const INVALID_IDENTITY_RETRY_DELAY: Duration = Duration::from_secs(60);
 
 /// Establish an outgoing connection to the provided peer using the dialer and then manage it.
 /// While this works it will send any data from the user to the peer. Any failures will be reported
 /// to the parent, so that connections can be reestablished if necessary.
+#[allow(clippy::too_many_arguments)]
 pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
     secret_key: SK,
     public_key: SK::PublicKey,
@@ -84,6 +110,9 @@ pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
 ) {
     if let Err(e) = manage_outgoing(
         secret_key,
@@ -92,19 +121,26 @@ pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
         address.clone(),
         result_for_parent.clone(),
         data_for_user,
-        metrics,
+        metrics.clone(),
+        latency,
+        compression,
+        keep_alive,
     )
     .await
     {
+        if let OutgoingError::Dial(_) = e {
+            metrics.report_failed_dial();
+        }
+        let retry_delay = e.retry_delay();
         info!(
             target: LOG_TARGET,
             "Outgoing connection to {} {:?} failed: {}, will retry after {}s.",
             public_key,
             address,
             e,
-            RETRY_DELAY.as_secs()
+            retry_delay.as_secs()
         );
-        sleep(RETRY_DELAY).await;
+        sleep(retry_delay).await;
         if result_for_parent
             .unbounded_send((public_key, None))
             .is_err()
@@ -113,3 +149,39 @@ pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{OutgoingError, INVALID_IDENTITY_RETRY_DELAY, RETRY_DELAY};
+    use crate::{
+        mock::{key, MockPublicKey},
+        protocols::{HandshakeError, ProtocolError},
+        DialError,
+    };
+
+    #[test]
+    fn backs_off_longer_for_invalid_identity() {
+        let dial_error = |e| OutgoingError::<MockPublicKey>::Dial(e);
+        assert_eq!(
+            dial_error(DialError::Unreachable).retry_delay(),
+            RETRY_DELAY
+        );
+        assert_eq!(dial_error(DialError::Timeout).retry_delay(), RETRY_DELAY);
+        assert_eq!(
+            dial_error(DialError::InvalidIdentity).retry_delay(),
+            INVALID_IDENTITY_RETRY_DELAY
+        );
+        let (expected, _) = key();
+        let (got, _) = key();
+        let challenge_error = OutgoingError::Protocol(
+            String::from("address"),
+            ProtocolError::HandshakeError(HandshakeError::ChallengeError(expected, got)),
+        );
+        assert_eq!(challenge_error.retry_delay(), INVALID_IDENTITY_RETRY_DELAY);
+        let heartbeat_error = OutgoingError::<MockPublicKey>::Protocol(
+            String::from("address"),
+            ProtocolError::CardiacArrest,
+        );
+        assert_eq!(heartbeat_error.retry_delay(), RETRY_DELAY);
+    }
+}
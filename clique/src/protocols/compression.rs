@@ -0,0 +1,403 @@
+use std::{
+    cmp::min,
+    io::{Error as IoError, ErrorKind, Read, Result as IoResult},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    io::{receive_data, send_data, MAX_DATA_SIZE},
+    protocols::ProtocolError,
+    PublicKey,
+};
+
+// Enough to fit the biggest message we ever send, together with its length prefix.
+const MAX_FRAME_PAYLOAD: usize = MAX_DATA_SIZE as usize + 4;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// A codec that can be used to compress the whole stream after the handshake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// No compression, the bytes are passed through as they are.
+    None,
+    /// Zstandard, every write is compressed as a separate, length prefixed frame.
+    Zstd,
+}
+
+impl Compression {
+    /// Codecs we know, from the most to the least preferred.
+    const PREFERENCE: [Compression; 2] = [Compression::Zstd, Compression::None];
+
+    fn id(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd => 1,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Chooses the most preferred codec supported by both sides. Since the preference order is
+/// fixed, both sides always end up with the same choice.
+fn choose(ours: &[Compression], theirs: &[Compression]) -> Compression {
+    Compression::PREFERENCE
+        .into_iter()
+        .find(|compression| ours.contains(compression) && theirs.contains(compression))
+        .unwrap_or(Compression::None)
+}
+
+/// Exchanges the supported codecs with the peer and wraps both halves of the stream with the
+/// chosen one. Codecs we do not know are ignored, so that new ones can be added in the future
+/// without breaking the negotiation.
+pub async fn negotiate<PK: PublicKey, S: AsyncWrite + Unpin, R: AsyncRead + Unpin>(
+    sender: S,
+    receiver: R,
+    supported: &[Compression],
+) -> Result<(CompressedSender<S>, CompressedReceiver<R>, Compression), ProtocolError<PK>> {
+    let ours: Vec<u8> = supported.iter().map(Compression::id).collect();
+    let sender = send_data(sender, ours).await?;
+    let (receiver, theirs) = receive_data::<_, Vec<u8>>(receiver).await?;
+    let theirs: Vec<_> = theirs
+        .into_iter()
+        .filter_map(Compression::from_id)
+        .collect();
+    let compression = choose(supported, &theirs);
+    Ok((
+        CompressedSender::new(sender, compression),
+        CompressedReceiver::new(receiver, compression),
+        compression,
+    ))
+}
+
+/// The sending half of a stream, compressing everything written to it.
+///
+/// Every write is turned into a single frame, which is then written out fully before the write
+/// is reported as done. This assumes that a write that returned `Poll::Pending` is retried with
+/// the same data, which is what `write_all` does.
+pub struct CompressedSender<S> {
+    inner: S,
+    compression: Compression,
+    frame: Vec<u8>,
+    frame_written: usize,
+    frame_source_len: usize,
+}
+
+impl<S: AsyncWrite + Unpin> CompressedSender<S> {
+    fn new(inner: S, compression: Compression) -> Self {
+        CompressedSender {
+            inner,
+            compression,
+            frame: Vec::new(),
+            frame_written: 0,
+            frame_source_len: 0,
+        }
+    }
+
+    fn poll_write_frame(&mut self, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        while self.frame_written < self.frame.len() {
+            let written = ready!(
+                Pin::new(&mut self.inner).poll_write(cx, &self.frame[self.frame_written..])
+            )?;
+            if written == 0 {
+                return Poll::Ready(Err(ErrorKind::WriteZero.into()));
+            }
+            self.frame_written += written;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn encode_frame(source: &[u8]) -> IoResult<Vec<u8>> {
+    let compressed = zstd::bulk::compress(source, ZSTD_LEVEL)?;
+    let len = u32::try_from(compressed.len())
+        .map_err(|_| IoError::new(ErrorKind::InvalidData, "compressed frame too long"))?;
+    let mut frame = len.to_le_bytes().to_vec();
+    frame.extend(compressed);
+    Ok(frame)
+}
+
+/// Decompresses a single frame. The output grows with the data actually decompressed rather than
+/// being preallocated for the biggest possible frame, and decompression stops as soon as the
+/// frame turns out to be too big.
+fn decode_frame(frame: &[u8]) -> IoResult<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::with_buffer(frame)?
+        .take(MAX_FRAME_PAYLOAD as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+    if decompressed.len() > MAX_FRAME_PAYLOAD {
+        return Err(IoError::new(
+            ErrorKind::InvalidData,
+            "decompressed frame too long",
+        ));
+    }
+    Ok(decompressed)
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CompressedSender<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        let this = self.get_mut();
+        match this.compression {
+            Compression::None => Pin::new(&mut this.inner).poll_write(cx, buf),
+            Compression::Zstd => {
+                if this.frame_written == this.frame.len() {
+                    if buf.is_empty() {
+                        return Poll::Ready(Ok(0));
+                    }
+                    let source = &buf[..min(buf.len(), MAX_FRAME_PAYLOAD)];
+                    this.frame = encode_frame(source)?;
+                    this.frame_written = 0;
+                    this.frame_source_len = source.len();
+                }
+                ready!(this.poll_write_frame(cx))?;
+                Poll::Ready(Ok(this.frame_source_len))
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_frame(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_frame(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+/// The receiving half of a stream, decompressing everything read from it.
+pub struct CompressedReceiver<R> {
+    inner: R,
+    compression: Compression,
+    header: [u8; 4],
+    header_read: usize,
+    frame: Vec<u8>,
+    frame_read: usize,
+    decompressed: Vec<u8>,
+    decompressed_read: usize,
+}
+
+impl<R: AsyncRead + Unpin> CompressedReceiver<R> {
+    fn new(inner: R, compression: Compression) -> Self {
+        CompressedReceiver {
+            inner,
+            compression,
+            header: [0; 4],
+            header_read: 0,
+            frame: Vec::new(),
+            frame_read: 0,
+            decompressed: Vec::new(),
+            decompressed_read: 0,
+        }
+    }
+
+    /// Reads the next frame and decompresses it. Returns `false` if the stream ended cleanly
+    /// before the frame started.
+    fn poll_read_frame(&mut self, cx: &mut Context<'_>) -> Poll<IoResult<bool>> {
+        while self.header_read < self.header.len() {
+            let mut buf = ReadBuf::new(&mut self.header[self.header_read..]);
+            ready!(Pin::new(&mut self.inner).poll_read(cx, &mut buf))?;
+            let read = buf.filled().len();
+            if read == 0 {
+                return match self.header_read {
+                    0 => Poll::Ready(Ok(false)),
+                    _ => Poll::Ready(Err(ErrorKind::UnexpectedEof.into())),
+                };
+            }
+            self.header_read += read;
+        }
+        if self.frame.is_empty() {
+            let len = u32::from_le_bytes(self.header) as usize;
+            if len == 0 || len > zstd::zstd_safe::compress_bound(MAX_FRAME_PAYLOAD) {
+                return Poll::Ready(Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    "invalid compressed frame length",
+                )));
+            }
+            self.frame = vec![0; len];
+            self.frame_read = 0;
+        }
+        while self.frame_read < self.frame.len() {
+            let mut buf = ReadBuf::new(&mut self.frame[self.frame_read..]);
+            ready!(Pin::new(&mut self.inner).poll_read(cx, &mut buf))?;
+            let read = buf.filled().len();
+            if read == 0 {
+                return Poll::Ready(Err(ErrorKind::UnexpectedEof.into()));
+            }
+            self.frame_read += read;
+        }
+        self.decompressed = decode_frame(&self.frame)?;
+        self.decompressed_read = 0;
+        self.header_read = 0;
+        self.frame = Vec::new();
+        self.frame_read = 0;
+        Poll::Ready(Ok(true))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CompressedReceiver<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        if this.compression == Compression::None {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        while this.decompressed_read == this.decompressed.len() {
+            if !ready!(this.poll_read_frame(cx))? {
+                return Poll::Ready(Ok(()));
+            }
+        }
+        let available = &this.decompressed[this.decompressed_read..];
+        let len = min(available.len(), buf.remaining());
+        buf.put_slice(&available[..len]);
+        this.decompressed_read += len;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::join;
+    use tokio::io::{duplex, AsyncReadExt};
+
+    use super::{
+        choose, decode_frame, encode_frame, negotiate, CompressedReceiver, CompressedSender,
+        Compression, MAX_FRAME_PAYLOAD, ZSTD_LEVEL,
+    };
+    use crate::{
+        io::{receive_data, send_data},
+        mock::MockPublicKey,
+    };
+
+    const ALL: [Compression; 2] = [Compression::Zstd, Compression::None];
+    const UNCOMPRESSED: [Compression; 1] = [Compression::None];
+
+    #[test]
+    fn chooses_common_codec() {
+        assert_eq!(choose(&ALL, &ALL), Compression::Zstd);
+        assert_eq!(choose(&ALL, &UNCOMPRESSED), Compression::None);
+        assert_eq!(choose(&UNCOMPRESSED, &ALL), Compression::None);
+        assert_eq!(choose(&[], &[]), Compression::None);
+    }
+
+    #[tokio::test]
+    async fn compressing_peers_exchange_compressed_bytes() {
+        let (sender, mut raw_receiver) = duplex(4096);
+        let data = vec![43u8; 100_000];
+        let sender = send_data(
+            CompressedSender::new(sender, Compression::Zstd),
+            data.clone(),
+        )
+        .await
+        .expect("should send");
+        drop(sender);
+        let mut raw = Vec::new();
+        raw_receiver
+            .read_to_end(&mut raw)
+            .await
+            .expect("should read");
+        assert!(raw.len() < data.len() / 10);
+
+        let receiver = CompressedReceiver::new(&raw[..], Compression::Zstd);
+        let (_receiver, received) = receive_data::<_, Vec<u8>>(receiver)
+            .await
+            .expect("should receive");
+        assert_eq!(received, data);
+    }
+
+    #[test]
+    fn decodes_encoded_frame() {
+        let data = vec![43u8; 1000];
+        let frame = encode_frame(&data).expect("should encode");
+        assert_eq!(decode_frame(&frame[4..]).expect("should decode"), data);
+    }
+
+    #[test]
+    fn rejects_frame_decompressing_above_limit() {
+        let compressed = zstd::bulk::compress(&vec![0u8; MAX_FRAME_PAYLOAD + 1], ZSTD_LEVEL)
+            .expect("should compress");
+        assert!(decode_frame(&compressed).is_err());
+    }
+
+    async fn negotiate_and_exchange(
+        ours: &[Compression],
+        theirs: &[Compression],
+    ) -> (Compression, Compression) {
+        let (our_sender, their_receiver) = duplex(4096);
+        let (their_sender, our_receiver) = duplex(4096);
+        let data = vec![7u8; 10_000];
+        let ours = async {
+            let (sender, receiver, compression) =
+                negotiate::<MockPublicKey, _, _>(our_sender, our_receiver, ours)
+                    .await
+                    .expect("should negotiate");
+            let _sender = send_data(sender, data.clone()).await.expect("should send");
+            let (_receiver, received) = receive_data::<_, Vec<u8>>(receiver)
+                .await
+                .expect("should receive");
+            assert_eq!(received, data);
+            compression
+        };
+        let theirs = async {
+            let (sender, receiver, compression) =
+                negotiate::<MockPublicKey, _, _>(their_sender, their_receiver, theirs)
+                    .await
+                    .expect("should negotiate");
+            let _sender = send_data(sender, data.clone()).await.expect("should send");
+            let (_receiver, received) = receive_data::<_, Vec<u8>>(receiver)
+                .await
+                .expect("should receive");
+            assert_eq!(received, data);
+            compression
+        };
+        join!(ours, theirs)
+    }
+
+    #[tokio::test]
+    async fn negotiates_compression_when_both_support_it() {
+        assert_eq!(
+            negotiate_and_exchange(&ALL, &ALL).await,
+            (Compression::Zstd, Compression::Zstd)
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_uncompressed() {
+        assert_eq!(
+            negotiate_and_exchange(&ALL, &UNCOMPRESSED).await,
+            (Compression::None, Compression::None)
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_unknown_codecs() {
+        let (sender, _their_receiver) = duplex(4096);
+        let (their_sender, receiver) = duplex(4096);
+        let _their_sender = send_data(their_sender, vec![43u8, Compression::Zstd.id()])
+            .await
+            .expect("should send");
+        let (_, _, compression) = negotiate::<MockPublicKey, _, _>(sender, receiver, &ALL)
+            .await
+            .expect("should negotiate");
+        assert_eq!(compression, Compression::Zstd);
+    }
+}
@@ -1,33 +1,69 @@
-use std::fmt::{Display, Error as FmtError, Formatter};
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    time::Duration,
+};
 
 use futures::channel::{mpsc, oneshot};
 
 use crate::{
     io::{ReceiveError, SendError},
+    latency::LatencyTracker,
     metrics::Metrics,
-    Data, PublicKey, SecretKey, Splittable,
+    queue::QueueSender,
+    Data, PeerAddressInfo, PublicKey, SecretKey, Splittable,
 };
 
+mod compression;
 mod handshake;
 mod negotiation;
 mod v1;
+mod v2;
 
-use handshake::HandshakeError;
+pub use compression::Compression;
+pub use handshake::HandshakeError;
 pub use negotiation::{protocol, ProtocolNegotiationError};
 
 pub type Version = u32;
 
+/// How often to send a heartbeat when there is no data to send.
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// How long to wait for anything from the peer before considering the connection dead.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Keeps idle connections alive and detects dead ones. Heartbeats are separate frames, never
+/// passed to the user, sent every `heartbeat_interval`. From V2 on the peer echoes them, which
+/// measures the round-trip time to it. A connection on
+/// which nothing arrived for `idle_timeout` is torn down. The timeout should be a few times
+/// longer than the interval used by the peers, otherwise healthy connections will be dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAlive {
+    pub heartbeat_interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        KeepAlive {
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+}
+
 /// What connections send back to the service after they become established. Starts with a public
-/// key of the remote node, followed by a channel for sending data to that node, with None if the
-/// connection was unsuccessful and should be reestablished.
-pub type ResultForService<PK, D> = (PK, Option<mpsc::UnboundedSender<D>>);
+/// key of the remote node, followed by a channel for sending data to that node together with the
+/// address of the node, with None if the connection was unsuccessful and should be reestablished.
+pub type ResultForService<PK, D> = (PK, Option<(QueueSender<D>, PeerAddressInfo)>);
 
-/// Defines the protocol for communication. Currently single variant, but left in case of protocol change.
+/// Defines the protocol for communication.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Protocol {
-    /// The current version of the protocol, with pseudorandom connection direction and
+    /// The first version of the protocol, with pseudorandom connection direction and
     /// multiplexing.
     V1,
+    /// The current version of the protocol, same as `V1`, but additionally negotiating
+    /// the compression of the whole stream after the handshake.
+    V2,
 }
 
 /// Protocol error.
@@ -49,6 +85,8 @@ pub enum ProtocolError<PK: PublicKey> {
     NotAuthorized,
     /// Send operation took too long
     SendTimeout,
+    /// The peer said goodbye, closing the connection.
+    PeerClosed,
 }
 
 impl<PK: PublicKey> Display for ProtocolError<PK> {
@@ -63,6 +101,7 @@ impl<PK: PublicKey> Display for ProtocolError<PK> {
             NoUserConnection => write!(f, "cannot send data to user"),
             NotAuthorized => write!(f, "peer not authorized"),
             SendTimeout => write!(f, "send timed out"),
+            PeerClosed => write!(f, "peer closed the connection"),
         }
     }
 }
@@ -90,9 +129,10 @@ impl Protocol {
     const MIN_VERSION: Version = 1;
 
     /// Maximal supported protocol version.
-    const MAX_VERSION: Version = 1;
+    const MAX_VERSION: Version = 2;
 
     /// Launches the proper variant of the protocol (receiver half).
+    #[allow(clippy::too_many_arguments)]
     pub async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
         &self,
         stream: S,
@@ -104,6 +144,9 @@ impl Protocol {
             oneshot::Sender<bool>,
         )>,
         metrics: Metrics,
+        latency: LatencyTracker,
+        compression: Vec<Compression>,
+        keep_alive: KeepAlive,
     ) -> Result<(), ProtocolError<SK::PublicKey>> {
         use Protocol::*;
         match self {
@@ -115,6 +158,22 @@ impl Protocol {
                     result_for_parent,
                     data_for_user,
                     metrics,
+                    latency,
+                    keep_alive,
+                )
+                .await
+            }
+            V2 => {
+                v2::incoming(
+                    stream,
+                    secret_key,
+                    authorization_requests_sender,
+                    result_for_parent,
+                    data_for_user,
+                    metrics,
+                    latency,
+                    compression,
+                    keep_alive,
                 )
                 .await
             }
@@ -122,6 +181,7 @@ impl Protocol {
     }
 
     /// Launches the proper variant of the protocol (sender half).
+    #[allow(clippy::too_many_arguments)]
     pub async fn manage_outgoing<SK: SecretKey, D: Data, S: Splittable>(
         &self,
         stream: S,
@@ -130,6 +190,9 @@ impl Protocol {
         result_for_service: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
         data_for_user: mpsc::UnboundedSender<D>,
         metrics: Metrics,
+        latency: LatencyTracker,
+        compression: Vec<Compression>,
+        keep_alive: KeepAlive,
     ) -> Result<(), ProtocolError<SK::PublicKey>> {
         use Protocol::*;
         match self {
@@ -141,6 +204,22 @@ impl Protocol {
                     result_for_service,
                     data_for_user,
                     metrics,
+                    latency,
+                    keep_alive,
+                )
+                .await
+            }
+            V2 => {
+                v2::outgoing(
+                    stream,
+                    secret_key,
+                    public_key,
+                    result_for_service,
+                    data_for_user,
+                    metrics,
+                    latency,
+                    compression,
+                    keep_alive,
                 )
                 .await
             }
@@ -154,6 +233,7 @@ impl TryFrom<Version> for Protocol {
     fn try_from(version: Version) -> Result<Self, Self::Error> {
         match version {
             1 => Ok(Protocol::V1),
+            2 => Ok(Protocol::V2),
             unknown_version => Err(unknown_version),
         }
     }
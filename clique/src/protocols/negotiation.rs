@@ -138,12 +138,15 @@ mod tests {
     use futures::{pin_mut, FutureExt};
     use tokio::io::duplex;
 
-    use super::{negotiate_protocol_version, supported_protocol_range, ProtocolNegotiationError};
+    use super::{
+        negotiate_protocol_version, supported_protocol_range, ProtocolNegotiationError,
+        ProtocolsRange,
+    };
     use crate::protocols::Protocol;
 
     fn correct_negotiation<S>(result: Result<(S, Protocol), ProtocolNegotiationError>) {
         match result {
-            Ok((_stream, protocol)) => assert_eq!(Protocol::V1, protocol),
+            Ok((_stream, protocol)) => assert_eq!(Protocol::V2, protocol),
             Err(e) => panic!("Unexpected error: {e:?}"),
         }
     }
@@ -192,6 +195,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn negotiates_first_version_with_old_peer() {
+        let (stream1, stream2) = duplex(4096);
+        let old_protocol_range = ProtocolsRange(1, 1);
+        let negotiation1 = negotiate_protocol_version(stream1, supported_protocol_range()).fuse();
+        pin_mut!(negotiation1);
+        let negotiation2 = negotiate_protocol_version(stream2, old_protocol_range).fuse();
+        pin_mut!(negotiation2);
+        for _ in 0..2 {
+            tokio::select! {
+                result = &mut negotiation1 => assert_eq!(Protocol::V1, result.expect("should negotiate").1),
+                result = &mut negotiation2 => assert_eq!(Protocol::V1, result.expect("should negotiate").1),
+            }
+        }
+    }
+
     #[tokio::test]
     async fn fails_when_no_intersection() {
         let (stream1, stream2) = duplex(4096);
@@ -5,22 +5,44 @@ use futures::{
 use log::{debug, info, trace};
 use parity_scale_codec::{Decode, Encode};
 use tokio::{
-    io::{AsyncRead, AsyncWrite},
-    time::{timeout, Duration},
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    time::{sleep_until, timeout, Duration, Instant},
 };
 
 use crate::{
     io::{receive_data, send_data},
-    metrics::{Event, Metrics},
+    latency::LatencyTracker,
+    metrics::{Direction, Event, Metrics},
     protocols::{
         handshake::{v0_handshake_incoming, v0_handshake_outgoing},
-        ProtocolError, ResultForService,
+        KeepAlive, ProtocolError, ResultForService,
     },
-    Data, PublicKey, SecretKey, Splittable, LOG_TARGET,
+    queue::{queue, QueueReceiver},
+    ConnectionInfo, Data, PublicKey, SecretKey, Splittable, LOG_TARGET,
 };
 
-const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
-const MAX_MISSED_HEARTBEATS: u32 = 4;
+/// What can be exchanged over an established connection.
+pub(super) enum Frame<D: Data> {
+    Data(D),
+    /// A heartbeat, carrying the time it was sent if the peer wants it echoed.
+    Heartbeat(Option<u64>),
+    /// The echo of our heartbeat, carrying the time we sent it.
+    HeartbeatEcho(u64),
+    Goodbye,
+}
+
+/// How a protocol version encodes frames on the wire. Older versions do not know about some of
+/// the frames, so they never send them.
+pub(super) trait WireMessage<D: Data>: Data {
+    fn data(data: D) -> Self;
+    /// A heartbeat sent at the given time, which the version might not transmit.
+    fn heartbeat(sent_at: u64) -> Self;
+    /// The echo of a heartbeat sent by the peer at the given time, if the version has one.
+    fn heartbeat_echo(sent_at: u64) -> Option<Self>;
+    /// The message announcing that we are leaving on purpose, if the version has one.
+    fn goodbye() -> Option<Self>;
+    fn into_frame(self) -> Frame<D>;
+}
 
 #[derive(Debug, Clone, Encode, Decode)]
 enum Message<D: Data> {
@@ -28,7 +50,32 @@ enum Message<D: Data> {
     Heartbeat,
 }
 
-async fn check_authorization<SK: SecretKey>(
+impl<D: Data> WireMessage<D> for Message<D> {
+    fn data(data: D) -> Self {
+        Message::Data(data)
+    }
+
+    fn heartbeat(_sent_at: u64) -> Self {
+        Message::Heartbeat
+    }
+
+    fn heartbeat_echo(_sent_at: u64) -> Option<Self> {
+        None
+    }
+
+    fn goodbye() -> Option<Self> {
+        None
+    }
+
+    fn into_frame(self) -> Frame<D> {
+        match self {
+            Message::Data(data) => Frame::Data(data),
+            Message::Heartbeat => Frame::Heartbeat(None),
+        }
+    }
+}
+
+pub(super) async fn check_authorization<SK: SecretKey>(
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     public_key: SK::PublicKey,
 ) -> Result<bool, ProtocolError<SK::PublicKey>> {
@@ -41,64 +88,159 @@ async fn check_authorization<SK: SecretKey>(
         .map_err(|_| ProtocolError::NoParentConnection)
 }
 
-async fn sending<PK: PublicKey, D: Data, S: AsyncWrite + Unpin + Send>(
+/// The heartbeat interval of the nodes speaking only this version, which consider a connection
+/// dead after four intervals without anything arriving.
+const V1_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Peers on this version might not know about configurable keep-alive, so never send heartbeats
+/// less often than they expect.
+fn v1_keep_alive(keep_alive: KeepAlive) -> KeepAlive {
+    KeepAlive {
+        heartbeat_interval: keep_alive.heartbeat_interval.min(V1_HEARTBEAT_INTERVAL),
+        ..keep_alive
+    }
+}
+
+/// The time passed since the start of the connection, as carried by heartbeats.
+fn connection_time(start: Instant) -> u64 {
+    start.elapsed().as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+async fn sending<PK: PublicKey, D: Data, M: WireMessage<D>, S: AsyncWrite + Unpin + Send>(
     mut sender: S,
-    mut data_from_user: mpsc::UnboundedReceiver<D>,
+    mut data_from_user: QueueReceiver<D>,
+    mut heartbeats_to_echo: mpsc::UnboundedReceiver<u64>,
+    start: Instant,
+    keep_alive: KeepAlive,
+    metrics: Metrics,
+    direction: Direction,
 ) -> Result<(), ProtocolError<PK>> {
-    use Message::*;
+    let mut next_heartbeat = Instant::now() + keep_alive.heartbeat_interval;
     loop {
-        let to_send = match timeout(HEARTBEAT_TIMEOUT, data_from_user.next()).await {
-            Ok(maybe_data) => match maybe_data {
-                Some(data) => Data(data),
-                // We have been closed by the parent service, all good.
-                None => return Ok(()),
+        let to_send = tokio::select! {
+            biased;
+            Some(sent_at) = heartbeats_to_echo.next() => match M::heartbeat_echo(sent_at) {
+                Some(echo) => echo,
+                None => continue,
+            },
+            _ = sleep_until(next_heartbeat) => {
+                next_heartbeat = Instant::now() + keep_alive.heartbeat_interval;
+                M::heartbeat(connection_time(start))
+            },
+            maybe_data = data_from_user.next() => match maybe_data {
+                Some(data) => M::data(data),
+                // We have been closed by the parent service and sent everything it queued,
+                // let the peer know we are leaving on purpose, if we can.
+                None => {
+                    return match M::goodbye() {
+                        Some(goodbye) => timeout(
+                            keep_alive.idle_timeout,
+                            say_goodbye::<PK, M, S>(sender, goodbye),
+                        )
+                        .await
+                        .map_err(|_| ProtocolError::SendTimeout)?,
+                        None => Ok(()),
+                    }
+                }
             },
-            _ => Heartbeat,
         };
-        sender = timeout(
-            MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
-            send_data(sender, to_send),
-        )
-        .await
-        .map_err(|_| ProtocolError::SendTimeout)??;
+        let size = to_send.encoded_size();
+        sender = timeout(keep_alive.idle_timeout, send_data(sender, to_send))
+            .await
+            .map_err(|_| ProtocolError::SendTimeout)??;
+        metrics.report_sent_bytes(direction, size);
     }
 }
 
-async fn receiving<PK: PublicKey, D: Data, S: AsyncRead + Unpin + Send>(
+async fn say_goodbye<PK: PublicKey, M: Data, S: AsyncWrite + Unpin + Send>(
+    sender: S,
+    goodbye: M,
+) -> Result<(), ProtocolError<PK>> {
+    let mut sender = send_data(sender, goodbye).await?;
+    // The connection is going away anyway, so failing to close it cleanly changes nothing.
+    let _ = sender.shutdown().await;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn receiving<PK: PublicKey, D: Data, M: WireMessage<D>, S: AsyncRead + Unpin + Send>(
     mut stream: S,
     data_for_user: mpsc::UnboundedSender<D>,
+    heartbeats_to_echo: mpsc::UnboundedSender<u64>,
+    start: Instant,
+    keep_alive: KeepAlive,
+    metrics: Metrics,
+    latency: LatencyTracker,
+    direction: Direction,
 ) -> Result<(), ProtocolError<PK>> {
-    use Message::*;
+    use Frame::*;
     loop {
-        let (old_stream, message) = timeout(
-            MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
-            receive_data(stream),
-        )
-        .await
-        .map_err(|_| ProtocolError::CardiacArrest)??;
+        let (old_stream, message) = timeout(keep_alive.idle_timeout, receive_data::<_, M>(stream))
+            .await
+            .map_err(|_| ProtocolError::CardiacArrest)??;
         stream = old_stream;
-        match message {
+        metrics.report_received_bytes(direction, message.encoded_size());
+        match message.into_frame() {
             Data(data) => data_for_user
                 .unbounded_send(data)
                 .map_err(|_| ProtocolError::NoUserConnection)?,
-            Heartbeat => (),
+            Heartbeat(None) => (),
+            // The sending half only stops together with the whole connection.
+            Heartbeat(Some(sent_at)) => {
+                let _ = heartbeats_to_echo.unbounded_send(sent_at);
+            }
+            HeartbeatEcho(sent_at) => {
+                if let Some(round_trip) =
+                    start.elapsed().checked_sub(Duration::from_micros(sent_at))
+                {
+                    latency.report(round_trip);
+                }
+            }
+            Goodbye => return Err(ProtocolError::PeerClosed),
         }
     }
 }
 
-async fn manage_connection<
+/// Sends and receives data over an established connection. Heartbeats are sent every
+/// `heartbeat_interval`, and if the version echoes them, the round trips they take are reported.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn manage_connection<
     PK: PublicKey,
     D: Data,
+    M: WireMessage<D>,
     S: AsyncWrite + Unpin + Send,
     R: AsyncRead + Unpin + Send,
 >(
     sender: S,
     receiver: R,
-    data_from_user: mpsc::UnboundedReceiver<D>,
+    data_from_user: QueueReceiver<D>,
     data_for_user: mpsc::UnboundedSender<D>,
+    keep_alive: KeepAlive,
+    metrics: Metrics,
+    latency: LatencyTracker,
+    direction: Direction,
 ) -> Result<(), ProtocolError<PK>> {
-    let sending = sending(sender, data_from_user);
-    let receiving = receiving(receiver, data_for_user);
+    let start = Instant::now();
+    let (heartbeats_for_echo, heartbeats_to_echo) = mpsc::unbounded();
+    let sending = sending::<PK, D, M, S>(
+        sender,
+        data_from_user,
+        heartbeats_to_echo,
+        start,
+        keep_alive,
+        metrics.clone(),
+        direction,
+    );
+    let receiving = receiving::<PK, D, M, R>(
+        receiver,
+        data_for_user,
+        heartbeats_for_echo,
+        start,
+        keep_alive,
+        metrics,
+        latency,
+        direction,
+    );
     tokio::select! {
         result = receiving => result,
         result = sending => result,
@@ -107,6 +249,7 @@ async fn manage_connection<
 
 /// Performs the outgoing handshake, and then manages a connection sending and receiving data.
 /// Exits on parent request, or in case of broken or dead network connection.
+#[allow(clippy::too_many_arguments)]
 pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
     stream: S,
     secret_key: SK,
@@ -114,17 +257,23 @@ pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    latency: LatencyTracker,
+    keep_alive: KeepAlive,
 ) -> Result<(), ProtocolError<SK::PublicKey>> {
     use Event::*;
+    let peer_address_info = stream.peer_address_info();
     trace!(target: LOG_TARGET, "Extending hand to {}.", public_key);
     let (sender, receiver) = v0_handshake_outgoing(stream, secret_key, public_key.clone()).await?;
     info!(
         target: LOG_TARGET,
         "Outgoing handshake with {} finished successfully.", public_key
     );
-    let (data_for_network, data_from_user) = mpsc::unbounded();
+    let (data_for_network, data_from_user) = queue();
     result_for_parent
-        .unbounded_send((public_key.clone(), Some(data_for_network)))
+        .unbounded_send((
+            public_key.clone(),
+            Some((data_for_network, peer_address_info)),
+        ))
         .map_err(|_| ProtocolError::NoParentConnection)?;
     metrics.report_event(ConnectedOutgoing);
 
@@ -132,7 +281,17 @@ pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
         target: LOG_TARGET,
         "Starting worker for communicating with {}.", public_key
     );
-    let result = manage_connection(sender, receiver, data_from_user, data_for_user).await;
+    let result = manage_connection::<_, _, Message<D>, _, _>(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        v1_keep_alive(keep_alive),
+        metrics.clone(),
+        latency,
+        Direction::Outgoing,
+    )
+    .await;
     metrics.report_event(DisconnectedOutgoing);
     result
 }
@@ -140,6 +299,7 @@ pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
 /// Performs the incoming handshake, and then manages a connection sending and receiving data.
 /// Exits on parent request (when the data source is dropped), or in case of broken or dead
 /// network connection.
+#[allow(clippy::too_many_arguments)]
 pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     stream: S,
     secret_key: SK,
@@ -147,10 +307,16 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    latency: LatencyTracker,
+    keep_alive: KeepAlive,
 ) -> Result<(), ProtocolError<SK::PublicKey>> {
     use Event::*;
+    let peer_address_info = stream.peer_address_info();
     trace!(target: LOG_TARGET, "Waiting for extended hand...");
+    let handshake_start = Instant::now();
     let (sender, receiver, public_key) = v0_handshake_incoming(stream, secret_key).await?;
+    // The handshake consists of our challenge and the response to it, so it takes a round trip.
+    latency.report(handshake_start.elapsed());
     info!(
         target: LOG_TARGET,
         "Incoming handshake with {} finished successfully.", public_key
@@ -160,16 +326,29 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
         return Err(ProtocolError::NotAuthorized);
     }
 
-    let (data_for_network, data_from_user) = mpsc::unbounded();
+    let (data_for_network, data_from_user) = queue();
     result_for_parent
-        .unbounded_send((public_key.clone(), Some(data_for_network)))
+        .unbounded_send((
+            public_key.clone(),
+            Some((data_for_network, peer_address_info)),
+        ))
         .map_err(|_| ProtocolError::NoParentConnection)?;
     metrics.report_event(ConnectedIncoming);
     debug!(
         target: LOG_TARGET,
         "Starting worker for communicating with {}.", public_key
     );
-    let result = manage_connection(sender, receiver, data_from_user, data_for_user).await;
+    let result = manage_connection::<_, _, Message<D>, _, _>(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        v1_keep_alive(keep_alive),
+        metrics.clone(),
+        latency,
+        Direction::Incoming,
+    )
+    .await;
     metrics.report_event(DisconnectedIncoming);
     result
 }
@@ -180,18 +359,29 @@ mod tests {
         channel::{mpsc, oneshot},
         pin_mut, Future, FutureExt, StreamExt,
     };
+    use parity_scale_codec::Encode;
+    use tokio::time::{sleep, Duration};
 
+    use super::{v1_keep_alive, Message, WireMessage, V1_HEARTBEAT_INTERVAL};
     use crate::{
+        latency::LatencyTracker,
         metrics::Metrics,
         mock::{key, MockPrelims, MockSplittable},
         protocols::{
             v1::{incoming, outgoing},
-            ProtocolError,
+            KeepAlive, ProtocolError,
         },
         Data,
     };
 
     fn prepare<D: Data>() -> MockPrelims<D> {
+        prepare_with_keep_alive(KeepAlive::default(), KeepAlive::default())
+    }
+
+    fn prepare_with_keep_alive<D: Data>(
+        incoming_keep_alive: KeepAlive,
+        outgoing_keep_alive: KeepAlive,
+    ) -> MockPrelims<D> {
         let (stream_incoming, stream_outgoing) = MockSplittable::new(4096);
         let (id_incoming, pen_incoming) = key();
         let (id_outgoing, pen_outgoing) = key();
@@ -208,6 +398,8 @@ mod tests {
             incoming_result_for_service,
             incoming_data_for_user,
             Metrics::noop(),
+            LatencyTracker::new(),
+            incoming_keep_alive,
         ));
         let outgoing_handle = Box::pin(outgoing(
             stream_outgoing,
@@ -216,6 +408,8 @@ mod tests {
             outgoing_result_for_service,
             outgoing_data_for_user,
             Metrics::noop(),
+            LatencyTracker::new(),
+            outgoing_keep_alive,
         ));
         MockPrelims {
             id_incoming,
@@ -288,7 +482,7 @@ mod tests {
             _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
             result = result_from_outgoing.next() => {
                 let (_, maybe_data_for_outgoing) = result.expect("the channel shouldn't be dropped");
-                let data_for_outgoing = maybe_data_for_outgoing.expect("successfully connected");
+                let (data_for_outgoing, _) = maybe_data_for_outgoing.expect("successfully connected");
                 data_for_outgoing
                     .unbounded_send(vec![4, 3, 43])
                     .expect("should send");
@@ -303,7 +497,7 @@ mod tests {
             _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
             result = result_from_incoming.next() => {
                 let (_, maybe_data_for_incoming) = result.expect("the channel shouldn't be dropped");
-                let data_for_incoming = maybe_data_for_incoming.expect("successfully connected");
+                let (data_for_incoming, _) = maybe_data_for_incoming.expect("successfully connected");
                 data_for_incoming
                     .unbounded_send(vec![5, 4, 44])
                     .expect("should send");
@@ -343,6 +537,84 @@ mod tests {
         };
     }
 
+    #[tokio::test]
+    async fn heartbeats_keep_idle_connection_alive() {
+        let keep_alive = KeepAlive {
+            heartbeat_interval: Duration::from_millis(50),
+            idle_timeout: Duration::from_millis(200),
+        };
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming: _data_from_incoming,
+            data_from_outgoing: _data_from_outgoing,
+            mut result_from_incoming,
+            mut result_from_outgoing,
+            authorization_requests,
+            ..
+        } = prepare_with_keep_alive::<Vec<i32>>(keep_alive, keep_alive);
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = all_pass_authorization_handler(authorization_requests);
+        let mut results = Vec::new();
+        while results.len() < 2 {
+            tokio::select! {
+                _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+                _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+                result = result_from_incoming.next() => results.push(result),
+                result = result_from_outgoing.next() => results.push(result),
+            };
+        }
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            _ = sleep(5 * keep_alive.idle_timeout) => (),
+        };
+    }
+
+    #[tokio::test]
+    async fn silent_peer_disconnected() {
+        let keep_alive = KeepAlive {
+            heartbeat_interval: Duration::from_millis(50),
+            idle_timeout: Duration::from_millis(200),
+        };
+        let silent_keep_alive = KeepAlive {
+            heartbeat_interval: Duration::from_secs(60),
+            idle_timeout: Duration::from_secs(180),
+        };
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming: _data_from_incoming,
+            data_from_outgoing: _data_from_outgoing,
+            mut result_from_incoming,
+            mut result_from_outgoing,
+            authorization_requests,
+            ..
+        } = prepare_with_keep_alive::<Vec<i32>>(keep_alive, silent_keep_alive);
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = all_pass_authorization_handler(authorization_requests);
+        let mut results = Vec::new();
+        loop {
+            tokio::select! {
+                e = &mut incoming_handle => match e {
+                    Err(ProtocolError::CardiacArrest) => break,
+                    Err(e) => panic!("unexpected error: {e}"),
+                    Ok(_) => panic!("successfully finished with a silent peer"),
+                },
+                _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+                result = result_from_incoming.next() => results.push(result),
+                result = result_from_outgoing.next() => results.push(result),
+            };
+        }
+        assert_eq!(results.len(), 2);
+    }
+
     #[tokio::test]
     async fn closed_by_parent_service() {
         let MockPrelims {
@@ -375,6 +647,72 @@ mod tests {
             .expect("closed manually, should finish with no error");
     }
 
+    #[tokio::test]
+    async fn leaves_without_goodbye_after_sending_queued_data() {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming: _data_from_incoming,
+            data_from_outgoing,
+            mut result_from_incoming,
+            result_from_outgoing: _result_from_outgoing,
+            authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>();
+        let mut data_from_outgoing = data_from_outgoing.expect("No data from outgoing!");
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = all_pass_authorization_handler(authorization_requests);
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            received = result_from_incoming.next() => {
+                // we close the data sending channel right after queueing some data
+                let (_, maybe_data_for_incoming) = received.expect("the channel shouldn't be dropped");
+                let (data_for_incoming, _) = maybe_data_for_incoming.expect("successfully connected");
+                data_for_incoming
+                    .unbounded_send(vec![5, 4, 44])
+                    .expect("should send");
+                data_for_incoming
+                    .unbounded_send(vec![3, 2, 4, 8])
+                    .expect("should send");
+                data_for_incoming.close();
+            },
+        };
+        incoming_handle
+            .await
+            .expect("closed manually, should finish with no error");
+        assert_eq!(data_from_outgoing.next().await, Some(vec![5, 4, 44]));
+        assert_eq!(data_from_outgoing.next().await, Some(vec![3, 2, 4, 8]));
+        match outgoing_handle.await {
+            Err(ProtocolError::PeerClosed) => panic!("peers using V1 should not say goodbye"),
+            Err(_) => (),
+            Ok(_) => panic!("successfully finished when peer left"),
+        }
+    }
+
+    #[test]
+    fn wire_format_is_unchanged() {
+        assert_eq!(Message::<Vec<u8>>::heartbeat(7).encode(), vec![1]);
+        assert!(Message::<Vec<u8>>::heartbeat_echo(7).is_none());
+        assert_eq!(Message::data(vec![7u8]).encode(), vec![0, 4, 7]);
+        assert!(Message::<Vec<u8>>::goodbye().is_none());
+    }
+
+    #[test]
+    fn heartbeats_are_frequent_enough_for_old_peers() {
+        let keep_alive = v1_keep_alive(KeepAlive::default());
+        assert_eq!(keep_alive.heartbeat_interval, V1_HEARTBEAT_INTERVAL);
+        assert_eq!(keep_alive.idle_timeout, KeepAlive::default().idle_timeout);
+        let frequent = KeepAlive {
+            heartbeat_interval: Duration::from_millis(50),
+            idle_timeout: Duration::from_millis(200),
+        };
+        assert_eq!(v1_keep_alive(frequent), frequent);
+    }
+
     #[tokio::test]
     async fn parent_service_dead() {
         let MockPrelims {
@@ -426,7 +764,7 @@ mod tests {
             _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
             result = result_from_outgoing.next() => {
                 let (_, maybe_data_for_outgoing) = result.expect("the channel shouldn't be dropped");
-                let data_for_outgoing = maybe_data_for_outgoing.expect("successfully connected");
+                let (data_for_outgoing, _) = maybe_data_for_outgoing.expect("successfully connected");
                 data_for_outgoing
                     .unbounded_send(vec![2, 1, 3, 7])
                     .expect("should send");
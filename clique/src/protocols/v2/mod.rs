@@ -0,0 +1,455 @@
+use std::time::Instant;
+
+use futures::channel::{mpsc, oneshot};
+use log::{debug, info, trace};
+use parity_scale_codec::{Decode, Encode};
+
+use crate::{
+    latency::LatencyTracker,
+    metrics::{Direction, Event, Metrics},
+    protocols::{
+        compression::{negotiate, Compression},
+        handshake::{v0_handshake_incoming, v0_handshake_outgoing},
+        v1::{check_authorization, manage_connection, Frame, WireMessage},
+        KeepAlive, ProtocolError, ResultForService,
+    },
+    queue::queue,
+    ConnectionInfo, Data, SecretKey, Splittable, LOG_TARGET,
+};
+
+#[derive(Debug, Clone, Encode, Decode)]
+enum Message<D: Data> {
+    Data(D),
+    /// Carries the time it was sent, for the peer to echo.
+    Heartbeat(u64),
+    Goodbye,
+    HeartbeatEcho(u64),
+}
+
+impl<D: Data> WireMessage<D> for Message<D> {
+    fn data(data: D) -> Self {
+        Message::Data(data)
+    }
+
+    fn heartbeat(sent_at: u64) -> Self {
+        Message::Heartbeat(sent_at)
+    }
+
+    fn heartbeat_echo(sent_at: u64) -> Option<Self> {
+        Some(Message::HeartbeatEcho(sent_at))
+    }
+
+    fn goodbye() -> Option<Self> {
+        Some(Message::Goodbye)
+    }
+
+    fn into_frame(self) -> Frame<D> {
+        match self {
+            Message::Data(data) => Frame::Data(data),
+            Message::Heartbeat(sent_at) => Frame::Heartbeat(Some(sent_at)),
+            Message::Goodbye => Frame::Goodbye,
+            Message::HeartbeatEcho(sent_at) => Frame::HeartbeatEcho(sent_at),
+        }
+    }
+}
+
+/// Performs the outgoing handshake, negotiates the compression of the stream, and then manages
+/// a connection sending and receiving data.
+/// Exits on parent request, or in case of broken or dead network connection.
+#[allow(clippy::too_many_arguments)]
+pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    public_key: SK::PublicKey,
+    result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
+) -> Result<(), ProtocolError<SK::PublicKey>> {
+    use Event::*;
+    let peer_address_info = stream.peer_address_info();
+    trace!(target: LOG_TARGET, "Extending hand to {}.", public_key);
+    let (sender, receiver) = v0_handshake_outgoing(stream, secret_key, public_key.clone()).await?;
+    info!(
+        target: LOG_TARGET,
+        "Outgoing handshake with {} finished successfully.", public_key
+    );
+    let (sender, receiver, compression) =
+        negotiate::<SK::PublicKey, _, _>(sender, receiver, &compression).await?;
+    debug!(
+        target: LOG_TARGET,
+        "Negotiated {:?} compression with {}.", compression, public_key
+    );
+    let (data_for_network, data_from_user) = queue();
+    result_for_parent
+        .unbounded_send((
+            public_key.clone(),
+            Some((data_for_network, peer_address_info)),
+        ))
+        .map_err(|_| ProtocolError::NoParentConnection)?;
+    metrics.report_event(ConnectedOutgoing);
+
+    debug!(
+        target: LOG_TARGET,
+        "Starting worker for communicating with {}.", public_key
+    );
+    let result = manage_connection::<_, _, Message<D>, _, _>(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        keep_alive,
+        metrics.clone(),
+        latency,
+        Direction::Outgoing,
+    )
+    .await;
+    metrics.report_event(DisconnectedOutgoing);
+    result
+}
+
+/// Performs the incoming handshake, negotiates the compression of the stream, and then manages
+/// a connection sending and receiving data.
+/// Exits on parent request (when the data source is dropped), or in case of broken or dead
+/// network connection.
+#[allow(clippy::too_many_arguments)]
+pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
+    result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
+) -> Result<(), ProtocolError<SK::PublicKey>> {
+    use Event::*;
+    let peer_address_info = stream.peer_address_info();
+    trace!(target: LOG_TARGET, "Waiting for extended hand...");
+    let handshake_start = Instant::now();
+    let (sender, receiver, public_key) = v0_handshake_incoming(stream, secret_key).await?;
+    // The handshake consists of our challenge and the response to it, so it takes a round trip.
+    latency.report(handshake_start.elapsed());
+    info!(
+        target: LOG_TARGET,
+        "Incoming handshake with {} finished successfully.", public_key
+    );
+
+    if !check_authorization::<SK>(authorization_requests_sender, public_key.clone()).await? {
+        return Err(ProtocolError::NotAuthorized);
+    }
+
+    let (sender, receiver, compression) =
+        negotiate::<SK::PublicKey, _, _>(sender, receiver, &compression).await?;
+    debug!(
+        target: LOG_TARGET,
+        "Negotiated {:?} compression with {}.", compression, public_key
+    );
+    let (data_for_network, data_from_user) = queue();
+    result_for_parent
+        .unbounded_send((
+            public_key.clone(),
+            Some((data_for_network, peer_address_info)),
+        ))
+        .map_err(|_| ProtocolError::NoParentConnection)?;
+    metrics.report_event(ConnectedIncoming);
+    debug!(
+        target: LOG_TARGET,
+        "Starting worker for communicating with {}.", public_key
+    );
+    let result = manage_connection::<_, _, Message<D>, _, _>(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        keep_alive,
+        metrics.clone(),
+        latency,
+        Direction::Incoming,
+    )
+    .await;
+    metrics.report_event(DisconnectedIncoming);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc, pin_mut, FutureExt, StreamExt};
+    use tokio::time::{sleep, Duration};
+
+    use crate::{
+        latency::LatencyTracker,
+        metrics::Metrics,
+        mock::{key, MockPrelims, MockSplittable},
+        protocols::{
+            v2::{incoming, outgoing},
+            Compression, KeepAlive, ProtocolError,
+        },
+        Data,
+    };
+
+    fn prepare<D: Data>(
+        incoming_compression: Vec<Compression>,
+        outgoing_compression: Vec<Compression>,
+    ) -> MockPrelims<D> {
+        prepare_with_keep_alive(
+            incoming_compression,
+            outgoing_compression,
+            KeepAlive::default(),
+            LatencyTracker::new(),
+        )
+    }
+
+    fn prepare_with_keep_alive<D: Data>(
+        incoming_compression: Vec<Compression>,
+        outgoing_compression: Vec<Compression>,
+        keep_alive: KeepAlive,
+        outgoing_latency: LatencyTracker,
+    ) -> MockPrelims<D> {
+        let (stream_incoming, stream_outgoing) = MockSplittable::new(4096);
+        let (id_incoming, pen_incoming) = key();
+        let (id_outgoing, pen_outgoing) = key();
+        assert_ne!(id_incoming, id_outgoing);
+        let (incoming_result_for_service, result_from_incoming) = mpsc::unbounded();
+        let (outgoing_result_for_service, result_from_outgoing) = mpsc::unbounded();
+        let (incoming_data_for_user, data_from_incoming) = mpsc::unbounded::<D>();
+        let (outgoing_data_for_user, data_from_outgoing) = mpsc::unbounded::<D>();
+        let (authorization_requests_sender, authorization_requests) = mpsc::unbounded();
+        let incoming_handle = Box::pin(incoming(
+            stream_incoming,
+            pen_incoming.clone(),
+            authorization_requests_sender,
+            incoming_result_for_service,
+            incoming_data_for_user,
+            Metrics::noop(),
+            LatencyTracker::new(),
+            incoming_compression,
+            keep_alive,
+        ));
+        let outgoing_handle = Box::pin(outgoing(
+            stream_outgoing,
+            pen_outgoing.clone(),
+            id_incoming.clone(),
+            outgoing_result_for_service,
+            outgoing_data_for_user,
+            Metrics::noop(),
+            outgoing_latency,
+            outgoing_compression,
+            keep_alive,
+        ));
+        MockPrelims {
+            id_incoming,
+            pen_incoming,
+            id_outgoing,
+            pen_outgoing,
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming,
+            data_from_outgoing: Some(data_from_outgoing),
+            result_from_incoming,
+            result_from_outgoing,
+            authorization_requests,
+        }
+    }
+
+    async fn exchange_data(
+        incoming_compression: Vec<Compression>,
+        outgoing_compression: Vec<Compression>,
+    ) {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            mut data_from_incoming,
+            data_from_outgoing,
+            mut result_from_incoming,
+            mut result_from_outgoing,
+            mut authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>(incoming_compression, outgoing_compression);
+        let mut data_from_outgoing = data_from_outgoing.expect("No data from outgoing!");
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = tokio::spawn(async move {
+            let (_, response_sender) = authorization_requests
+                .next()
+                .await
+                .expect("We should recieve at least one authorization request.");
+            response_sender
+                .send(true)
+                .expect("We should be able to send back an authorization response.");
+        });
+        let _data_for_outgoing = tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            result = result_from_outgoing.next() => {
+                let (_, maybe_data_for_outgoing) = result.expect("the channel shouldn't be dropped");
+                let (data_for_outgoing, _) = maybe_data_for_outgoing.expect("successfully connected");
+                data_for_outgoing
+                    .unbounded_send(vec![4; 10_000])
+                    .expect("should send");
+                data_for_outgoing
+            },
+        };
+        let _data_for_incoming = tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            result = result_from_incoming.next() => {
+                let (_, maybe_data_for_incoming) = result.expect("the channel shouldn't be dropped");
+                let (data_for_incoming, _) = maybe_data_for_incoming.expect("successfully connected");
+                data_for_incoming
+                    .unbounded_send(vec![5, 4, 44])
+                    .expect("should send");
+                data_for_incoming
+            },
+        };
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            v = data_from_incoming.next() => {
+                assert_eq!(v, Some(vec![4; 10_000]));
+            },
+        };
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            v = data_from_outgoing.next() => {
+                assert_eq!(v, Some(vec![5, 4, 44]));
+            },
+        };
+    }
+
+    #[tokio::test]
+    async fn send_compressed_data() {
+        exchange_data(
+            vec![Compression::Zstd, Compression::None],
+            vec![Compression::Zstd, Compression::None],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn send_data_to_uncompressed_peer() {
+        exchange_data(
+            vec![Compression::None],
+            vec![Compression::Zstd, Compression::None],
+        )
+        .await;
+        exchange_data(
+            vec![Compression::Zstd, Compression::None],
+            vec![Compression::None],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn says_goodbye_after_sending_queued_data() {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming: _data_from_incoming,
+            data_from_outgoing,
+            mut result_from_incoming,
+            result_from_outgoing: _result_from_outgoing,
+            mut authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>(vec![Compression::None], vec![Compression::None]);
+        let mut data_from_outgoing = data_from_outgoing.expect("No data from outgoing!");
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = tokio::spawn(async move {
+            let (_, response_sender) = authorization_requests
+                .next()
+                .await
+                .expect("We should recieve at least one authorization request.");
+            response_sender
+                .send(true)
+                .expect("We should be able to send back an authorization response.");
+        });
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            received = result_from_incoming.next() => {
+                // we close the data sending channel right after queueing some data
+                let (_, maybe_data_for_incoming) = received.expect("the channel shouldn't be dropped");
+                let (data_for_incoming, _) = maybe_data_for_incoming.expect("successfully connected");
+                data_for_incoming
+                    .unbounded_send(vec![5, 4, 44])
+                    .expect("should send");
+                data_for_incoming
+                    .unbounded_send(vec![3, 2, 4, 8])
+                    .expect("should send");
+                data_for_incoming.close();
+            },
+        };
+        incoming_handle
+            .await
+            .expect("closed manually, should finish with no error");
+        assert_eq!(data_from_outgoing.next().await, Some(vec![5, 4, 44]));
+        assert_eq!(data_from_outgoing.next().await, Some(vec![3, 2, 4, 8]));
+        match outgoing_handle.await {
+            Err(ProtocolError::PeerClosed) => (),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("successfully finished when peer left"),
+        }
+    }
+
+    #[tokio::test]
+    async fn heartbeat_echoes_measure_round_trip() {
+        let keep_alive = KeepAlive {
+            heartbeat_interval: Duration::from_millis(50),
+            idle_timeout: Duration::from_millis(200),
+        };
+        // Only the incoming side measures the handshake, so any estimate here comes from heartbeats.
+        let outgoing_latency = LatencyTracker::new();
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming: _data_from_incoming,
+            data_from_outgoing: _data_from_outgoing,
+            mut result_from_incoming,
+            mut result_from_outgoing,
+            mut authorization_requests,
+            ..
+        } = prepare_with_keep_alive::<Vec<i32>>(
+            vec![Compression::None],
+            vec![Compression::None],
+            keep_alive,
+            outgoing_latency.clone(),
+        );
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = tokio::spawn(async move {
+            let (_, response_sender) = authorization_requests
+                .next()
+                .await
+                .expect("We should recieve at least one authorization request.");
+            response_sender
+                .send(true)
+                .expect("We should be able to send back an authorization response.");
+        });
+        let mut results = Vec::new();
+        while results.len() < 2 {
+            tokio::select! {
+                _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+                _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+                result = result_from_incoming.next() => results.push(result),
+                result = result_from_outgoing.next() => results.push(result),
+            };
+        }
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            _ = sleep(4 * keep_alive.heartbeat_interval) => (),
+        };
+        assert!(outgoing_latency.round_trip().is_some());
+    }
+}
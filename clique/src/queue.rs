@@ -0,0 +1,196 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Error as FmtError, Formatter},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use futures::Stream;
+
+/// Error returned when sending to a queue whose receiver is gone.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueueClosed;
+
+impl Display for QueueClosed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "queue receiver dropped")
+    }
+}
+
+struct Shared<D> {
+    items: VecDeque<D>,
+    receiver_waker: Option<Waker>,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+/// Sending end of a queue of messages for a single peer. Unlike a channel, it allows checking
+/// how many messages are waiting and discarding the oldest ones.
+pub struct QueueSender<D> {
+    shared: Arc<Mutex<Shared<D>>>,
+}
+
+/// Receiving end of a queue of messages for a single peer.
+pub struct QueueReceiver<D> {
+    shared: Arc<Mutex<Shared<D>>>,
+}
+
+/// Create a new, initially unbounded, queue.
+pub fn queue<D>() -> (QueueSender<D>, QueueReceiver<D>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        items: VecDeque::new(),
+        receiver_waker: None,
+        sender_alive: true,
+        receiver_alive: true,
+    }));
+    (
+        QueueSender {
+            shared: shared.clone(),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+impl<D> QueueSender<D> {
+    /// Append the data to the queue.
+    pub fn unbounded_send(&self, data: D) -> Result<(), QueueClosed> {
+        self.send_dropping_oldest(data, usize::MAX).map(|_| ())
+    }
+
+    /// Append the data to the queue, discarding the oldest messages so that no more than
+    /// `max_depth` remain. Returns how many messages were discarded.
+    pub fn send_dropping_oldest(&self, data: D, max_depth: usize) -> Result<usize, QueueClosed> {
+        let mut shared = self.shared.lock().expect("queue lock poisoned");
+        if !shared.receiver_alive || !shared.sender_alive {
+            return Err(QueueClosed);
+        }
+        shared.items.push_back(data);
+        let mut dropped = 0;
+        while shared.items.len() > max_depth.max(1) {
+            shared.items.pop_front();
+            dropped += 1;
+        }
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+        Ok(dropped)
+    }
+
+    /// Number of messages waiting in the queue.
+    pub fn depth(&self) -> usize {
+        self.shared.lock().expect("queue lock poisoned").items.len()
+    }
+
+    /// Stop accepting messages. The receiver still gets the messages already in the queue, after
+    /// which the stream ends, just as if the sender was dropped. Unlike dropping, this allows
+    /// checking when the receiver is done.
+    pub fn close(&self) {
+        let mut shared = self.shared.lock().expect("queue lock poisoned");
+        shared.sender_alive = false;
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether the receiver is gone, i.e. nothing sent will ever be received.
+    pub fn is_closed(&self) -> bool {
+        !self
+            .shared
+            .lock()
+            .expect("queue lock poisoned")
+            .receiver_alive
+    }
+}
+
+impl<D> Drop for QueueSender<D> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.sender_alive = false;
+            if let Some(waker) = shared.receiver_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<D> Drop for QueueReceiver<D> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.receiver_alive = false;
+            shared.items.clear();
+        }
+    }
+}
+
+impl<D> Stream for QueueReceiver<D> {
+    type Item = D;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<D>> {
+        let mut shared = self.shared.lock().expect("queue lock poisoned");
+        match shared.items.pop_front() {
+            Some(data) => Poll::Ready(Some(data)),
+            None if !shared.sender_alive => Poll::Ready(None),
+            None => {
+                shared.receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{FutureExt, StreamExt};
+
+    use super::{queue, QueueClosed};
+
+    #[tokio::test]
+    async fn sends_and_receives_in_order() {
+        let (sender, mut receiver) = queue();
+        sender.unbounded_send(1).expect("should send");
+        sender.unbounded_send(2).expect("should send");
+        assert_eq!(sender.depth(), 2);
+        assert_eq!(receiver.next().await, Some(1));
+        assert_eq!(receiver.next().await, Some(2));
+        assert_eq!(sender.depth(), 0);
+        assert!(receiver.next().now_or_never().is_none());
+        drop(sender);
+        assert_eq!(receiver.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_above_max_depth() {
+        let (sender, mut receiver) = queue();
+        for i in 0..5 {
+            let expected_dropped = if i < 3 { 0 } else { 1 };
+            assert_eq!(sender.send_dropping_oldest(i, 3), Ok(expected_dropped));
+        }
+        assert_eq!(sender.depth(), 3);
+        assert_eq!(receiver.next().await, Some(2));
+        assert_eq!(receiver.next().await, Some(3));
+        assert_eq!(receiver.next().await, Some(4));
+    }
+
+    #[tokio::test]
+    async fn delivers_queued_messages_after_close() {
+        let (sender, mut receiver) = queue();
+        sender.unbounded_send(1).expect("should send");
+        sender.close();
+        assert_eq!(sender.unbounded_send(2), Err(QueueClosed));
+        assert_eq!(receiver.next().await, Some(1));
+        assert_eq!(receiver.next().await, None);
+        assert!(!sender.is_closed());
+        drop(receiver);
+        assert!(sender.is_closed());
+    }
+
+    #[test]
+    fn fails_when_receiver_dropped() {
+        let (sender, receiver) = queue();
+        assert!(!sender.is_closed());
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert_eq!(sender.unbounded_send(1), Err(QueueClosed));
+    }
+}
@@ -16,20 +16,27 @@ use substrate_prometheus_endpoint::Registry;
 use tokio::time;
 
 use crate::{
+    events::{ConnectionEvent, ConnectionEvents},
     incoming::incoming,
+    latency::LatencyTracker,
     manager::{AddResult, Manager},
     metrics::Metrics,
     outgoing::outgoing,
-    protocols::ResultForService,
-    Data, Dialer, Listener, Network, PeerId, PublicKey, SecretKey, LOG_TARGET,
+    protocols::{Compression, KeepAlive, ResultForService},
+    queue::QueueSender,
+    Data, Dialer, Listener, Network, PeerAddressInfo, PeerId, PublicKey, SecretKey, LOG_TARGET,
 };
 
 const STATUS_REPORT_INTERVAL: Duration = Duration::from_secs(20);
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
 enum ServiceCommand<PK: PublicKey, D: Data, A: Data> {
     AddConnection(PK, A),
     DelConnection(PK),
     SendData(D, PK),
+    SendDataToMultiple(D, Vec<PK>),
 }
 
 struct ServiceInterface<PK: PublicKey, D: Data, A: Data> {
@@ -73,6 +80,20 @@ impl<PK: PublicKey, D: Data, A: Data> Network<PK, A, D> for ServiceInterface<PK,
         };
     }
 
+    /// Send a message to multiple peers, passing it to the service only once.
+    fn send_to_multiple(&self, data: D, recipients: Vec<PK>) {
+        if recipients.is_empty() {
+            return;
+        }
+        if self
+            .commands_for_service
+            .unbounded_send(ServiceCommand::SendDataToMultiple(data, recipients))
+            .is_err()
+        {
+            info!(target: LOG_TARGET, "Service is dead.");
+        };
+    }
+
     /// Receive a message from the network.
     async fn next(&mut self) -> Option<D> {
         self.next_from_service.next().await
@@ -148,6 +169,10 @@ where
     spawn_handle: SH,
     secret_key: SK,
     metrics: Metrics,
+    latency: LatencyTracker,
+    compression: Vec<Compression>,
+    keep_alive: KeepAlive,
+    connection_events: ConnectionEvents<SK::PublicKey>,
 }
 
 impl<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>, NL: Listener, SH: SpawnHandleT>
@@ -156,12 +181,15 @@ where
     SK::PublicKey: PeerId,
 {
     /// Create a new clique network service plus an interface for interacting with it.
+    /// The `keep_alive` settings determine how often heartbeats are sent on idle connections and
+    /// after how long without hearing from a peer its connection is considered dead.
     pub fn new(
         dialer: ND,
         listener: NL,
         secret_key: SK,
         spawn_handle: SH,
         metrics_registry: Option<Registry>,
+        keep_alive: KeepAlive,
     ) -> (Self, impl Network<SK::PublicKey, A, D>) {
         // Channel for sending commands between the service and interface
         let (commands_for_service, commands_from_interface) = mpsc::unbounded();
@@ -184,6 +212,10 @@ where
                 spawn_handle,
                 secret_key,
                 metrics,
+                latency: LatencyTracker::new(),
+                compression: vec![Compression::None],
+                keep_alive,
+                connection_events: ConnectionEvents::new(),
             },
             ServiceInterface {
                 commands_for_service,
@@ -192,6 +224,39 @@ where
         )
     }
 
+    /// Set the codecs this node is willing to compress its connections with. The most preferred
+    /// codec supported by both sides is used, peers not supporting any of them (including ones
+    /// running older protocol versions) fall back to no compression. By default no compression
+    /// is used.
+    pub fn set_compression(&mut self, compression: Vec<Compression>) {
+        self.compression = compression;
+    }
+
+    /// Limit the number of messages waiting to be sent to a single peer. Once the limit is
+    /// exceeded the oldest messages for that peer are dropped, so that a peer unable to keep up
+    /// does not make us run out of memory. By default the queues are unbounded.
+    pub fn with_max_queue_depth(mut self, max_queue_depth: usize) -> Self {
+        self.manager.set_max_queue_depth(max_queue_depth);
+        self
+    }
+
+    /// Estimate of the round-trip time to the peers, updated whenever a peer connects to us.
+    pub fn latency_tracker(&self) -> LatencyTracker {
+        self.latency.clone()
+    }
+
+    /// Number of messages waiting to be sent to the peer, `None` if we are not connected to it.
+    pub fn queue_depth(&self, peer: &SK::PublicKey) -> Option<usize> {
+        self.manager.queue_depth(peer)
+    }
+
+    /// Returns a stream of events informing when connections with peers get established or
+    /// dropped. Every such change is reported exactly once, so a peer reconnecting after a drop
+    /// results in a new `Connected` event.
+    pub fn connection_events(&mut self) -> mpsc::UnboundedReceiver<ConnectionEvent<SK::PublicKey>> {
+        self.connection_events.subscribe()
+    }
+
     fn spawn_new_outgoing(
         &mut self,
         public_key: SK::PublicKey,
@@ -202,6 +267,9 @@ where
         let dialer = self.dialer.clone();
         let next_to_interface = self.next_to_interface.clone();
         let metrics = self.metrics.clone();
+        let latency = self.latency.clone();
+        let compression = self.compression.clone();
+        let keep_alive = self.keep_alive;
         self.spawn_handle
             .spawn("aleph/clique_network_outgoing", async move {
                 outgoing(
@@ -212,6 +280,9 @@ where
                     result_for_parent,
                     next_to_interface,
                     metrics,
+                    latency,
+                    compression,
+                    keep_alive,
                 )
                 .await;
             });
@@ -229,6 +300,9 @@ where
         let secret_key = self.secret_key.clone();
         let next_to_interface = self.next_to_interface.clone();
         let metrics = self.metrics.clone();
+        let latency = self.latency.clone();
+        let compression = self.compression.clone();
+        let keep_alive = self.keep_alive;
         self.spawn_handle
             .spawn("aleph/clique_network_incoming", async move {
                 incoming(
@@ -238,6 +312,9 @@ where
                     next_to_interface,
                     authorization_requests_sender,
                     metrics,
+                    latency,
+                    compression,
+                    keep_alive,
                 )
                 .await;
             });
@@ -250,7 +327,7 @@ where
     fn add_connection(
         &mut self,
         public_key: SK::PublicKey,
-        data_for_network: mpsc::UnboundedSender<D>,
+        data_for_network: QueueSender<D>,
     ) -> AddResult {
         self.manager.add_connection(public_key, data_for_network)
     }
@@ -258,10 +335,7 @@ where
     fn handle_command(
         &mut self,
         command: ServiceCommand<<SK as SecretKey>::PublicKey, D, A>,
-        result_for_parent: &UnboundedSender<(
-            <SK as SecretKey>::PublicKey,
-            Option<UnboundedSender<D>>,
-        )>,
+        result_for_parent: &UnboundedSender<ResultForService<SK::PublicKey, D>>,
     ) {
         use ServiceCommand::*;
         match command {
@@ -275,6 +349,7 @@ where
             // remove the peer from the manager all workers will be killed automatically, due to closed channels
             DelConnection(public_key) => {
                 self.manager.remove_peer(&public_key);
+                self.connection_events.disconnected(&public_key);
             }
             // pass the data to the manager
             SendData(data, public_key) => match self.manager.send_to(&public_key, data) {
@@ -286,6 +361,17 @@ where
                     e
                 ),
             },
+            // pass the data to the manager, which sends it to every connected recipient
+            SendDataToMultiple(data, public_keys) => {
+                for (public_key, e) in self.manager.send_to_multiple(public_keys, data) {
+                    trace!(
+                        target: LOG_TARGET,
+                        "Failed sending to {}: {}",
+                        public_key,
+                        e
+                    );
+                }
+            }
         }
     }
 
@@ -306,28 +392,37 @@ where
     fn handle_data_for_network(
         &mut self,
         public_key: <SK as SecretKey>::PublicKey,
-        maybe_data_for_network: Option<UnboundedSender<D>>,
-        result_for_parent: &UnboundedSender<(
-            <SK as SecretKey>::PublicKey,
-            Option<UnboundedSender<D>>,
-        )>,
+        maybe_data_for_network: Option<(QueueSender<D>, PeerAddressInfo)>,
+        result_for_parent: &UnboundedSender<ResultForService<SK::PublicKey, D>>,
     ) {
         use AddResult::*;
+        // The previous connection might have died without us noticing yet.
+        if !self.manager.active_connection(&public_key) {
+            self.connection_events.disconnected(&public_key);
+        }
         match maybe_data_for_network {
-            Some(data_for_network) => {
+            Some((data_for_network, peer_address_info)) => {
                 match self.add_connection(public_key.clone(), data_for_network) {
                     Uninterested => warn!(
                         target: LOG_TARGET,
                         "Established connection with peer {} for unknown reasons.", public_key
                     ),
-                    Added => info!(
-                        target: LOG_TARGET,
-                        "New connection with peer {}.", public_key
-                    ),
-                    Replaced => info!(
-                        target: LOG_TARGET,
-                        "Replaced connection with peer {}.", public_key
-                    ),
+                    Added => {
+                        info!(
+                            target: LOG_TARGET,
+                            "New connection with peer {}.", public_key
+                        );
+                        self.connection_events
+                            .connected(public_key, peer_address_info);
+                    }
+                    Replaced => {
+                        info!(
+                            target: LOG_TARGET,
+                            "Replaced connection with peer {}.", public_key
+                        );
+                        self.connection_events
+                            .connected(public_key, peer_address_info);
+                    }
                 }
             }
             None => {
@@ -338,9 +433,49 @@ where
         }
     }
 
-    /// Run the service until a signal from exit.
+    fn check_connections(&mut self) {
+        let dropped: Vec<_> = self
+            .connection_events
+            .connected_peers()
+            .filter(|public_key| !self.manager.active_connection(public_key))
+            .cloned()
+            .collect();
+        for public_key in dropped {
+            self.connection_events.disconnected(&public_key);
+        }
+        self.metrics
+            .report_connected_peers(self.connection_events.connected_peers().count());
+    }
+
+    /// Close all connections, letting them send the data still waiting for the peers and say
+    /// goodbye. Waits until they finish, but no longer than `DRAIN_TIMEOUT`.
+    async fn drain_connections(&mut self) {
+        let queues = self.manager.close_connections();
+        info!(
+            target: LOG_TARGET,
+            "Draining {} connections.",
+            queues.len()
+        );
+        let mut drain_check_ticker = time::interval(DRAIN_CHECK_INTERVAL);
+        let drained = async {
+            while !queues.iter().all(|queue| queue.is_closed()) {
+                drain_check_ticker.tick().await;
+            }
+        };
+        if time::timeout(DRAIN_TIMEOUT, drained).await.is_err() {
+            warn!(
+                target: LOG_TARGET,
+                "Connections did not drain within {}s, closing them anyway.",
+                DRAIN_TIMEOUT.as_secs()
+            );
+        }
+    }
+
+    /// Run the service until a signal from exit. Before returning, the established connections
+    /// are drained, so that the peers get the remaining data and know we left on purpose.
     pub async fn run(mut self, mut exit: oneshot::Receiver<()>) -> Result<(), Error> {
         let mut status_ticker = time::interval(STATUS_REPORT_INTERVAL);
+        let mut connection_check_ticker = time::interval(CONNECTION_CHECK_INTERVAL);
         let (result_for_parent, mut worker_results) = mpsc::unbounded();
         let (authorization_requests_sender, mut authorization_requests) = mpsc::unbounded();
         loop {
@@ -365,15 +500,19 @@ where
                     let (public_key, maybe_data_for_network) = maybe_data_for_network.ok_or(Error::ConnectionWorker)?;
                     self.handle_data_for_network(public_key, maybe_data_for_network, &result_for_parent);
                 },
+                // periodically checking whether any of the connections died
+                _ = connection_check_ticker.tick() => self.check_connections(),
                 // periodically reporting what we are trying to do
                 _ = status_ticker.tick() => {
                     info!(target: LOG_TARGET, "Clique Network status: {}", self.manager.status_report());
                 }
                 // received exit signal, stop the network
-                // all workers will be killed automatically after the manager gets dropped
+                // established connections are drained, other workers will be killed
+                // automatically after the service gets dropped
                 _ = &mut exit => break,
             };
         }
+        self.drain_connections().await;
         Ok(())
     }
 }
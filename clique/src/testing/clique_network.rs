@@ -19,7 +19,7 @@ use crate::{
         UnreliableConnectionMaker,
     },
     service::SpawnHandleT,
-    Network, SecretKey, Service,
+    KeepAlive, Network, SecretKey, Service,
 };
 
 impl SpawnHandleT for Spawner {
@@ -45,7 +45,14 @@ fn spawn_peer(
     spawn_handle: Spawner,
 ) {
     let our_id = secret_key.public_key();
-    let (service, mut interface) = Service::new(dialer, listener, secret_key, spawn_handle, None);
+    let (service, mut interface) = Service::new(
+        dialer,
+        listener,
+        secret_key,
+        spawn_handle,
+        None,
+        KeepAlive::default(),
+    );
     // run the service
     tokio::spawn(async {
         let (_exit, rx) = oneshot::channel();
@@ -0,0 +1,350 @@
+//! TLS-wrapped connections. The clique protocols authenticate peers and encrypt the data on
+//! their own, TLS adds another layer of protection against passive observers of the traffic.
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, ClientConfig, PrivateKey, ServerConfig, ServerName,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf, ReadHalf, WriteHalf},
+    net::TcpStream,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+
+use crate::{ConnectionInfo, Data, DialError, Dialer, Listener, PeerAddressInfo, Splittable};
+
+/// Name put into the self-signed certificates, peers do not rely on it in any way.
+const SERVER_NAME: &str = "clique";
+
+/// PKCS#8 v1 prefix of an ed25519 private key, to be followed by the 32 byte seed.
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+/// Errors that can occur when setting up TLS.
+#[derive(Debug)]
+pub enum TlsError {
+    Certificate(rcgen::RcgenError),
+    Config(rustls::Error),
+}
+
+impl Display for TlsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use TlsError::*;
+        match self {
+            Certificate(e) => write!(f, "failed to create certificate: {e}"),
+            Config(e) => write!(f, "invalid TLS configuration: {e}"),
+        }
+    }
+}
+
+impl From<rcgen::RcgenError> for TlsError {
+    fn from(e: rcgen::RcgenError) -> Self {
+        TlsError::Certificate(e)
+    }
+}
+
+impl From<rustls::Error> for TlsError {
+    fn from(e: rustls::Error) -> Self {
+        TlsError::Config(e)
+    }
+}
+
+/// A self-signed certificate of the node, together with its private key.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    certificate: Certificate,
+    private_key: PrivateKey,
+}
+
+impl TlsIdentity {
+    /// Create a self-signed certificate for the ed25519 key given by its seed, which should be
+    /// the key the node uses as its network identity.
+    pub fn from_ed25519_seed(seed: &[u8; 32]) -> Result<Self, TlsError> {
+        let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+        pkcs8.extend_from_slice(seed);
+        let mut params = rcgen::CertificateParams::new(vec![SERVER_NAME.to_string()]);
+        params.alg = &rcgen::PKCS_ED25519;
+        params.key_pair = Some(rcgen::KeyPair::from_der(&pkcs8)?);
+        let certificate = rcgen::Certificate::from_params(params)?;
+        Ok(TlsIdentity {
+            certificate: Certificate(certificate.serialize_der()?),
+            private_key: PrivateKey(certificate.serialize_private_key_der()),
+        })
+    }
+}
+
+/// Certificates are self-signed, so they cannot be verified against any authority. Whether the
+/// peer is who it claims to be is checked by the clique handshake afterwards.
+struct AcceptSelfSigned;
+
+impl ServerCertVerifier for AcceptSelfSigned {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// One half of a split TLS stream, remembering the address of the peer.
+pub struct TlsHalf<H> {
+    half: H,
+    peer_address_info: PeerAddressInfo,
+}
+
+impl<H> ConnectionInfo for TlsHalf<H> {
+    fn peer_address_info(&self) -> PeerAddressInfo {
+        self.peer_address_info.clone()
+    }
+}
+
+impl<H: AsyncRead + Unpin> AsyncRead for TlsHalf<H> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.half).poll_read(cx, buf)
+    }
+}
+
+impl<H: AsyncWrite + Unpin> AsyncWrite for TlsHalf<H> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, io::Error>> {
+        Pin::new(&mut self.half).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        Pin::new(&mut self.half).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        Pin::new(&mut self.half).poll_shutdown(cx)
+    }
+}
+
+impl ConnectionInfo for TlsStream<TcpStream> {
+    fn peer_address_info(&self) -> PeerAddressInfo {
+        self.get_ref().0.peer_address_info()
+    }
+}
+
+impl Splittable for TlsStream<TcpStream> {
+    type Sender = TlsHalf<WriteHalf<TlsStream<TcpStream>>>;
+    type Receiver = TlsHalf<ReadHalf<TlsStream<TcpStream>>>;
+
+    fn split(self) -> (Self::Sender, Self::Receiver) {
+        let peer_address_info = self.peer_address_info();
+        let (receiver, sender) = tokio::io::split(self);
+        (
+            TlsHalf {
+                half: sender,
+                peer_address_info: peer_address_info.clone(),
+            },
+            TlsHalf {
+                half: receiver,
+                peer_address_info,
+            },
+        )
+    }
+}
+
+/// Errors when dialing a peer over TLS.
+pub enum TlsDialerError<E> {
+    Dial(E),
+    Handshake(io::Error),
+}
+
+impl<E: Display> Display for TlsDialerError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use TlsDialerError::*;
+        match self {
+            Dial(e) => write!(f, "{e}"),
+            Handshake(e) => write!(f, "TLS handshake failed: {e}"),
+        }
+    }
+}
+
+impl<E: Into<DialError>> From<TlsDialerError<E>> for DialError {
+    fn from(e: TlsDialerError<E>) -> Self {
+        use TlsDialerError::*;
+        match e {
+            Dial(e) => e.into(),
+            Handshake(e) => e.into(),
+        }
+    }
+}
+
+/// Wraps connections made by the underlying dialer in TLS.
+#[derive(Clone)]
+pub struct TlsDialer<ND> {
+    dialer: ND,
+    connector: TlsConnector,
+}
+
+impl<ND> TlsDialer<ND> {
+    pub fn new(dialer: ND) -> Self {
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(AcceptSelfSigned))
+            .with_no_client_auth();
+        TlsDialer {
+            dialer,
+            connector: TlsConnector::from(Arc::new(config)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: Data, ND: Dialer<A, Connection = TcpStream>> Dialer<A> for TlsDialer<ND> {
+    type Connection = TlsStream<TcpStream>;
+    type Error = TlsDialerError<ND::Error>;
+
+    async fn connect(&mut self, address: A) -> Result<Self::Connection, Self::Error> {
+        let stream = self
+            .dialer
+            .connect(address)
+            .await
+            .map_err(TlsDialerError::Dial)?;
+        let server_name = ServerName::try_from(SERVER_NAME).expect("the name is valid");
+        let stream = self
+            .connector
+            .connect(server_name, stream)
+            .await
+            .map_err(TlsDialerError::Handshake)?;
+        Ok(TlsStream::Client(stream))
+    }
+}
+
+/// Errors when accepting a connection over TLS.
+pub enum TlsListenerError<E> {
+    Accept(E),
+    Handshake(io::Error),
+}
+
+impl<E: Display> Display for TlsListenerError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use TlsListenerError::*;
+        match self {
+            Accept(e) => write!(f, "{e}"),
+            Handshake(e) => write!(f, "TLS handshake failed: {e}"),
+        }
+    }
+}
+
+/// Wraps connections accepted by the underlying listener in TLS, presenting the given identity.
+pub struct TlsListener<NL> {
+    listener: NL,
+    acceptor: TlsAcceptor,
+}
+
+impl<NL> TlsListener<NL> {
+    pub fn new(listener: NL, identity: TlsIdentity) -> Result<Self, TlsError> {
+        let TlsIdentity {
+            certificate,
+            private_key,
+        } = identity;
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(vec![certificate], private_key)?;
+        Ok(TlsListener {
+            listener,
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<NL: Listener<Connection = TcpStream> + Send> Listener for TlsListener<NL> {
+    type Connection = TlsStream<TcpStream>;
+    type Error = TlsListenerError<NL::Error>;
+
+    async fn accept(&mut self) -> Result<Self::Connection, Self::Error> {
+        let stream = self
+            .listener
+            .accept()
+            .await
+            .map_err(TlsListenerError::Accept)?;
+        let stream = self
+            .acceptor
+            .accept(stream)
+            .await
+            .map_err(TlsListenerError::Handshake)?;
+        Ok(TlsStream::Server(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::{TlsDialer, TlsIdentity, TlsListener};
+    use crate::{ConnectionInfo, Dialer, Listener, Splittable};
+
+    #[derive(Clone)]
+    struct TcpDialer;
+
+    #[async_trait::async_trait]
+    impl Dialer<String> for TcpDialer {
+        type Connection = TcpStream;
+        type Error = std::io::Error;
+
+        async fn connect(&mut self, address: String) -> Result<Self::Connection, Self::Error> {
+            TcpStream::connect(address).await
+        }
+    }
+
+    #[tokio::test]
+    async fn exchanges_data_over_tls() {
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.expect("should bind");
+        let address = tcp_listener
+            .local_addr()
+            .expect("should have an address")
+            .to_string();
+        let identity = TlsIdentity::from_ed25519_seed(&[7; 32]).expect("should create identity");
+        let mut listener = TlsListener::new(tcp_listener, identity).expect("should configure");
+        let mut dialer = TlsDialer::new(TcpDialer);
+
+        let (outgoing, incoming) = tokio::join!(dialer.connect(address.clone()), listener.accept());
+        let outgoing = outgoing.unwrap_or_else(|e| panic!("dialing failed: {e}"));
+        let incoming = incoming.unwrap_or_else(|e| panic!("accepting failed: {e}"));
+        assert_eq!(outgoing.peer_address_info(), address);
+
+        let (mut sender, _outgoing_receiver) = outgoing.split();
+        let (_incoming_sender, mut receiver) = incoming.split();
+        assert_eq!(sender.peer_address_info(), address);
+        sender.write_all(b"clique").await.expect("should send");
+        sender.flush().await.expect("should flush");
+        let mut received = [0; 6];
+        receiver
+            .read_exact(&mut received)
+            .await
+            .expect("should receive");
+        assert_eq!(&received, b"clique");
+    }
+}
@@ -17,9 +17,12 @@ use aleph_client::{
 };
 use anyhow::anyhow;
 use log::{debug, info};
-use primitives::{Balance, BlockHash, EraIndex, SessionIndex, LENIENT_THRESHOLD, TOKEN};
+use primitives::{
+    performance::{expected_blocks_per_validator, session_reward_ratio},
+    Balance, BlockHash, EraIndex, SessionIndex, LENIENT_THRESHOLD, TOKEN,
+};
 use rand::Rng;
-use sp_runtime::Perquintill;
+use sp_runtime::{PerThing, Perquintill};
 
 use crate::{
     accounts::{get_validators_keys, get_validators_seeds, NodeKeys},
@@ -149,10 +152,9 @@ async fn get_node_performance<S: ElectionsApi + CommitteeManagementApi>(
     );
     let performance = block_count as f64 / blocks_to_produce_per_session as f64;
     info!("validator {}, performance {:?}.", account_id, performance);
-    let lenient_performance = match Perquintill::from_float(performance) >= LENIENT_THRESHOLD {
-        true => 1.0,
-        false => performance,
-    };
+    let reward_ratio =
+        session_reward_ratio(Perquintill::from_float(performance), LENIENT_THRESHOLD);
+    let lenient_performance = reward_ratio.deconstruct() as f64 / Perquintill::ACCURACY as f64;
     info!(
         "Validator {}, lenient performance {:?}.",
         account_id, lenient_performance
@@ -193,7 +195,8 @@ pub async fn check_points<S: AsConnection + Sync>(
 
     info!("Members per session: {}.", members_per_session);
 
-    let blocks_to_produce_per_session = session_period / members_per_session;
+    let blocks_to_produce_per_session =
+        expected_blocks_per_validator(session_period, members_per_session);
     info!(
         "Blocks to produce per session: {} - session period {}.",
         blocks_to_produce_per_session, session_period
@@ -44,11 +44,6 @@ impl SyntheticNetworkConfigurator {
         Ok(Self { config })
     }
 
-    pub fn set_out_latency(&mut self, latency: Milliseconds) -> &mut Self {
-        self.config.default_link.egress.latency = latency;
-        self
-    }
-
     fn set_bit_rate(
         &mut self,
         bits_per_second: u64,
@@ -136,11 +131,9 @@ pub async fn set_out_latency(
         synthetic_url, milliseconds
     );
     let mut client = SyntheticNetworkClient::new(synthetic_url);
-    let config = client.load_config().await?;
-    let mut config = SyntheticNetworkConfigurator::new(config);
-    config.set_out_latency(milliseconds);
+    let config = client.load_config().await?.with_out_latency(milliseconds);
     client
-        .commit_config(&config.into())
+        .commit_config(&config)
         .await
         .context("unable to commit network configuration")
 }
@@ -213,7 +213,7 @@ pub async fn clearing_session_count() -> anyhow::Result<()> {
         setup_test(config).await?;
 
     root_connection
-        .set_ban_config(None, Some(3), Some(2), None, TxStatus::InBlock)
+        .set_ban_config(None, Some(3), Some(2), None, None, TxStatus::InBlock)
         .await?;
 
     let validator_to_disable =
@@ -282,7 +282,7 @@ pub async fn permissionless_ban() -> anyhow::Result<()> {
         .set_election_openness(ElectionOpenness::Permissionless, TxStatus::InBlock)
         .await?;
     root_connection
-        .set_ban_config(None, None, None, Some(ban_period), TxStatus::InBlock)
+        .set_ban_config(None, None, None, Some(ban_period), None, TxStatus::InBlock)
         .await?;
     root_connection
         .ban_from_committee(validator_to_ban.clone(), vec![], TxStatus::InBlock)
@@ -350,6 +350,7 @@ pub async fn ban_threshold() -> anyhow::Result<()> {
             None,
             None,
             None,
+            None,
             TxStatus::InBlock,
         )
         .await?;
@@ -0,0 +1,40 @@
+use aleph_client::{
+    keypair_from_string,
+    pallets::{
+        balances::BalanceUserApi,
+        identity::{IdentityApi, IdentityUserApi},
+    },
+    SignedConnection, SignedConnectionApi, TxStatus,
+};
+use primitives::TOKEN;
+
+use crate::{config::setup_test, transfer::setup_for_transfer};
+
+const DISPLAY_NAME: &str = "e2e-identity";
+
+#[tokio::test]
+pub async fn set_and_read_identity() -> anyhow::Result<()> {
+    let config = setup_test();
+    let (funder, _) = setup_for_transfer(config).await;
+
+    let keypair = keypair_from_string("//IdentityOwner");
+    let account = keypair.account_id().clone();
+    funder
+        .transfer_keep_alive(account.clone(), 100 * TOKEN, TxStatus::Finalized)
+        .await?;
+    let owner = SignedConnection::from_connection(funder.connection.clone(), keypair);
+
+    owner
+        .set_identity(DISPLAY_NAME, TxStatus::Finalized)
+        .await?;
+
+    let identity = owner
+        .identity_of(account.clone(), None)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Identity of {account} should be set"))?;
+    assert_eq!(identity.display.as_deref(), Some(DISPLAY_NAME));
+    assert!(identity.judgements.is_empty());
+    assert!(identity.sub_accounts.is_empty());
+
+    Ok(())
+}
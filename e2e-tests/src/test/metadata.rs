@@ -0,0 +1,60 @@
+use aleph_client::{
+    pallets::system::{SystemApi, SystemSudoApi},
+    utility::BlocksApi,
+    waiting::{AlephWaiting, BlockStatus},
+    Connection, ConnectionApi, TxStatus,
+};
+use anyhow::anyhow;
+use log::info;
+
+use crate::config::setup_test;
+
+#[tokio::test]
+pub async fn metadata_hash_is_stable() -> anyhow::Result<()> {
+    let config = setup_test();
+    let connection = Connection::new(&config.node).await;
+
+    let first = connection.metadata_hash().await?;
+    let second = connection.metadata_hash().await?;
+    info!("[+] Runtime metadata hash: {:?}", first);
+
+    assert_eq!(
+        first, second,
+        "Metadata hash changed without a runtime upgrade"
+    );
+
+    Ok(())
+}
+
+/// Upgrades the runtime to its own code, so the metadata hash has to be read again from the
+/// upgraded runtime, but must not change.
+#[tokio::test]
+pub async fn metadata_hash_survives_runtime_upgrade() -> anyhow::Result<()> {
+    let config = setup_test();
+    let connection = Connection::new(&config.node).await;
+    let root_connection = config.create_root_connection().await;
+
+    let before = connection.metadata_hash().await?;
+    let code = connection.get_code(None).await?;
+    let tx_info = root_connection
+        .set_code_without_checks(code, TxStatus::Finalized)
+        .await?;
+    let upgrade_block = connection
+        .get_block_number(tx_info.block_hash)
+        .await?
+        .ok_or_else(|| anyhow!("Failed to obtain the runtime upgrade block number"))?;
+    info!("[+] Runtime upgraded in block {}", upgrade_block);
+    connection
+        .wait_for_block(|number| number > upgrade_block + 1, BlockStatus::Finalized)
+        .await;
+
+    let after = connection.metadata_hash().await?;
+    assert_eq!(
+        before, after,
+        "Metadata hash changed after upgrading the runtime to the same code"
+    );
+    let fresh_connection = Connection::new(&config.node).await;
+    assert_eq!(after, fresh_connection.metadata_hash().await?);
+
+    Ok(())
+}
@@ -14,14 +14,19 @@ pub use finality_version::{
 };
 pub use finalization::finalization;
 pub use high_latency::{high_out_latency_for_all, high_out_latency_for_each_quorum};
+pub use identity::set_and_read_identity;
+pub use metadata::{metadata_hash_is_stable, metadata_hash_survives_runtime_upgrade};
+pub use nomination_pools::pool_pending_rewards;
 pub use rewards::{
     change_stake_and_force_new_era, disable_node, force_new_era, points_basic, points_stake_change,
 };
-pub use staking::{staking_era_payouts, staking_new_validator};
-pub use transfer::token_transfer;
+pub use session_keys::safe_rotate_keys;
+pub use staking::{staking_era_payouts, staking_new_validator, staking_validators_overview};
+pub use transfer::{token_transfer, wait_for_balance_after_transfer};
 pub use treasury::{channeling_fee_and_tip, treasury_access};
 pub use utility::batch_transactions;
 pub use validators_rotate::validators_rotate;
+pub use vesting::compact_vesting_schedules_in_batches;
 
 mod adder;
 mod ban;
@@ -35,10 +40,14 @@ mod finality_version;
 mod finalization;
 mod helpers;
 mod high_latency;
+mod identity;
 mod lenient_threshold;
+mod metadata;
+mod nomination_pools;
 mod proxy;
 mod rewards;
 mod safe_mode;
+mod session_keys;
 mod staking;
 mod sync;
 mod transfer;
@@ -46,3 +55,4 @@ mod treasury;
 mod utility;
 mod validators_change;
 mod validators_rotate;
+mod vesting;
@@ -0,0 +1,90 @@
+use aleph_client::{
+    api, keypair_from_string,
+    pallets::{balances::BalanceUserApi, nomination_pools::NominationPoolsApi},
+    utility::BlocksApi,
+    ConnectionApi, SignedConnection, SignedConnectionApi, TxStatus,
+};
+use log::info;
+use primitives::TOKEN;
+use subxt::utils::{MultiAddress, Static};
+
+use crate::{config::setup_test, transfer::setup_for_transfer};
+
+const POOL_MEMBERS: u32 = 3;
+
+#[tokio::test]
+pub async fn pool_pending_rewards() -> anyhow::Result<()> {
+    let config = setup_test();
+    let (funder, _) = setup_for_transfer(config).await;
+
+    let min_create_bond = funder
+        .get_storage_entry(&api::storage().nomination_pools().min_create_bond(), None)
+        .await;
+    let min_join_bond = funder
+        .get_storage_entry(&api::storage().nomination_pools().min_join_bond(), None)
+        .await;
+
+    let mut members = vec![];
+    for i in 0..POOL_MEMBERS {
+        let keypair = keypair_from_string(&format!("//PoolMember{i}"));
+        funder
+            .transfer_keep_alive(
+                keypair.account_id().clone(),
+                min_create_bond + TOKEN,
+                TxStatus::Finalized,
+            )
+            .await?;
+        members.push(SignedConnection::from_connection(
+            funder.connection.clone(),
+            keypair,
+        ));
+    }
+
+    let depositor = &members[0];
+    let depositor_id = Static(depositor.account_id().clone());
+    depositor
+        .send_tx(
+            api::tx().nomination_pools().create(
+                min_create_bond,
+                MultiAddress::Id(depositor_id.clone()),
+                MultiAddress::Id(depositor_id.clone()),
+                MultiAddress::Id(depositor_id),
+            ),
+            TxStatus::Finalized,
+        )
+        .await?;
+    let pool_id = funder
+        .get_storage_entry(&api::storage().nomination_pools().last_pool_id(), None)
+        .await;
+    info!("[+] Created pool {}", pool_id);
+
+    for member in &members[1..] {
+        member
+            .send_tx(
+                api::tx().nomination_pools().join(min_join_bond, pool_id),
+                TxStatus::Finalized,
+            )
+            .await?;
+    }
+
+    let at = funder.get_finalized_block_hash().await?;
+    let mut rewards = funder.pool_pending_rewards(pool_id, Some(at)).await?;
+    rewards.sort();
+
+    let mut expected = vec![];
+    for member in &members {
+        let account_id = member.account_id().clone();
+        let pending = funder
+            .get_pending_rewards(account_id.clone(), Some(at))
+            .await?;
+        expected.push((account_id, pending));
+    }
+    expected.sort();
+
+    assert_eq!(
+        rewards, expected,
+        "Pending rewards of pool {pool_id} do not match per-member values"
+    );
+
+    Ok(())
+}
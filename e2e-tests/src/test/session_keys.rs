@@ -0,0 +1,59 @@
+use aleph_client::{
+    api,
+    api::runtime_types::primitives::AlephNodeSessionKeys as SessionKeys,
+    pallets::session::{SessionApi, SessionKeysRotationApi},
+    utility::BlocksApi,
+    waiting::{AlephWaiting, BlockStatus},
+    AccountId, ConnectionApi, SessionIndex, SignedConnection, SignedConnectionApi,
+};
+use log::info;
+
+use crate::config::setup_test;
+
+async fn queued_keys_of(
+    connection: &SignedConnection,
+    account: &AccountId,
+    session: SessionIndex,
+) -> anyhow::Result<Option<SessionKeys>> {
+    let at = connection.first_block_of_session(session).await?;
+    let queued_keys = connection
+        .get_storage_entry(&api::storage().session().queued_keys(), at)
+        .await;
+
+    Ok(queued_keys
+        .into_iter()
+        .find(|(queued_account, _)| &queued_account.0 == account)
+        .map(|(_, keys)| keys))
+}
+
+#[tokio::test]
+pub async fn safe_rotate_keys() -> anyhow::Result<()> {
+    let config = setup_test();
+    // keys are rotated on the node we connect to, which has to be the one of the first validator
+    let connection = config.get_first_signed_connection().await;
+    let account = connection.account_id().clone();
+
+    let activation_session = connection.safe_rotate_keys().await?;
+    info!("[+] New keys should become active in session {activation_session}");
+    let new_keys = connection
+        .get_next_session_keys(account.clone(), None)
+        .await
+        .expect("Keys should be set");
+
+    connection
+        .wait_for_session(activation_session, BlockStatus::Finalized)
+        .await;
+
+    assert_ne!(
+        queued_keys_of(&connection, &account, activation_session - 2).await?,
+        Some(new_keys.clone()),
+        "New keys were queued before the session in which they were set ended"
+    );
+    assert_eq!(
+        queued_keys_of(&connection, &account, activation_session - 1).await?,
+        Some(new_keys),
+        "New keys were not queued for session {activation_session}"
+    );
+
+    Ok(())
+}
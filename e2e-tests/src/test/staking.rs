@@ -5,11 +5,12 @@ use aleph_client::{
     pallets::{
         author::AuthorRpc,
         balances::{BalanceApi, BalanceUserApi, BalanceUserBatchExtApi},
-        elections::ElectionsSudoApi,
+        elections::{ElectionsApi, ElectionsSudoApi},
         session::SessionUserApi,
         staking::{StakingApi, StakingUserApi},
     },
     primitives::CommitteeSeats,
+    utility::BlocksApi,
     waiting::{BlockStatus, WaitingExt},
     AccountId, AsConnection, KeyPair, Pair, SignedConnection, SignedConnectionApi, TxStatus,
 };
@@ -214,6 +215,37 @@ pub async fn staking_new_validator() -> anyhow::Result<()> {
     Ok(())
 }
 
+// 1. read the overview of all validators at the latest finalized block
+// 2. check that every validator bonded in the genesis block is covered, with its stake and
+//    current era membership matching the values read directly from storage
+#[tokio::test]
+pub async fn staking_validators_overview() -> anyhow::Result<()> {
+    let config = setup_test();
+    let (_, validator_accounts) = get_validator_stashes_key_pairs(config);
+    let connection = config.get_first_signed_connection().await;
+
+    let at = connection.get_finalized_block_hash().await?;
+    let overview = connection.validators_overview(Some(at)).await?;
+    let era_validators: primitives::EraValidators<_> =
+        connection.get_current_era_validators(Some(at)).await.into();
+
+    for validator in account_ids_from_keys(&validator_accounts) {
+        let validator_overview = overview
+            .iter()
+            .find(|o| o.stash == validator)
+            .unwrap_or_else(|| panic!("Validator {validator} is missing from the overview"));
+        let ledger = connection.get_ledger(validator.clone(), Some(at)).await;
+        assert_eq!(validator_overview.total_stake, ledger.total);
+        assert!(validator_overview.total_stake >= MIN_VALIDATOR_BOND);
+        assert_eq!(
+            validator_overview.in_current_era,
+            era_validators.contains(&validator)
+        );
+    }
+
+    Ok(())
+}
+
 pub async fn multi_bond(node: &str, bonders: &[KeyPair], stake: Balance) {
     for bonder in bonders {
         let connection = SignedConnection::new(node, KeyPair::new(bonder.signer().clone())).await;
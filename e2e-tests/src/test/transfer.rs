@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use aleph_client::{
     pallets::{balances::BalanceUserApi, system::SystemApi},
+    waiting::AlephWaiting,
     TxStatus,
 };
 use log::info;
@@ -30,3 +33,31 @@ pub async fn token_transfer() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+pub async fn wait_for_balance_after_transfer() -> anyhow::Result<()> {
+    let config = setup_test();
+    let (connection, to) = setup_for_transfer(config).await;
+
+    let balance_before = connection.get_free_balance(to.clone(), None).await;
+    let transfer_value = 1000;
+    connection
+        .transfer_keep_alive(to.clone(), transfer_value, TxStatus::Submitted)
+        .await?;
+
+    let balance_after = connection
+        .wait_for_balance(
+            to.clone(),
+            balance_before + transfer_value,
+            Duration::from_secs(60),
+        )
+        .await?;
+    info!("[+] Account {} balance reached: {}", to, balance_after);
+
+    assert!(
+        balance_after >= balance_before + transfer_value,
+        "before = {balance_before}, after = {balance_after}, tx = {transfer_value}"
+    );
+
+    Ok(())
+}
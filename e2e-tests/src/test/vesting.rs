@@ -0,0 +1,79 @@
+use aleph_client::{
+    api, keypair_from_string,
+    pallet_vesting::vesting_info::VestingInfo,
+    pallets::{
+        balances::BalanceUserApi,
+        vesting::{VestingApi, VestingApiExt, VestingUserApi},
+    },
+    utility::BlocksApi,
+    AsConnection, SignedConnection, SignedConnectionApi, TxStatus,
+};
+use log::info;
+use primitives::TOKEN;
+
+use crate::{config::setup_test, transfer::setup_for_transfer};
+
+const MAX_MERGES_PER_BATCH: u32 = 10;
+
+#[tokio::test]
+pub async fn compact_vesting_schedules_in_batches() -> anyhow::Result<()> {
+    let config = setup_test();
+    let (funder, _) = setup_for_transfer(config).await;
+
+    let constants = funder.as_connection().as_client().constants();
+    let max_schedules = constants.at(&api::constants().vesting().max_vesting_schedules())?;
+    let min_vested_transfer = constants.at(&api::constants().vesting().min_vested_transfer())?;
+
+    let receiver = SignedConnection::from_connection(
+        funder.connection.clone(),
+        keypair_from_string("//VestingReceiver"),
+    );
+    let receiver_id = receiver.account_id().clone();
+    funder
+        .transfer_keep_alive(receiver_id.clone(), TOKEN, TxStatus::Finalized)
+        .await?;
+
+    let starting_block = funder.get_best_block().await?.unwrap_or_default() + 1_000;
+    for _ in 0..max_schedules {
+        funder
+            .vested_transfer(
+                receiver_id.clone(),
+                VestingInfo {
+                    locked: min_vested_transfer,
+                    per_block: 1,
+                    starting_block,
+                },
+                TxStatus::InBlock,
+            )
+            .await?;
+    }
+    assert_eq!(
+        receiver.get_vesting(receiver_id.clone(), None).await.len() as u32,
+        max_schedules
+    );
+
+    let infos = receiver
+        .compact_and_vest(MAX_MERGES_PER_BATCH, TxStatus::Finalized)
+        .await?;
+    info!(
+        "[+] Compacted vesting schedules in {} extrinsics",
+        infos.len()
+    );
+
+    // One `vest` call and enough batches to perform `max_schedules - 1` merges.
+    let expected_batches = (max_schedules - 1).div_ceil(MAX_MERGES_PER_BATCH) as usize;
+    assert_eq!(infos.len(), 1 + expected_batches);
+    assert!(
+        expected_batches > 1,
+        "compaction should take multiple batches"
+    );
+
+    let schedules = receiver.get_vesting(receiver_id, None).await;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(
+        schedules[0].locked,
+        min_vested_transfer * max_schedules as u128
+    );
+
+    Ok(())
+}
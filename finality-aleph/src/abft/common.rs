@@ -1,6 +1,8 @@
 use std::{sync::Arc, time::Duration};
 
-use crate::UnitCreationDelay;
+use network_clique::LatencyTracker;
+
+use crate::{AdaptiveUnitCreationDelay, UnitCreationDelay};
 
 // Chosen as a round number large enough so that given the default 200 ms unit creation delay, and the exponential
 // slowdown consts below, the time to reach the max round noticeably surpasses the required 7 days. With this
@@ -34,12 +36,46 @@ fn exponential_slowdown(
 
 pub type DelaySchedule = Arc<dyn Fn(usize) -> Duration + Sync + Send + 'static>;
 
-pub fn unit_creation_delay_fn(unit_creation_delay: UnitCreationDelay) -> DelaySchedule {
+// How many round trips to the other committee members the base unit creation delay should cover
+// when adapting it to the latency of the validator network.
+const ROUND_TRIPS_PER_UNIT: u64 = 2;
+
+/// The source of the base delay between creating consecutive units.
+#[derive(Clone)]
+pub enum UnitCreationDelaySource {
+    /// Always the same delay.
+    Fixed(UnitCreationDelay),
+    /// A delay following the round-trip time observed in the validator network, kept within
+    /// the bounds. Without any observations the lower bound is used.
+    Adaptive {
+        bounds: AdaptiveUnitCreationDelay,
+        latency: LatencyTracker,
+    },
+}
+
+impl UnitCreationDelaySource {
+    fn base_delay(&self) -> UnitCreationDelay {
+        match self {
+            UnitCreationDelaySource::Fixed(delay) => *delay,
+            UnitCreationDelaySource::Adaptive { bounds, latency } => {
+                let delay = latency
+                    .round_trip()
+                    .map(|round_trip| {
+                        (round_trip.as_millis() as u64).saturating_mul(ROUND_TRIPS_PER_UNIT)
+                    })
+                    .unwrap_or(bounds.min.0);
+                UnitCreationDelay(delay.clamp(bounds.min.0, bounds.max.0))
+            }
+        }
+    }
+}
+
+pub fn unit_creation_delay_fn(unit_creation_delay: UnitCreationDelaySource) -> DelaySchedule {
     Arc::new(move |t| match t {
         0 => Duration::from_millis(2000),
         _ => exponential_slowdown(
             t,
-            unit_creation_delay.0 as f64,
+            unit_creation_delay.base_delay().0 as f64,
             EXP_SLOWDOWN_START_ROUND,
             EXP_SLOWDOWN_MUL,
         ),
@@ -48,3 +84,70 @@ pub fn unit_creation_delay_fn(unit_creation_delay: UnitCreationDelay) -> DelaySc
 
 // 7 days (as milliseconds)
 pub const SESSION_LEN_LOWER_BOUND_MS: u128 = 1000 * 60 * 60 * 24 * 7;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use network_clique::LatencyTracker;
+
+    use super::UnitCreationDelaySource;
+    use crate::{AdaptiveUnitCreationDelay, UnitCreationDelay};
+
+    fn adaptive(latency: &LatencyTracker) -> UnitCreationDelaySource {
+        UnitCreationDelaySource::Adaptive {
+            bounds: AdaptiveUnitCreationDelay {
+                min: UnitCreationDelay(100),
+                max: UnitCreationDelay(1000),
+            },
+            latency: latency.clone(),
+        }
+    }
+
+    #[test]
+    fn fixed_delay_ignores_latency() {
+        let source = UnitCreationDelaySource::Fixed(UnitCreationDelay(200));
+        assert_eq!(source.base_delay(), UnitCreationDelay(200));
+    }
+
+    #[test]
+    fn adaptive_delay_starts_at_lower_bound() {
+        let latency = LatencyTracker::new();
+        assert_eq!(adaptive(&latency).base_delay(), UnitCreationDelay(100));
+    }
+
+    #[test]
+    fn adaptive_delay_covers_round_trips() {
+        let latency = LatencyTracker::new();
+        latency.report(Duration::from_millis(150));
+        assert_eq!(adaptive(&latency).base_delay(), UnitCreationDelay(300));
+    }
+
+    #[test]
+    fn adaptive_delay_stays_within_bounds() {
+        let latency = LatencyTracker::new();
+        let source = adaptive(&latency);
+        latency.report(Duration::from_millis(10));
+        assert_eq!(source.base_delay(), UnitCreationDelay(100));
+        for _ in 0..100 {
+            latency.report(Duration::from_secs(5));
+        }
+        assert_eq!(source.base_delay(), UnitCreationDelay(1000));
+    }
+
+    #[test]
+    fn adaptive_delay_follows_latency() {
+        let latency = LatencyTracker::new();
+        let source = adaptive(&latency);
+        for _ in 0..100 {
+            latency.report(Duration::from_millis(400));
+        }
+        let high = source.base_delay();
+        for _ in 0..100 {
+            latency.report(Duration::from_millis(60));
+        }
+        let low = source.base_delay();
+        assert!(high > UnitCreationDelay(700));
+        assert!(low < UnitCreationDelay(150));
+    }
+}
@@ -14,7 +14,9 @@ pub use performance::Service as PerformanceService;
 pub use crate::aleph_primitives::CURRENT_FINALITY_VERSION as VERSION;
 use crate::{
     abft::{
-        common::{unit_creation_delay_fn, MAX_ROUNDS, SESSION_LEN_LOWER_BOUND_MS},
+        common::{
+            unit_creation_delay_fn, UnitCreationDelaySource, MAX_ROUNDS, SESSION_LEN_LOWER_BOUND_MS,
+        },
         NetworkWrapper,
     },
     block::UnverifiedHeader,
@@ -26,7 +28,7 @@ use crate::{
         backup::ABFTBackup,
         manager::{Task, TaskCommon},
     },
-    CurrentNetworkData, Hasher, Keychain, NodeIndex, SessionId, SignatureSet, UnitCreationDelay,
+    CurrentNetworkData, Hasher, Keychain, NodeIndex, SessionId, SignatureSet,
 };
 
 type WrappedNetwork<H, ADN> = NetworkWrapper<
@@ -88,7 +90,7 @@ pub fn create_aleph_config(
     n_members: usize,
     node_id: NodeIndex,
     session_id: SessionId,
-    unit_creation_delay: UnitCreationDelay,
+    unit_creation_delay: UnitCreationDelaySource,
 ) -> Config {
     let mut delay_config = default_delay_config();
     delay_config.unit_creation_delay = unit_creation_delay_fn(unit_creation_delay);
@@ -12,7 +12,9 @@ pub use network::NetworkData;
 pub use crate::aleph_primitives::LEGACY_FINALITY_VERSION as VERSION;
 use crate::{
     abft::{
-        common::{unit_creation_delay_fn, MAX_ROUNDS, SESSION_LEN_LOWER_BOUND_MS},
+        common::{
+            unit_creation_delay_fn, UnitCreationDelaySource, MAX_ROUNDS, SESSION_LEN_LOWER_BOUND_MS,
+        },
         NetworkWrapper,
     },
     block::{Header, HeaderBackend, HeaderVerifier},
@@ -24,7 +26,7 @@ use crate::{
         backup::ABFTBackup,
         manager::{Task, TaskCommon},
     },
-    Hasher, Keychain, LegacyNetworkData, NodeIndex, SessionId, SignatureSet, UnitCreationDelay,
+    Hasher, Keychain, LegacyNetworkData, NodeIndex, SessionId, SignatureSet,
 };
 
 type WrappedNetwork<H, ADN> = NetworkWrapper<
@@ -79,7 +81,7 @@ pub fn create_aleph_config(
     n_members: usize,
     node_id: NodeIndex,
     session_id: SessionId,
-    unit_creation_delay: UnitCreationDelay,
+    unit_creation_delay: UnitCreationDelaySource,
 ) -> Config {
     let mut delay_config = default_delay_config();
     delay_config.unit_creation_delay = unit_creation_delay_fn(unit_creation_delay);
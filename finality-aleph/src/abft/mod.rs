@@ -17,6 +17,7 @@ mod types;
 use std::fmt::Debug;
 
 use aleph_bft_crypto::{PartialMultisignature, Signature as AbftSignature};
+pub use common::UnitCreationDelaySource;
 pub use crypto::Keychain;
 pub use current::{
     create_aleph_config as current_create_aleph_config, run_member as run_current_member,
@@ -6,7 +6,7 @@ use sp_runtime::traits::{CheckedSub, Header as _, One};
 use crate::{
     aleph_primitives::{Block, Header},
     block::{Block as BlockT, BlockId, BlockImport, Header as HeaderT, UnverifiedHeader},
-    metrics::TimingBlockMetrics,
+    metrics::{BlockImportMetrics, TimingBlockMetrics},
 };
 
 mod chain_status;
@@ -65,6 +65,7 @@ impl HeaderT for Header {
 pub struct BlockImporter {
     importer: Box<dyn ImportQueueService<Block>>,
     metrics: TimingBlockMetrics,
+    import_metrics: BlockImportMetrics,
 }
 
 impl BlockImporter {
@@ -72,11 +73,17 @@ impl BlockImporter {
         Self {
             importer,
             metrics: TimingBlockMetrics::noop(),
+            import_metrics: BlockImportMetrics::noop(),
         }
     }
 
-    pub fn attach_metrics(&mut self, metrics: TimingBlockMetrics) {
+    pub fn attach_metrics(
+        &mut self,
+        metrics: TimingBlockMetrics,
+        import_metrics: BlockImportMetrics,
+    ) {
         self.metrics = metrics;
+        self.import_metrics = import_metrics;
     }
 }
 
@@ -102,6 +109,7 @@ impl BlockImport<Block> for BlockImporter {
             state: None,
         };
         self.metrics.report_block(hash, Checkpoint::Importing);
+        self.import_metrics.report_import_started(hash);
         self.importer.import_blocks(origin, vec![incoming_block]);
     }
 }
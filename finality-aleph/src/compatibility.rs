@@ -1,8 +1,67 @@
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, Encode, Error, Input};
+
+/// Default limit on the nesting depth of versioned data decoded from the network.
+pub const DEFAULT_MAX_DECODE_DEPTH: u32 = 64;
+
+/// Default limit on the encoded size of versioned data decoded from the network.
+pub const DEFAULT_MAX_DECODE_SIZE: usize = 4 * 1024 * 1024;
 
 #[derive(Encode, Eq, Decode, PartialEq, Debug, Copy, Clone)]
 pub struct Version(pub u16);
 
 pub trait Versioned {
     const VERSION: Version;
+    /// Data nested deeper than this is rejected during decoding, before it gets allocated.
+    const MAX_DECODE_DEPTH: u32 = DEFAULT_MAX_DECODE_DEPTH;
+    /// Data whose encoding is longer than this is rejected during decoding. As every element of
+    /// a vector takes at least one byte, this also bounds the lengths of all the vectors.
+    const MAX_DECODE_SIZE: usize = DEFAULT_MAX_DECODE_SIZE;
+}
+
+/// Input that fails once more than `limit` bytes are read from it. It also reports at most
+/// `limit` bytes remaining, so the decoder never preallocates more than that.
+pub struct BoundedInput<'a, I: Input> {
+    input: &'a mut I,
+    limit: usize,
+}
+
+impl<'a, I: Input> BoundedInput<'a, I> {
+    pub fn new(input: &'a mut I, limit: usize) -> Self {
+        BoundedInput { input, limit }
+    }
+
+    fn consume(&mut self, len: usize) -> Result<(), Error> {
+        self.limit = self
+            .limit
+            .checked_sub(len)
+            .ok_or("Decoded data exceeds the size limit")?;
+        Ok(())
+    }
+}
+
+impl<'a, I: Input> Input for BoundedInput<'a, I> {
+    fn remaining_len(&mut self) -> Result<Option<usize>, Error> {
+        Ok(Some(match self.input.remaining_len()? {
+            Some(len) => len.min(self.limit),
+            None => self.limit,
+        }))
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), Error> {
+        self.consume(into.len())?;
+        self.input.read(into)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        self.consume(1)?;
+        self.input.read_byte()
+    }
+
+    fn descend_ref(&mut self) -> Result<(), Error> {
+        self.input.descend_ref()
+    }
+
+    fn ascend_ref(&mut self) {
+        self.input.ascend_ref()
+    }
 }
@@ -1,10 +1,13 @@
+use std::sync::Arc;
+
 use parity_scale_codec::{Decode, Encode};
+use sc_keystore::LocalKeystore;
 use sp_runtime::Justification;
 
 use crate::{
     abft::SignatureSet,
-    aleph_primitives::{AuthoritySignature, ALEPH_ENGINE_ID},
-    crypto::Signature,
+    aleph_primitives::{AuthorityId, AuthoritySignature, BlockHash, ALEPH_ENGINE_ID},
+    crypto::{AuthorityPen, Signature},
 };
 
 mod compatibility;
@@ -26,3 +29,29 @@ impl From<AlephJustification> for Justification {
         (ALEPH_ENGINE_ID, versioned_encode(val))
     }
 }
+
+/// Creates emergency justifications with the key of the emergency finalizer, as long as the
+/// keystore of the node holds it.
+#[derive(Clone)]
+pub struct EmergencyJustificationSigner {
+    keystore: Arc<LocalKeystore>,
+}
+
+impl EmergencyJustificationSigner {
+    pub fn new(keystore: Arc<LocalKeystore>) -> Self {
+        EmergencyJustificationSigner { keystore }
+    }
+
+    /// Signs the block with the given `hash` as `emergency_finalizer`. Returns `None` if the
+    /// keystore does not hold the key of `emergency_finalizer`.
+    pub fn sign(
+        &self,
+        emergency_finalizer: AuthorityId,
+        hash: BlockHash,
+    ) -> Option<AlephJustification> {
+        let pen = AuthorityPen::new(emergency_finalizer, self.keystore.clone()).ok()?;
+        Some(AlephJustification::EmergencySignature(
+            pen.sign(&hash.encode()).0,
+        ))
+    }
+}
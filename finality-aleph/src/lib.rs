@@ -8,7 +8,8 @@ use futures::{
     },
     Future,
 };
-use parity_scale_codec::{Decode, Encode, Output};
+use log::warn;
+use parity_scale_codec::{Decode, DecodeLimit, Encode, Output};
 use primitives as aleph_primitives;
 use primitives::{AuthorityId, Block as AlephBlock, BlockHash, BlockNumber};
 use sc_client_api::{
@@ -30,8 +31,11 @@ use crate::{
     },
     aggregation::{CurrentRmcNetworkData, LegacyRmcNetworkData},
     block::UnverifiedHeader,
-    compatibility::{Version, Versioned},
-    network::data::split::Split,
+    compatibility::{BoundedInput, Version, Versioned},
+    network::data::{
+        component::{Network, Receiver, SimpleNetwork},
+        split::Split,
+    },
     session::{SessionBoundaries, SessionBoundaryInfo, SessionId},
     VersionedTryFromError::{ExpectedNewGotOld, ExpectedOldGotNew},
 };
@@ -64,15 +68,15 @@ pub use crate::{
         BlockId,
     },
     import::{get_aleph_block_import, AlephBlockImport, RedirectingBlockImport},
-    justification::AlephJustification,
+    justification::{AlephJustification, EmergencyJustificationSigner},
     network::{
         address_cache::{ValidatorAddressCache, ValidatorAddressingInfo},
         build_network, BuildNetworkOutput, ProtocolNetwork, SubstrateNetworkConfig,
         SubstratePeerId,
     },
     nodes::run_validator_node,
-    session::SessionPeriod,
-    sync::FavouriteSelectChainProvider,
+    session::{CurrentSessionInfo, CurrentSessionTracker, SessionPeriod},
+    sync::{FavouriteSelectChainProvider, PinnedSelectChainProvider, SelectChainProvider},
     sync_oracle::SyncOracle,
 };
 
@@ -85,6 +89,14 @@ pub struct MillisecsPerBlock(pub u64);
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
 pub struct UnitCreationDelay(pub u64);
 
+/// Bounds within which the unit creation delay is adapted to the latency observed in the
+/// validator network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct AdaptiveUnitCreationDelay {
+    pub min: UnitCreationDelay,
+    pub max: UnitCreationDelay,
+}
+
 type LegacySplitData<UH> = Split<LegacyNetworkData<UH>, LegacyRmcNetworkData>;
 type CurrentSplitData<UH> = Split<CurrentNetworkData<UH>, CurrentRmcNetworkData>;
 
@@ -101,6 +113,8 @@ impl<UH: UnverifiedHeader> Versioned for CurrentNetworkData<UH> {
 /// representations for provided type parameters with their version (they need to implement the `Versioned` trait). If one
 /// provides data types that declares equal versions, the first data type parameter will have priority while decoding. Keep in
 /// mind that in such case, `decode` might fail even if the second data type would be able decode provided byte representation.
+/// Decoding respects `Versioned::MAX_DECODE_DEPTH` and `Versioned::MAX_DECODE_SIZE` of the chosen data type, so that
+/// maliciously nested or enormous messages are rejected.
 #[derive(Clone)]
 pub enum VersionedEitherMessage<L, R> {
     Left(L),
@@ -113,10 +127,16 @@ impl<L: Versioned + Decode, R: Versioned + Decode> Decode for VersionedEitherMes
     ) -> Result<Self, parity_scale_codec::Error> {
         let version = Version::decode(input)?;
         if version == L::VERSION {
-            return Ok(VersionedEitherMessage::Left(L::decode(input)?));
+            return Ok(VersionedEitherMessage::Left(L::decode_with_depth_limit(
+                L::MAX_DECODE_DEPTH,
+                &mut BoundedInput::new(input, L::MAX_DECODE_SIZE),
+            )?));
         }
         if version == R::VERSION {
-            return Ok(VersionedEitherMessage::Right(R::decode(input)?));
+            return Ok(VersionedEitherMessage::Right(R::decode_with_depth_limit(
+                R::MAX_DECODE_DEPTH,
+                &mut BoundedInput::new(input, R::MAX_DECODE_SIZE),
+            )?));
         }
         Err("Invalid version while decoding VersionedEitherMessage".into())
     }
@@ -144,6 +164,68 @@ impl<L: Versioned + Encode, R: Versioned + Encode> Encode for VersionedEitherMes
     }
 }
 
+/// The AlephBFT version run in a session, chosen from the finality version known for it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum SessionVersion {
+    Legacy,
+    Current,
+}
+
+impl SessionVersion {
+    /// Whether data of the given version may be passed to a session running this version.
+    pub fn accepts<L, R>(&self, message: &VersionedEitherMessage<L, R>) -> bool {
+        matches!(
+            (self, message),
+            (SessionVersion::Legacy, VersionedEitherMessage::Left(_))
+                | (SessionVersion::Current, VersionedEitherMessage::Right(_))
+        )
+    }
+}
+
+/// Drops incoming data of a version other than the one run in the session, so that a peer cannot
+/// force a downgrade of a session by sending data in the legacy format.
+struct VersionGuardedReceiver<R> {
+    receiver: R,
+    version: SessionVersion,
+}
+
+impl<R> VersionGuardedReceiver<R> {
+    pub fn new(receiver: R, version: SessionVersion) -> Self {
+        VersionGuardedReceiver { receiver, version }
+    }
+}
+
+#[async_trait::async_trait]
+impl<L, R, Rx> Receiver<VersionedEitherMessage<L, R>> for VersionGuardedReceiver<Rx>
+where
+    VersionedEitherMessage<L, R>: network::Data,
+    Rx: Receiver<VersionedEitherMessage<L, R>>,
+{
+    async fn next(&mut self) -> Option<VersionedEitherMessage<L, R>> {
+        loop {
+            let message = self.receiver.next().await?;
+            if self.version.accepts(&message) {
+                return Some(message);
+            }
+            warn!(target: "aleph-network", "Dropping data of another version in a session running the {:?} version.", self.version);
+        }
+    }
+}
+
+/// Guards the network of a session running the given version against data of the other version.
+fn guard_session_version<D: network::Data, N: Network<D>>(
+    network: N,
+    version: SessionVersion,
+) -> SimpleNetwork<D, VersionGuardedReceiver<N::R>, N::S>
+where
+    VersionGuardedReceiver<N::R>: Receiver<D>,
+{
+    let (sender, receiver) = network.into();
+    SimpleNetwork::new(VersionGuardedReceiver::new(receiver, version), sender)
+}
+
+/// Data exchanged by AlephBFT instances. Every session runs a single version and its network is
+/// guarded with `guard_session_version` before being mapped to the data of that version.
 type VersionedNetworkData<UH> = VersionedEitherMessage<LegacySplitData<UH>, CurrentSplitData<UH>>;
 
 #[derive(Debug, Display, Clone)]
@@ -257,13 +339,13 @@ pub struct RateLimiterConfig {
     pub substrate_network_bit_rate: u64,
 }
 
-pub struct AlephConfig<C, T> {
+pub struct AlephConfig<C, T, SC = FavouriteSelectChainProvider<AlephBlock>> {
     pub authentication_network: ProtocolNetwork,
     pub block_sync_network: ProtocolNetwork,
     pub client: Arc<C>,
     pub chain_status: SubstrateChainStatus,
     pub import_queue_handle: BlockImporter,
-    pub select_chain_provider: FavouriteSelectChainProvider<AlephBlock>,
+    pub select_chain_provider: SC,
     pub spawn_handle: SpawnHandle,
     pub keystore: Arc<LocalKeystore>,
     pub justification_channel_provider: ChannelProvider<Justification>,
@@ -272,11 +354,131 @@ pub struct AlephConfig<C, T> {
     pub session_period: SessionPeriod,
     pub millisecs_per_block: MillisecsPerBlock,
     pub unit_creation_delay: UnitCreationDelay,
+    pub adaptive_unit_creation_delay: Option<AdaptiveUnitCreationDelay>,
+    pub max_in_flight_aggregations: u32,
     pub backup_saving_path: Option<PathBuf>,
+    pub backup_retained_sessions: u32,
     pub external_addresses: Vec<String>,
     pub validator_port: u16,
+    pub validator_network_compression: bool,
     pub rate_limiter_config: RateLimiterConfig,
     pub sync_oracle: SyncOracle,
     pub validator_address_cache: Option<ValidatorAddressCache>,
+    pub current_session: CurrentSessionTracker,
     pub transaction_pool: Arc<T>,
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use parity_scale_codec::{Compact, Decode, Encode};
+
+    use crate::{
+        compatibility::{Version, Versioned},
+        network::data::component::Receiver,
+        SessionVersion, VersionGuardedReceiver, VersionedEitherMessage,
+    };
+
+    #[derive(Clone, Encode, Decode, Debug, PartialEq)]
+    struct Flat(Vec<u32>);
+
+    impl Versioned for Flat {
+        const VERSION: Version = Version(1);
+        const MAX_DECODE_DEPTH: u32 = 3;
+    }
+
+    #[derive(Clone, Encode, Decode, Debug, PartialEq)]
+    struct Nested(Vec<Vec<Vec<Vec<Vec<u32>>>>>);
+
+    impl Versioned for Nested {
+        const VERSION: Version = Version(2);
+        const MAX_DECODE_DEPTH: u32 = 3;
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct Wide(Vec<Vec<u32>>);
+
+    impl Versioned for Wide {
+        const VERSION: Version = Version(3);
+        const MAX_DECODE_SIZE: usize = 1024;
+    }
+
+    type TestMessage = VersionedEitherMessage<Flat, Nested>;
+    type WideMessage = VersionedEitherMessage<Flat, Wide>;
+
+    #[test]
+    fn decodes_data_within_limits() {
+        let encoded = TestMessage::Left(Flat(vec![1, 2, 3])).encode();
+
+        match TestMessage::decode(&mut encoded.as_slice()) {
+            Ok(VersionedEitherMessage::Left(flat)) => assert_eq!(flat, Flat(vec![1, 2, 3])),
+            _ => panic!("should decode flat data"),
+        }
+    }
+
+    #[test]
+    fn rejects_too_deeply_nested_data() {
+        let encoded = TestMessage::Right(Nested(vec![vec![vec![vec![vec![7]]]]])).encode();
+
+        assert!(TestMessage::decode(&mut encoded.as_slice()).is_err());
+    }
+
+    #[test]
+    fn decodes_wide_data_within_size_limit() {
+        let encoded = WideMessage::Right(Wide(vec![Vec::new(); 1000])).encode();
+
+        match WideMessage::decode(&mut encoded.as_slice()) {
+            Ok(VersionedEitherMessage::Right(wide)) => assert_eq!(wide.0.len(), 1000),
+            _ => panic!("should decode data within the size limit"),
+        }
+    }
+
+    #[test]
+    fn rejects_enormous_vector_before_allocating_it() {
+        // Every empty inner vector takes a single byte to encode, but a few dozen to allocate.
+        let elements = 1_000_000u32;
+        let mut encoded = Version(3).encode();
+        Compact(elements).encode_to(&mut encoded);
+        encoded.extend(vec![0u8; elements as usize]);
+
+        assert!(WideMessage::decode(&mut encoded.as_slice()).is_err());
+    }
+
+    #[tokio::test]
+    async fn current_version_session_drops_legacy_data() {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut receiver = VersionGuardedReceiver::new(receiver, SessionVersion::Current);
+        sender
+            .unbounded_send(TestMessage::Left(Flat(vec![1])))
+            .unwrap();
+        sender
+            .unbounded_send(TestMessage::Right(Nested(vec![])))
+            .unwrap();
+        sender.close_channel();
+
+        match receiver.next().await {
+            Some(VersionedEitherMessage::Right(nested)) => assert_eq!(nested, Nested(vec![])),
+            _ => panic!("should pass only current data"),
+        }
+        assert!(receiver.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn legacy_version_session_drops_current_data() {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut receiver = VersionGuardedReceiver::new(receiver, SessionVersion::Legacy);
+        sender
+            .unbounded_send(TestMessage::Right(Nested(vec![])))
+            .unwrap();
+        sender
+            .unbounded_send(TestMessage::Left(Flat(vec![1])))
+            .unwrap();
+        sender.close_channel();
+
+        match receiver.next().await {
+            Some(VersionedEitherMessage::Left(flat)) => assert_eq!(flat, Flat(vec![1])),
+            _ => panic!("should pass only legacy data"),
+        }
+        assert!(receiver.next().await.is_none());
+    }
+}
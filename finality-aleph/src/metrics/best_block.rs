@@ -4,15 +4,20 @@ use substrate_prometheus_endpoint::{
     register, Gauge, Histogram, HistogramOpts, PrometheusError, Registry, U64,
 };
 
-use crate::{BlockId, BlockNumber, SubstrateChainStatus};
+use crate::{
+    block::{ChainStatus, Header, Justification},
+    BlockId, BlockNumber, SubstrateChainStatus,
+};
 
 #[derive(Clone)]
 pub enum BestBlockMetrics {
     Prometheus {
         top_finalized_block: Gauge<U64>,
         best_block: Gauge<U64>,
+        finality_lag: Gauge<U64>,
         reorgs: Histogram,
         best_block_id: BlockId,
+        top_finalized_number: BlockNumber,
         chain_status: SubstrateChainStatus,
     },
     Noop,
@@ -27,12 +32,21 @@ impl BestBlockMetrics {
             Some(registry) => registry,
             None => return Ok(Self::Noop),
         };
+        let top_finalized_number = match chain_status.top_finalized() {
+            Ok(justification) => justification.header().id().number(),
+            Err(e) => {
+                log::warn!("Failed to get the top finalized block: {:?}", e);
+                0
+            }
+        };
+        let top_finalized_block = register(
+            Gauge::new("aleph_top_finalized_block", "Top finalized block number")?,
+            &registry,
+        )?;
+        top_finalized_block.set(top_finalized_number as u64);
 
         Ok(Self::Prometheus {
-            top_finalized_block: register(
-                Gauge::new("aleph_top_finalized_block", "Top finalized block number")?,
-                &registry,
-            )?,
+            top_finalized_block,
             best_block: register(
                 Gauge::new(
                     "aleph_best_block",
@@ -40,6 +54,13 @@ impl BestBlockMetrics {
                 )?,
                 &registry,
             )?,
+            finality_lag: register(
+                Gauge::new(
+                    "aleph_finality_lag",
+                    "Number of blocks between the best and the top finalized block",
+                )?,
+                &registry,
+            )?,
             reorgs: register(
                 Histogram::with_opts(
                     HistogramOpts::new("aleph_reorgs", "Number of reorgs by length")
@@ -48,6 +69,7 @@ impl BestBlockMetrics {
                 &registry,
             )?,
             best_block_id: (Default::default(), 0u32).into(),
+            top_finalized_number,
             chain_status,
         })
     }
@@ -55,14 +77,17 @@ impl BestBlockMetrics {
     pub fn report_best_block_imported(&mut self, block_id: BlockId) {
         if let Self::Prometheus {
             best_block,
+            finality_lag,
             ref mut best_block_id,
             reorgs,
             chain_status,
+            top_finalized_number,
             ..
         } = self
         {
             let reorg_len = retracted_path_length(chain_status, best_block_id, &block_id);
             best_block.set(block_id.number() as u64);
+            finality_lag.set(block_id.number().saturating_sub(*top_finalized_number) as u64);
             *best_block_id = block_id;
             match reorg_len {
                 Ok(0) => {}
@@ -76,13 +101,18 @@ impl BestBlockMetrics {
         }
     }
 
-    pub fn report_block_finalized(&self, block_id: BlockId) {
+    pub fn report_block_finalized(&mut self, block_id: BlockId) {
         if let Self::Prometheus {
             top_finalized_block,
+            finality_lag,
+            best_block_id,
+            ref mut top_finalized_number,
             ..
         } = self
         {
             top_finalized_block.set(block_id.number() as u64);
+            *top_finalized_number = block_id.number();
+            finality_lag.set(best_block_id.number().saturating_sub(block_id.number()) as u64);
         }
     }
 }
@@ -0,0 +1,176 @@
+use std::{
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use substrate_prometheus_endpoint::{
+    register, Gauge, Histogram, HistogramOpts, PrometheusError, Registry, U64,
+};
+
+use crate::{
+    aleph_primitives::BlockHash,
+    metrics::{exponential_buckets_two_sided, timing::Clock},
+};
+
+// How many blocks waiting for import we keep track of. Blocks that never get imported
+// (e.g. because they were invalid) are eventually evicted, so they do not inflate the queue depth forever.
+const MAX_PENDING_IMPORTS: usize = 5000;
+
+#[derive(Clone)]
+pub enum BlockImportMetrics<C: Clock> {
+    Prometheus {
+        time_to_import: Histogram,
+        import_queue_depth: Gauge<U64>,
+        pending: Arc<Mutex<LruCache<BlockHash, Instant>>>,
+        clock: C,
+    },
+    Noop,
+}
+
+impl<C: Clock> BlockImportMetrics<C> {
+    pub fn new(registry: Option<&Registry>, clock: C) -> Result<Self, PrometheusError> {
+        let registry = match registry {
+            Some(registry) => registry,
+            None => return Ok(BlockImportMetrics::Noop),
+        };
+
+        Ok(BlockImportMetrics::Prometheus {
+            time_to_import: register(
+                Histogram::with_opts(
+                    HistogramOpts::new(
+                        "aleph_block_import_time",
+                        "Time in milliseconds between passing a block to the import queue and its import",
+                    )
+                    .buckets(exponential_buckets_two_sided(100.0, 1.5, 4, 6)?),
+                )?,
+                registry,
+            )?,
+            import_queue_depth: register(
+                Gauge::new(
+                    "aleph_block_import_queue_depth",
+                    "Number of blocks passed to the import queue and not imported yet",
+                )?,
+                registry,
+            )?,
+            pending: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_PENDING_IMPORTS).unwrap(),
+            ))),
+            clock,
+        })
+    }
+
+    pub fn noop() -> Self {
+        BlockImportMetrics::Noop
+    }
+
+    /// Reports that a block was passed to the import queue.
+    pub fn report_import_started(&self, hash: BlockHash) {
+        if let BlockImportMetrics::Prometheus {
+            import_queue_depth,
+            pending,
+            clock,
+            ..
+        } = self
+        {
+            let mut pending = pending.lock();
+            if !pending.contains(&hash) {
+                pending.put(hash, clock.now());
+            }
+            import_queue_depth.set(pending.len() as u64);
+        }
+    }
+
+    /// Reports that a block was imported, does nothing if its import was not reported as started.
+    pub fn report_import_finished(&self, hash: BlockHash) {
+        if let BlockImportMetrics::Prometheus {
+            time_to_import,
+            import_queue_depth,
+            pending,
+            clock,
+        } = self
+        {
+            let mut pending = pending.lock();
+            if let Some(start) = pending.pop(&hash) {
+                let duration = clock
+                    .now()
+                    .checked_duration_since(start)
+                    .unwrap_or(Duration::new(0, 0));
+                time_to_import.observe(duration.as_secs_f64() * 1000.);
+            }
+            import_queue_depth.set(pending.len() as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use substrate_prometheus_endpoint::Registry;
+
+    use super::BlockImportMetrics;
+    use crate::{
+        aleph_primitives::BlockHash,
+        block::{
+            mock::{Backend, MockBlock, MockHeader},
+            BlockImport, ChainStatus, Header,
+        },
+        metrics::timing::{Clock, DefaultClock},
+        session::{SessionBoundaryInfo, SessionPeriod},
+    };
+
+    const SESSION_BOUNDARY_INFO: SessionBoundaryInfo = SessionBoundaryInfo::new(SessionPeriod(20));
+
+    fn sample_count<C: Clock>(metrics: &BlockImportMetrics<C>) -> u64 {
+        match metrics {
+            BlockImportMetrics::Prometheus { time_to_import, .. } => {
+                time_to_import.get_sample_count()
+            }
+            BlockImportMetrics::Noop => 0,
+        }
+    }
+
+    fn queue_depth<C: Clock>(metrics: &BlockImportMetrics<C>) -> u64 {
+        match metrics {
+            BlockImportMetrics::Prometheus {
+                import_queue_depth, ..
+            } => import_queue_depth.get(),
+            BlockImportMetrics::Noop => 0,
+        }
+    }
+
+    #[test]
+    fn noop_metrics() {
+        let metrics = BlockImportMetrics::<DefaultClock>::noop();
+        metrics.report_import_started(BlockHash::random());
+        assert!(matches!(metrics, BlockImportMetrics::Noop));
+    }
+
+    #[test]
+    fn observes_import_time_of_mock_block() {
+        let (mut backend, _keep) = Backend::setup(SESSION_BOUNDARY_INFO);
+        let metrics = BlockImportMetrics::new(Some(&Registry::new()), DefaultClock).unwrap();
+        let header = MockHeader::genesis().random_child();
+        let id = header.id();
+
+        metrics.report_import_started(id.hash());
+        assert_eq!(queue_depth(&metrics), 1);
+
+        backend.import_block(MockBlock::new(header, true), false);
+        assert!(backend.block(id.clone()).unwrap().is_some());
+        metrics.report_import_finished(id.hash());
+
+        assert_eq!(sample_count(&metrics), 1);
+        assert_eq!(queue_depth(&metrics), 0);
+    }
+
+    #[test]
+    fn ignores_blocks_not_reported_as_started() {
+        let metrics = BlockImportMetrics::new(Some(&Registry::new()), DefaultClock).unwrap();
+        metrics.report_import_finished(BlockHash::random());
+
+        assert_eq!(sample_count(&metrics), 0);
+        assert_eq!(queue_depth(&metrics), 0);
+    }
+}
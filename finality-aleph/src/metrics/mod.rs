@@ -1,5 +1,6 @@
 mod abft_score;
 mod best_block;
+mod block_import;
 mod finality_rate;
 mod slo;
 mod timing;
@@ -9,6 +10,7 @@ pub use abft_score::ScoreMetrics;
 pub use slo::{run_metrics_service, SloMetrics};
 pub use timing::{Checkpoint, DefaultClock};
 pub type TimingBlockMetrics = timing::TimingBlockMetrics<DefaultClock>;
+pub type BlockImportMetrics = block_import::BlockImportMetrics<DefaultClock>;
 use substrate_prometheus_endpoint::{exponential_buckets, prometheus};
 
 const LOG_TARGET: &str = "aleph-metrics";
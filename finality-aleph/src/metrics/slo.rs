@@ -10,7 +10,7 @@ use crate::{
     block::ChainStatus,
     metrics::{
         best_block::BestBlockMetrics, timing::Checkpoint, transaction_pool::TransactionPoolMetrics,
-        TimingBlockMetrics, LOG_TARGET,
+        BlockImportMetrics, TimingBlockMetrics, LOG_TARGET,
     },
     BlockId, SubstrateChainStatus,
 };
@@ -33,6 +33,7 @@ pub type TxHash = <Hashing as sp_runtime::traits::Hash>::Output;
 #[derive(Clone)]
 pub struct SloMetrics {
     timing_metrics: TimingBlockMetrics,
+    block_import_metrics: BlockImportMetrics,
     finality_rate_metrics: FinalityRateMetrics,
     best_block_metrics: BestBlockMetrics,
     transaction_metrics: TransactionPoolMetrics<TxHash, DefaultClock>,
@@ -49,6 +50,11 @@ impl SloMetrics {
             );
             TimingBlockMetrics::Noop
         });
+        let block_import_metrics =
+            BlockImportMetrics::new(registry, DefaultClock).unwrap_or_else(|e| {
+                warn_creation_failed("block import", e);
+                BlockImportMetrics::Noop
+            });
         let finality_rate_metrics = FinalityRateMetrics::new(registry).unwrap_or_else(|e| {
             warn!(
                 target: LOG_TARGET,
@@ -69,6 +75,7 @@ impl SloMetrics {
 
         SloMetrics {
             timing_metrics,
+            block_import_metrics,
             finality_rate_metrics,
             best_block_metrics,
             transaction_metrics,
@@ -78,6 +85,7 @@ impl SloMetrics {
 
     pub fn is_noop(&self) -> bool {
         matches!(self.timing_metrics, TimingBlockMetrics::Noop)
+            && matches!(self.block_import_metrics, BlockImportMetrics::Noop)
             && matches!(self.finality_rate_metrics, FinalityRateMetrics::Noop)
             && matches!(self.best_block_metrics, BestBlockMetrics::Noop)
             && matches!(self.transaction_metrics, TransactionPoolMetrics::Noop)
@@ -87,6 +95,10 @@ impl SloMetrics {
         &self.timing_metrics
     }
 
+    pub fn block_import_metrics(&self) -> &BlockImportMetrics {
+        &self.block_import_metrics
+    }
+
     pub fn report_transaction_in_pool(&self, hash: TxHash) {
         self.transaction_metrics.report_in_pool(hash);
     }
@@ -94,6 +106,8 @@ impl SloMetrics {
     pub fn report_block_imported(&mut self, block_id: BlockId, is_new_best: bool, own: bool) {
         self.timing_metrics
             .report_block(block_id.hash(), Checkpoint::Imported);
+        self.block_import_metrics
+            .report_import_finished(block_id.hash());
         if own {
             self.finality_rate_metrics
                 .report_own_imported(block_id.clone());
@@ -111,7 +125,7 @@ impl SloMetrics {
         }
     }
 
-    pub fn report_block_finalized(&self, block_id: BlockId) {
+    pub fn report_block_finalized(&mut self, block_id: BlockId) {
         self.timing_metrics
             .report_block(block_id.hash(), Checkpoint::Finalized);
         self.finality_rate_metrics
@@ -288,6 +288,8 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
 
     /// Handle a user request for sending data.
     /// Returns a list of data to be sent over the network.
+    /// Broadcasts only reach the authorities of the session, i.e. the finality committee,
+    /// so other validators never receive e.g. RMC messages of the aggregator.
     pub fn on_user_message(
         &self,
         data: D,
@@ -457,13 +459,17 @@ mod tests {
     use std::{iter, time::Duration};
 
     use futures::StreamExt;
-    use network_clique::mock::{random_address, MockAddressingInformation};
+    use network_clique::{
+        mock::{random_address, MockAddressingInformation},
+        AddressingInformation,
+    };
 
     use super::{
         ConnectionCommand, Manager, ManagerActions, PreNonvalidatorSession, PreValidatorSession,
         SendError,
     };
     use crate::{
+        crypto::AuthorityVerifier,
         network::{
             address_cache::{test::noop_updater, ValidatorAddressCacheUpdater},
             mock::crypto_basics,
@@ -473,6 +479,7 @@ mod tests {
     };
 
     const NUM_NODES: usize = 7;
+    const FINALITY_COMMITTEE_SIZE: usize = 4;
     const DISCOVERY_PERIOD: Duration = Duration::from_secs(60);
 
     fn build() -> Manager<MockAddressingInformation, i32, impl ValidatorAddressCacheUpdater> {
@@ -639,4 +646,62 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn broadcasts_only_to_finality_committee() {
+        // All validators of the session, only some of which are in the finality committee.
+        let (validator_data, session_verifier) = crypto_basics(NUM_NODES);
+        let finality_verifier = AuthorityVerifier::new(
+            validator_data[..FINALITY_COMMITTEE_SIZE]
+                .iter()
+                .map(|(_, pen)| pen.authority_id())
+                .collect(),
+        );
+        let session_id = SessionId(43);
+
+        let mut manager = build();
+        let (node_id, pen) = validator_data[0].clone();
+        manager
+            .update_validator_session(PreValidatorSession {
+                session_id,
+                verifier: finality_verifier.clone(),
+                node_id,
+                pen,
+            })
+            .unwrap();
+
+        // Another member of the finality committee.
+        let mut finality_manager = build();
+        let (node_id, pen) = validator_data[1].clone();
+        let (ManagerActions { maybe_message, .. }, _) = finality_manager
+            .update_validator_session(PreValidatorSession {
+                session_id,
+                verifier: finality_verifier,
+                node_id,
+                pen,
+            })
+            .unwrap();
+        let message = maybe_message.expect("there should be a discovery message");
+        let finality_peer = message.0.address().peer_id();
+        manager.on_discovery_message(message);
+
+        // A validator producing blocks in the same session, but not finalizing them.
+        let mut producer_manager = build();
+        let (node_id, pen) = validator_data[NUM_NODES - 1].clone();
+        let (ManagerActions { maybe_message, .. }, _) = producer_manager
+            .update_validator_session(PreValidatorSession {
+                session_id,
+                verifier: session_verifier,
+                node_id,
+                pen,
+            })
+            .unwrap();
+        let message = maybe_message.expect("there should be a discovery message");
+        let ManagerActions { maybe_command, .. } = manager.on_discovery_message(message);
+        assert!(maybe_command.is_none());
+
+        let messages = manager.on_user_message(2137, session_id, Recipient::Everyone);
+        let recipients: Vec<_> = messages.into_iter().map(|(_, peer_id)| peer_id).collect();
+        assert_eq!(recipients, vec![finality_peer]);
+    }
 }
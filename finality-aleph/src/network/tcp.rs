@@ -7,7 +7,7 @@ use std::{
 
 use derive_more::{AsRef, Display};
 use log::info;
-use network_clique::{Dialer, Listener, PeerId, PublicKey, SecretKey};
+use network_clique::{DialError, Dialer, Listener, PeerId, PublicKey, SecretKey};
 use parity_scale_codec::{Decode, Encode};
 use sp_core::crypto::KeyTypeId;
 use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
@@ -147,7 +147,7 @@ struct TcpDialer;
 #[async_trait::async_trait]
 impl Dialer<SignedTcpAddressingInformation> for TcpDialer {
     type Connection = TcpStream;
-    type Error = std::io::Error;
+    type Error = DialError;
 
     async fn connect(
         &mut self,
@@ -167,6 +167,9 @@ impl Dialer<SignedTcpAddressingInformation> for TcpDialer {
             .filter_map(|address| address.to_socket_addrs().ok())
             .flatten()
             .collect();
+        if parsed_addresses.is_empty() {
+            return Err(DialError::Unreachable);
+        }
         let stream = TcpStream::connect(&parsed_addresses[..]).await?;
         if stream.set_linger(None).is_err() {
             info!(target: LOG_TARGET, "stream.set_linger(None) failed.");
@@ -3,7 +3,9 @@ use std::{marker::PhantomData, sync::Arc};
 use bip39::{Language, Mnemonic, MnemonicType};
 use futures::channel::oneshot;
 use log::{debug, error};
-use network_clique::{RateLimitingDialer, RateLimitingListener, Service, SpawnHandleT};
+use network_clique::{
+    Compression, KeepAlive, RateLimitingDialer, RateLimitingListener, Service, SpawnHandleT,
+};
 use pallet_aleph_runtime_api::AlephSessionApi;
 use primitives::TransactionHash;
 use rate_limiter::SharedRateLimiter;
@@ -13,6 +15,7 @@ use sc_transaction_pool_api::TransactionPool;
 use sp_consensus_aura::AuraApi;
 
 use crate::{
+    abft::UnitCreationDelaySource,
     aleph_primitives::{AuraId, Block},
     block::{
         substrate::{JustificationTranslator, SubstrateFinalizationInfo, VerifierCache},
@@ -36,7 +39,9 @@ use crate::{
     session_map::{
         AuthorityProviderImpl, FinalityNotifierImpl, FinalizedBlockProviderImpl, SessionMapUpdater,
     },
-    sync::{DatabaseIO as SyncDatabaseIO, Service as SyncService, IO as SyncIO},
+    sync::{
+        DatabaseIO as SyncDatabaseIO, SelectChainProvider, Service as SyncService, IO as SyncIO,
+    },
     AlephConfig,
 };
 
@@ -55,12 +60,13 @@ pub fn new_pen(mnemonic: &str, keystore: Arc<LocalKeystore>) -> AuthorityPen {
         .expect("we just generated this key so everything should work")
 }
 
-pub async fn run_validator_node<C, BE, TP>(aleph_config: AlephConfig<C, TP>)
+pub async fn run_validator_node<C, BE, TP, SC>(aleph_config: AlephConfig<C, TP, SC>)
 where
     C: crate::ClientForAleph<Block, BE> + Send + Sync + 'static,
     C::Api: AlephSessionApi<Block> + AuraApi<Block, AuraId>,
     BE: Backend<Block> + 'static,
     TP: TransactionPool<Block = Block, Hash = TransactionHash> + 'static,
+    SC: SelectChainProvider<Block>,
 {
     let AlephConfig {
         authentication_network,
@@ -73,16 +79,21 @@ where
         keystore,
         registry,
         unit_creation_delay,
+        adaptive_unit_creation_delay,
+        max_in_flight_aggregations,
         session_period,
         millisecs_per_block,
         justification_channel_provider,
         block_rx,
         backup_saving_path,
+        backup_retained_sessions,
         external_addresses,
         validator_port,
+        validator_network_compression,
         rate_limiter_config,
         sync_oracle,
         validator_address_cache,
+        current_session,
         transaction_pool,
     } = aleph_config;
 
@@ -113,13 +124,24 @@ where
     let dialer = RateLimitingDialer::new(dialer, alephbft_rate_limiter.share());
     let listener = RateLimitingListener::new(listener, alephbft_rate_limiter);
 
-    let (validator_network_service, validator_network) = Service::new(
+    let (mut validator_network_service, validator_network) = Service::new(
         dialer,
         listener,
         network_authority_pen,
         spawn_handle.clone(),
         registry.clone(),
+        KeepAlive::default(),
     );
+    if validator_network_compression {
+        validator_network_service.set_compression(vec![Compression::Zstd, Compression::None]);
+    }
+    let unit_creation_delay = match adaptive_unit_creation_delay {
+        Some(bounds) => UnitCreationDelaySource::Adaptive {
+            bounds,
+            latency: validator_network_service.latency_tracker(),
+        },
+        None => UnitCreationDelaySource::Fixed(unit_creation_delay),
+    };
     let (_validator_network_exit, exit) = oneshot::channel();
     spawn_handle.spawn("aleph/validator_network", async move {
         debug!(target: LOG_TARGET, "Validator network has started.");
@@ -153,6 +175,7 @@ where
 
     let slo_metrics = SloMetrics::new(registry.as_ref(), chain_status.clone());
     let timing_metrics = slo_metrics.timing_metrics().clone();
+    let block_import_metrics = slo_metrics.block_import_metrics().clone();
 
     spawn_handle.spawn("aleph/slo-metrics", {
         let slo_metrics = slo_metrics.clone();
@@ -188,7 +211,7 @@ where
         genesis_header,
     );
     let finalizer = AlephFinalizer::new(client.clone());
-    import_queue_handle.attach_metrics(timing_metrics.clone());
+    import_queue_handle.attach_metrics(timing_metrics.clone(), block_import_metrics);
     let justifications_for_sync = justification_channel_provider.get_sender();
     let sync_io = SyncIO::new(
         SyncDatabaseIO::new(chain_status.clone(), finalizer, import_queue_handle),
@@ -253,6 +276,7 @@ where
         session_authorities,
         sync_oracle,
         backup_saving_path,
+        backup_retained_sessions,
         chain_state: ChainStateImpl {
             client: client.clone(),
             _phantom: PhantomData,
@@ -264,6 +288,7 @@ where
             verifier,
             session_period,
             unit_creation_delay,
+            max_in_flight_aggregations,
             justifications_for_sync,
             JustificationTranslator::new(chain_status.clone()),
             request_block,
@@ -274,6 +299,7 @@ where
             score_metrics,
         ),
         session_info,
+        current_session,
     });
 
     debug!(target: LOG_TARGET, "Consensus party has started.");
@@ -119,7 +119,8 @@ pub fn rotate(
     Ok((backup_saver, backup_loader))
 }
 
-/// Removes the backup directory for all old sessions except the current session.
+/// Removes the backup directories of all sessions except the `retained_sessions` most recent ones,
+/// the current session included. At least the current session is always retained.
 ///
 /// `backup_path` is the path to the backup directory (i.e. the argument to `--backup-saving-path`).
 /// If it is `None`, nothing is done.
@@ -127,7 +128,12 @@ pub fn rotate(
 /// Any filesystem errors are returned.
 ///
 /// This should be done at the beginning of the new session.
-pub fn remove_old_backups(path: Option<PathBuf>, current_session: u32) -> IoResult<()> {
+pub fn remove_old_backups(
+    path: Option<PathBuf>,
+    current_session: u32,
+    retained_sessions: u32,
+) -> IoResult<()> {
+    let oldest_retained_session = current_session.saturating_sub(retained_sessions.max(1) - 1);
     if let Some(path) = path {
         if !path.exists() {
             return Ok(());
@@ -137,7 +143,7 @@ pub fn remove_old_backups(path: Option<PathBuf>, current_session: u32) -> IoResu
             match item.file_name().to_str() {
                 Some(name) => match name.parse::<u32>() {
                     Ok(session_id) => {
-                        if session_id < current_session {
+                        if session_id < oldest_retained_session {
                             fs::remove_dir_all(item.path())?;
                         }
                     }
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display, time::Duration};
 
 use futures::{
     channel::{mpsc, oneshot},
@@ -14,7 +14,7 @@ use crate::{
     aggregation::{Aggregator, SignableTypedHash},
     aleph_primitives::BlockHash,
     block::{
-        substrate::{Justification, JustificationTranslator},
+        substrate::{Justification, JustificationTranslator, TranslateError},
         Header, HeaderBackend,
     },
     crypto::Signature,
@@ -26,10 +26,13 @@ use crate::{
         AuthoritySubtaskCommon, Task,
     },
     sync::JustificationSubmissions,
-    BlockId, CurrentRmcNetworkData, Keychain, LegacyRmcNetworkData, SessionBoundaries,
+    BlockId, BlockNumber, CurrentRmcNetworkData, Keychain, LegacyRmcNetworkData, SessionBoundaries,
     STATUS_REPORT_INTERVAL,
 };
 
+/// How often we check whether blocks we are aggregating for got finalized in some other way.
+const FINALIZATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug)]
 pub enum Error {
     MultisignaturesStreamTerminated,
@@ -47,14 +50,78 @@ impl Display for Error {
     }
 }
 
+/// Turns multisignatures gathered by the aggregator into justifications acceptable to sync.
+pub trait TranslateJustification: Send + Sync {
+    type Error: Display;
+
+    fn translate(
+        &self,
+        aleph_justification: AlephJustification,
+        block_id: BlockId,
+    ) -> Result<Justification, Self::Error>;
+}
+
+impl TranslateJustification for JustificationTranslator {
+    type Error = TranslateError;
+
+    fn translate(
+        &self,
+        aleph_justification: AlephJustification,
+        block_id: BlockId,
+    ) -> Result<Justification, Self::Error> {
+        JustificationTranslator::translate(self, aleph_justification, block_id)
+    }
+}
+
 /// IO channels used by the aggregator task.
-pub struct IO<JS>
+pub struct IO<JS, JT = JustificationTranslator>
 where
     JS: JustificationSubmissions<Justification> + Send + Sync + Clone,
+    JT: TranslateJustification,
 {
     pub blocks_from_interpreter: mpsc::UnboundedReceiver<BlockId>,
     pub justifications_for_chain: JS,
-    pub justification_translator: JustificationTranslator,
+    pub justification_translator: JT,
+}
+
+/// Blocks for which signatures are currently being aggregated. Their number is capped, so that
+/// during long finality stalls we do not keep starting aggregations for every new block.
+/// Once a block is finalized, aggregating its ancestors is pointless, so their slots are released
+/// as well.
+struct InFlightAggregations {
+    blocks: HashMap<BlockHash, BlockNumber>,
+    max: usize,
+}
+
+impl InFlightAggregations {
+    fn new(max: u32) -> Self {
+        InFlightAggregations {
+            blocks: HashMap::new(),
+            // With no room at all the aggregator would never finalize anything.
+            max: (max as usize).max(1),
+        }
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.blocks.len() < self.max
+    }
+
+    fn start(&mut self, block: &BlockId) {
+        self.blocks.insert(block.hash(), block.number());
+    }
+
+    /// Releases the slot of the given block and of all its ancestors.
+    fn finish(&mut self, hash: &BlockHash) {
+        if let Some(number) = self.blocks.get(hash).copied() {
+            self.release_finalized(number);
+        }
+    }
+
+    /// Releases the slots of all blocks up to the given finalized number.
+    fn release_finalized(&mut self, number: BlockNumber) {
+        self.blocks
+            .retain(|_, in_flight_number| *in_flight_number > number);
+    }
 }
 
 async fn process_new_block_data<CN, LN>(
@@ -73,17 +140,18 @@ async fn process_new_block_data<CN, LN>(
         .await;
 }
 
-fn process_block_hash<H, C, JS>(
+fn process_block_hash<H, C, JS, JT>(
     hash: BlockHash,
     multisignature: SignatureSet<Signature>,
     justifications_for_chain: &mut JS,
-    justification_translator: &JustificationTranslator,
+    justification_translator: &JT,
     client: &C,
 ) -> Result<(), ()>
 where
     H: Header,
     C: HeaderBackend<H> + 'static,
     JS: JustificationSubmissions<Justification> + Send + Sync + Clone,
+    JT: TranslateJustification,
 {
     let number = client.hash_to_id(hash).unwrap().unwrap().number();
     // The unwrap might actually fail if data availability is not implemented correctly.
@@ -104,17 +172,19 @@ where
     Ok(())
 }
 
-async fn run_aggregator<H, C, CN, LN, JS>(
+async fn run_aggregator<H, C, CN, LN, JS, JT>(
     mut aggregator: Aggregator<CN, LN>,
-    io: IO<JS>,
+    io: IO<JS, JT>,
     client: C,
     session_boundaries: &SessionBoundaries,
     mut metrics: TimingBlockMetrics,
+    max_in_flight_aggregations: u32,
     mut exit_rx: oneshot::Receiver<()>,
 ) -> Result<(), Error>
 where
     H: Header,
     JS: JustificationSubmissions<Justification> + Send + Sync + Clone,
+    JT: TranslateJustification,
     C: HeaderBackend<H> + 'static,
     LN: Network<LegacyRmcNetworkData>,
     CN: Network<CurrentRmcNetworkData>,
@@ -138,15 +208,19 @@ where
     pin_mut!(blocks_from_interpreter);
     let mut hash_of_last_block = None;
     let mut no_more_blocks = blocks_from_interpreter.is_terminated();
+    let mut in_flight = InFlightAggregations::new(max_in_flight_aggregations);
 
     let mut status_ticker = time::interval(STATUS_REPORT_INTERVAL);
+    let mut finalization_ticker = time::interval(FINALIZATION_CHECK_INTERVAL);
 
     loop {
         trace!(target: "aleph-party", "Aggregator Loop started a next iteration");
         tokio::select! {
-            maybe_block = blocks_from_interpreter.next(), if !no_more_blocks => match maybe_block {
+            // Blocks that do not fit wait in the channel, so they are aggregated in order later.
+            maybe_block = blocks_from_interpreter.next(), if !no_more_blocks && in_flight.has_capacity() => match maybe_block {
                 Some(block) => {
                     hash_of_last_block = Some(block.hash());
+                    in_flight.start(&block);
                     process_new_block_data::<CN, LN>(
                         &mut aggregator,
                         block,
@@ -162,6 +236,7 @@ where
                 let (hash, multisignature) = multisigned_hash.ok_or(Error::MultisignaturesStreamTerminated)?;
                 match hash {
                     Block(hash) => {
+                        in_flight.finish(&hash);
                         process_block_hash(hash, multisignature, &mut justifications_for_chain, &justification_translator, &client).map_err(|_| Error::UnableToProcessHash)?;
                         if Some(hash) == hash_of_last_block {
                             hash_of_last_block = None;
@@ -173,6 +248,11 @@ where
             _ = status_ticker.tick() => {
                 aggregator.status_report();
             },
+            // Blocks might get finalized with justifications from other nodes, in which case
+            // their aggregations would never complete.
+            _ = finalization_ticker.tick() => {
+                in_flight.release_finalized(client.top_finalized_id().number());
+            },
             _ = &mut exit_rx => {
                 debug!(target: "aleph-party", "Aggregator received exit signal. Terminating.");
                 break;
@@ -200,6 +280,7 @@ pub fn task<H, C, CN, LN, JS>(
     session_boundaries: SessionBoundaries,
     metrics: TimingBlockMetrics,
     multikeychain: Keychain,
+    max_in_flight_aggregations: u32,
     version: AggregatorVersion<CN, LN>,
 ) -> Task
 where
@@ -227,6 +308,7 @@ where
                 client,
                 &session_boundaries,
                 metrics,
+                max_in_flight_aggregations,
                 exit,
             )
             .await;
@@ -246,3 +328,225 @@ where
         spawn_handle.spawn_essential_with_result("aleph/consensus_session_aggregator", task);
     Task::new(handle, stop)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use futures::{
+        channel::{mpsc, oneshot},
+        join,
+    };
+    use parity_scale_codec::Encode;
+    use parking_lot::Mutex;
+
+    use super::{run_aggregator, InFlightAggregations, TranslateJustification, IO};
+    use crate::{
+        aggregation::Aggregator,
+        aleph_primitives::BlockHash,
+        block::{
+            mock::MockHeader,
+            substrate::{Justification, TranslateError},
+            Header, HeaderBackend,
+        },
+        justification::AlephJustification,
+        metrics::TimingBlockMetrics,
+        network::{data::component::SimpleNetwork, mock::crypto_basics},
+        session::{SessionBoundaryInfo, SessionId, SessionPeriod},
+        BlockId, BlockNumber, CurrentRmcNetworkData, Keychain, LegacyRmcNetworkData, Recipient,
+    };
+
+    fn block_ids(count: usize) -> Vec<BlockId> {
+        MockHeader::genesis()
+            .random_branch()
+            .take(count)
+            .map(|header| header.id())
+            .collect()
+    }
+
+    #[test]
+    fn zero_limit_still_allows_progress() {
+        let in_flight = InFlightAggregations::new(0);
+        assert!(in_flight.has_capacity());
+    }
+
+    #[test]
+    fn stays_within_limit_during_stall_and_resumes_in_order() {
+        let limit = 4;
+        let mut in_flight = InFlightAggregations::new(limit);
+        let blocks = block_ids(20);
+        let mut waiting = blocks.iter();
+        let mut started = Vec::new();
+
+        // Nothing gets multisigned, so only as many blocks as the limit allows are started.
+        while in_flight.has_capacity() {
+            let block = waiting.next().expect("more blocks than the limit");
+            in_flight.start(block);
+            started.push(block.clone());
+        }
+        assert_eq!(in_flight.blocks.len(), limit as usize);
+        assert_eq!(started, blocks[..limit as usize]);
+
+        // Participation returns, each finished block makes room for the next one in order.
+        let mut finished = 0;
+        while finished < blocks.len() {
+            in_flight.finish(&started[finished].hash());
+            finished += 1;
+            while in_flight.has_capacity() {
+                match waiting.next() {
+                    Some(block) => {
+                        in_flight.start(block);
+                        started.push(block.clone());
+                    }
+                    None => break,
+                }
+            }
+            assert!(in_flight.blocks.len() <= limit as usize);
+        }
+        assert_eq!(started, blocks);
+        assert!(in_flight.blocks.is_empty());
+    }
+
+    #[test]
+    fn finishing_a_block_releases_its_ancestors() {
+        let mut in_flight = InFlightAggregations::new(4);
+        let blocks = block_ids(4);
+        for block in &blocks {
+            in_flight.start(block);
+        }
+        in_flight.finish(&blocks[2].hash());
+        assert_eq!(in_flight.blocks.len(), 1);
+        assert!(in_flight.blocks.contains_key(&blocks[3].hash()));
+    }
+
+    #[derive(Clone)]
+    struct TestClient {
+        top_finalized: Arc<Mutex<BlockId>>,
+    }
+
+    impl HeaderBackend<MockHeader> for TestClient {
+        type Error = ();
+
+        fn header(&self, _: &BlockId) -> Result<Option<MockHeader>, Self::Error> {
+            Ok(None)
+        }
+
+        fn header_of_finalized_at(
+            &self,
+            _: BlockNumber,
+        ) -> Result<Option<MockHeader>, Self::Error> {
+            Ok(None)
+        }
+
+        fn top_finalized_id(&self) -> BlockId {
+            self.top_finalized.lock().clone()
+        }
+
+        fn hash_to_id(&self, _: BlockHash) -> Result<Option<BlockId>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    struct NoTranslation;
+
+    impl TranslateJustification for NoTranslation {
+        type Error = TranslateError;
+
+        fn translate(
+            &self,
+            _: AlephJustification,
+            _: BlockId,
+        ) -> Result<Justification, Self::Error> {
+            Err(TranslateError::NoBlock)
+        }
+    }
+
+    type TestNetwork<D> =
+        SimpleNetwork<D, mpsc::UnboundedReceiver<D>, mpsc::UnboundedSender<(D, Recipient)>>;
+
+    /// Collects what the aggregator broadcast so far and checks which blocks it is aggregating.
+    fn aggregated(
+        sent: &mut mpsc::UnboundedReceiver<(CurrentRmcNetworkData, Recipient)>,
+        messages: &mut Vec<Vec<u8>>,
+        blocks: &[BlockId],
+    ) -> Vec<bool> {
+        while let Ok(Some((message, _))) = sent.try_next() {
+            messages.push(message.encode());
+        }
+        blocks
+            .iter()
+            .map(|block| {
+                let hash = block.hash();
+                let hash = hash.as_ref();
+                messages
+                    .iter()
+                    .any(|message| message.windows(hash.len()).any(|window| window == hash))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn aggregator_releases_slots_of_blocks_finalized_during_stall() {
+        // Nobody else signs anything, so none of the aggregations complete.
+        let (validator_data, verifier) = crypto_basics(4);
+        let (node_id, pen) = validator_data[0].clone();
+        let keychain = Keychain::new(node_id, verifier, pen);
+        let (sent_tx, mut sent_rx) = mpsc::unbounded();
+        let (_incoming_tx, incoming_rx) = mpsc::unbounded();
+        let aggregator = Aggregator::<_, TestNetwork<LegacyRmcNetworkData>>::new_current(
+            &keychain,
+            TestNetwork::<CurrentRmcNetworkData>::new(incoming_rx, sent_tx),
+        );
+
+        let blocks = block_ids(6);
+        let (blocks_tx, blocks_rx) = mpsc::unbounded();
+        for block in &blocks {
+            blocks_tx
+                .unbounded_send(block.clone())
+                .expect("should send");
+        }
+        let (justifications_tx, _justifications_rx) = mpsc::unbounded::<Justification>();
+        let io = IO {
+            blocks_from_interpreter: blocks_rx,
+            justifications_for_chain: justifications_tx,
+            justification_translator: NoTranslation,
+        };
+        let top_finalized = Arc::new(Mutex::new(MockHeader::genesis().id()));
+        let client = TestClient {
+            top_finalized: top_finalized.clone(),
+        };
+        let session_boundaries =
+            SessionBoundaryInfo::new(SessionPeriod(100)).boundaries_for_session(SessionId(0));
+        let (exit_tx, exit_rx) = oneshot::channel();
+
+        let aggregator = run_aggregator(
+            aggregator,
+            io,
+            client,
+            &session_boundaries,
+            TimingBlockMetrics::noop(),
+            2,
+            exit_rx,
+        );
+        let checks = async {
+            let mut messages = Vec::new();
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            assert_eq!(
+                aggregated(&mut sent_rx, &mut messages, &blocks),
+                vec![true, true, false, false, false, false]
+            );
+
+            // The stalled blocks get finalized with justifications from elsewhere.
+            *top_finalized.lock() = blocks[1].clone();
+            tokio::time::sleep(Duration::from_millis(1500)).await;
+            assert_eq!(
+                aggregated(&mut sent_rx, &mut messages, &blocks),
+                vec![true, true, true, true, false, false]
+            );
+
+            exit_tx.send(()).expect("aggregator should be running");
+        };
+        let (result, _) = join!(aggregator, checks);
+        assert!(result.is_ok());
+    }
+}
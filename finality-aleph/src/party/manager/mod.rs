@@ -12,7 +12,7 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use crate::{
     abft::{
         current_create_aleph_config, legacy_create_aleph_config, run_current_member,
-        run_legacy_member, CurrentPerformanceService, SpawnHandle,
+        run_legacy_member, CurrentPerformanceService, SpawnHandle, UnitCreationDelaySource,
     },
     aleph_primitives::{BlockHash, BlockNumber, KEY_TYPE},
     block::{
@@ -21,6 +21,7 @@ use crate::{
     },
     crypto::{AuthorityPen, AuthorityVerifier},
     data_io::{ChainTracker, DataStore, OrderedDataInterpreter, SubstrateChainInfoProvider},
+    guard_session_version,
     metrics::{ScoreMetrics, TimingBlockMetrics},
     mpsc,
     network::{
@@ -37,7 +38,7 @@ use crate::{
     sync::JustificationSubmissions,
     AuthorityId, BlockId, CurrentRmcNetworkData, Keychain, LegacyRmcNetworkData, NodeIndex,
     ProvideRuntimeApi, SessionBoundaries, SessionBoundaryInfo, SessionId, SessionPeriod,
-    UnitCreationDelay, VersionedNetworkData,
+    SessionVersion, VersionedNetworkData,
 };
 
 mod aggregator;
@@ -107,7 +108,8 @@ where
     best_block_selection_strategy: BBS,
     verifier: V,
     session_info: SessionBoundaryInfo,
-    unit_creation_delay: UnitCreationDelay,
+    unit_creation_delay: UnitCreationDelaySource,
+    max_in_flight_aggregations: u32,
     justifications_for_sync: JS,
     justification_translator: JustificationTranslator,
     block_requester: RB,
@@ -140,7 +142,8 @@ where
         best_block_selection_strategy: BBS,
         verifier: V,
         session_period: SessionPeriod,
-        unit_creation_delay: UnitCreationDelay,
+        unit_creation_delay: UnitCreationDelaySource,
+        max_in_flight_aggregations: u32,
         justifications_for_sync: JS,
         justification_translator: JustificationTranslator,
         block_requester: RB,
@@ -157,6 +160,7 @@ where
             verifier,
             session_info: SessionBoundaryInfo::new(session_period),
             unit_creation_delay,
+            max_in_flight_aggregations,
             justifications_for_sync,
             justification_translator,
             block_requester,
@@ -201,9 +205,13 @@ where
             self.verifier.clone(),
             session_boundaries.clone(),
         );
-        let consensus_config =
-            legacy_create_aleph_config(n_members, node_id, session_id, self.unit_creation_delay);
-        let data_network = data_network.map();
+        let consensus_config = legacy_create_aleph_config(
+            n_members,
+            node_id,
+            session_id,
+            self.unit_creation_delay.clone(),
+        );
+        let data_network = guard_session_version(data_network, SessionVersion::Legacy).map();
 
         let (unfiltered_aleph_network, rmc_network) =
             split(data_network, "aleph_network", "rmc_network");
@@ -239,6 +247,7 @@ where
                 session_boundaries,
                 self.metrics.clone(),
                 multikeychain,
+                self.max_in_flight_aggregations,
                 AggregatorVersion::<CurrentNetworkType, _>::Legacy(rmc_network),
             ),
             task::task(subtask_common.clone(), chain_tracker, "chain tracker"),
@@ -284,9 +293,13 @@ where
             ordered_data_interpreter,
             self.score_metrics.clone(),
         );
-        let consensus_config =
-            current_create_aleph_config(n_members, node_id, session_id, self.unit_creation_delay);
-        let data_network = data_network.map();
+        let consensus_config = current_create_aleph_config(
+            n_members,
+            node_id,
+            session_id,
+            self.unit_creation_delay.clone(),
+        );
+        let data_network = guard_session_version(data_network, SessionVersion::Current).map();
 
         let (unfiltered_aleph_network, rmc_network) =
             split(data_network, "aleph_network", "rmc_network");
@@ -318,6 +331,7 @@ where
                 session_boundaries,
                 self.metrics.clone(),
                 multikeychain,
+                self.max_in_flight_aggregations,
                 AggregatorVersion::<_, LegacyNetworkType>::Current(rmc_network),
             ),
             task::task(subtask_common.clone(), chain_tracker, "chain tracker"),
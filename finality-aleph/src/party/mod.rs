@@ -11,7 +11,7 @@ use crate::{
         manager::{Handle, Task, TaskCommon as AuthoritySubtaskCommon},
         traits::{ChainState, NodeSessionManager},
     },
-    session::SessionBoundaryInfo,
+    session::{CurrentSessionTracker, SessionBoundaryInfo},
     session_map::ReadOnlySessionMap,
     SessionId, SyncOracle,
 };
@@ -31,8 +31,10 @@ pub(crate) struct ConsensusPartyParams<CS, NSM> {
     pub chain_state: CS,
     pub sync_oracle: SyncOracle,
     pub backup_saving_path: Option<PathBuf>,
+    pub backup_retained_sessions: u32,
     pub session_manager: NSM,
     pub session_info: SessionBoundaryInfo,
+    pub current_session: CurrentSessionTracker,
 }
 
 pub(crate) struct ConsensusParty<CS, NSM>
@@ -44,8 +46,10 @@ where
     chain_state: CS,
     sync_oracle: SyncOracle,
     backup_saving_path: Option<PathBuf>,
+    backup_retained_sessions: u32,
     session_manager: NSM,
     session_info: SessionBoundaryInfo,
+    current_session: CurrentSessionTracker,
 }
 
 const SESSION_STATUS_CHECK_PERIOD: Duration = Duration::from_millis(1000);
@@ -60,18 +64,21 @@ where
             session_authorities,
             sync_oracle,
             backup_saving_path,
+            backup_retained_sessions,
             chain_state,
             session_manager,
             session_info,
-            ..
+            current_session,
         } = params;
         Self {
             sync_oracle,
             session_authorities,
             backup_saving_path,
+            backup_retained_sessions,
             chain_state,
             session_manager,
             session_info,
+            current_session,
         }
     }
 
@@ -105,8 +112,11 @@ where
         let last_block = self.session_info.last_block_of_session(session_id);
         if session_id.0.checked_sub(1).is_some() {
             let backup_saving_path = self.backup_saving_path.clone();
+            let retained_sessions = self.backup_retained_sessions;
             spawn_blocking(move || {
-                if let Err(e) = backup::remove_old_backups(backup_saving_path, session_id.0) {
+                if let Err(e) =
+                    backup::remove_old_backups(backup_saving_path, session_id.0, retained_sessions)
+                {
                     warn!(target: "aleph-party", "Error when clearing old backups: {}", e);
                 }
             });
@@ -145,9 +155,13 @@ where
         let authorities = authority_data.authorities();
 
         trace!(target: "aleph-party", "Authority data for session {:?}: {:?}", session_id, authorities);
-        let mut maybe_authority_task = if let Some(node_id) =
-            self.session_manager.node_idx(authorities)
-        {
+        let maybe_node_id = self.session_manager.node_idx(authorities);
+        self.current_session.update(
+            session_id,
+            &self.session_info.boundaries_for_session(session_id),
+            maybe_node_id.is_some(),
+        );
+        let mut maybe_authority_task = if let Some(node_id) = maybe_node_id {
             match backup::rotate(self.backup_saving_path.clone(), session_id.0) {
                 Ok(backup) => {
                     debug!(target: "aleph-party", "Running session {:?} as authority id {:?}", session_id, node_id);
@@ -266,12 +280,12 @@ mod tests {
     use tokio::{task::JoinHandle, time::sleep};
 
     use crate::{
-        aleph_primitives::{AuthorityId, SessionAuthorityData},
+        aleph_primitives::{AuthorityId, SessionAuthorityData, DEFAULT_BACKUP_RETAINED_SESSIONS},
         party::{
             mocks::{MockChainState, MockNodeSessionManager},
             ConsensusParty, ConsensusPartyParams, SESSION_STATUS_CHECK_PERIOD,
         },
-        session::SessionBoundaryInfo,
+        session::{CurrentSessionTracker, SessionBoundaryInfo},
         session_map::SharedSessionMap,
         SessionId, SessionPeriod, SyncOracle,
     };
@@ -516,8 +530,10 @@ mod tests {
             chain_state,
             sync_oracle,
             backup_saving_path: None,
+            backup_retained_sessions: DEFAULT_BACKUP_RETAINED_SESSIONS,
             session_manager,
             session_info,
+            current_session: CurrentSessionTracker::new(),
         };
 
         (ConsensusParty::new(params), controller)
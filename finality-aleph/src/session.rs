@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
 use crate::aleph_primitives::BlockNumber;
@@ -53,6 +56,50 @@ impl SessionBoundaryInfo {
     }
 }
 
+/// Information about the session the node is currently running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrentSessionInfo {
+    /// Index of the session.
+    pub session: SessionId,
+    /// Number of the first block of the session.
+    pub first_block: BlockNumber,
+    /// Number of the last block of the session.
+    pub last_block: BlockNumber,
+    /// Whether the node is a member of the committee in this session.
+    pub is_committee_member: bool,
+}
+
+/// Stores information about the session the node is currently running.
+#[derive(Clone, Default)]
+pub struct CurrentSessionTracker {
+    data: Arc<Mutex<Option<CurrentSessionInfo>>>,
+}
+
+impl CurrentSessionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(
+        &self,
+        session: SessionId,
+        boundaries: &SessionBoundaries,
+        is_committee_member: bool,
+    ) {
+        *self.data.lock() = Some(CurrentSessionInfo {
+            session,
+            first_block: boundaries.first_block(),
+            last_block: boundaries.last_block(),
+            is_committee_member,
+        });
+    }
+
+    /// The session the node is currently running, if it has started any.
+    pub fn current(&self) -> Option<CurrentSessionInfo> {
+        self.data.lock().clone()
+    }
+}
+
 #[cfg(test)]
 pub mod testing {
     use sp_runtime::testing::UintAuthorityId;
@@ -204,6 +204,28 @@ where
     ChainExtensionRequest(State<J>),
 }
 
+/// Data to be sent over the network, version 4.
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum NetworkDataV4<B: Block, J: Justification>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    /// A periodic state broadcast, so that neighbouring nodes can request what they are missing,
+    /// send what we are missing, and sometimes just use the justifications to update their own
+    /// state.
+    StateBroadcast(State<J>),
+    /// Response to a state broadcast. Contains at most two justifications that the peer will
+    /// understand.
+    StateBroadcastResponse(J::Unverified, Option<J::Unverified>),
+    /// An explicit request for data, potentially a lot of it.
+    Request(Request<J>),
+    /// Response to the request for data.
+    RequestResponse(ResponseItems<B, J>),
+    /// A request for a chain extension.
+    ChainExtensionRequest(State<J>),
+}
+
 /// Data to be sent over the network, current version.
 #[derive(Clone, Debug, Encode, Decode)]
 pub enum NetworkData<B: Block, J: Justification>
@@ -224,6 +246,9 @@ where
     RequestResponse(ResponseItems<B, J>),
     /// A request for a chain extension.
     ChainExtensionRequest(State<J>),
+    /// A request for the justification of the finalized block with the given id, answered with a
+    /// `RequestResponse` if the peer has it.
+    JustificationRequest(BlockId),
 }
 
 impl<B: Block, J: Justification> TryFrom<NetworkDataV3<B, J>> for NetworkData<B, J>
@@ -250,25 +275,50 @@ where
     }
 }
 
-impl<B: Block, J: Justification> From<NetworkData<B, J>> for NetworkDataV3<B, J>
+impl<B: Block, J: Justification> From<NetworkDataV4<B, J>> for NetworkData<B, J>
 where
     J: Justification,
     B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
 {
-    fn from(data: NetworkData<B, J>) -> Self {
+    fn from(data: NetworkDataV4<B, J>) -> Self {
         match data {
-            NetworkData::StateBroadcast(state) => NetworkDataV3::StateBroadcast(state),
+            NetworkDataV4::StateBroadcast(state) => NetworkData::StateBroadcast(state),
+            NetworkDataV4::StateBroadcastResponse(justification, maybe_justification) => {
+                NetworkData::StateBroadcastResponse(justification, maybe_justification)
+            }
+            NetworkDataV4::Request(request) => NetworkData::Request(request),
+            NetworkDataV4::RequestResponse(response_items) => {
+                NetworkData::RequestResponse(response_items)
+            }
+            NetworkDataV4::ChainExtensionRequest(state) => {
+                NetworkData::ChainExtensionRequest(state)
+            }
+        }
+    }
+}
+
+impl<B: Block, J: Justification> TryFrom<NetworkData<B, J>> for NetworkDataV4<B, J>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    type Error = ();
+
+    fn try_from(data: NetworkData<B, J>) -> Result<Self, Self::Error> {
+        Ok(match data {
+            NetworkData::StateBroadcast(state) => NetworkDataV4::StateBroadcast(state),
             NetworkData::StateBroadcastResponse(justification, maybe_justification) => {
-                NetworkDataV3::StateBroadcastResponse(justification, maybe_justification)
+                NetworkDataV4::StateBroadcastResponse(justification, maybe_justification)
             }
-            NetworkData::Request(request) => NetworkDataV3::Request(request.into()),
+            NetworkData::Request(request) => NetworkDataV4::Request(request),
             NetworkData::RequestResponse(response_items) => {
-                NetworkDataV3::RequestResponse(response_items)
+                NetworkDataV4::RequestResponse(response_items)
             }
             NetworkData::ChainExtensionRequest(state) => {
-                NetworkDataV3::ChainExtensionRequest(state)
+                NetworkDataV4::ChainExtensionRequest(state)
             }
-        }
+            NetworkData::JustificationRequest(_) => return Err(()),
+        })
     }
 }
 
@@ -282,7 +332,8 @@ where
     // Most likely from the future.
     Other(Version, Vec<u8>),
     V3(NetworkDataV3<B, J>),
-    V4(NetworkData<B, J>),
+    V4(NetworkDataV4<B, J>),
+    V5(NetworkData<B, J>),
 }
 
 // We need 32 bits, since blocks can be quite sizeable.
@@ -334,6 +385,7 @@ where
                 Other(_, payload) => payload.len(),
                 V3(data) => data.size_hint(),
                 V4(data) => data.size_hint(),
+                V5(data) => data.size_hint(),
             }
     }
 
@@ -343,6 +395,7 @@ where
             Other(version, payload) => encode_with_version(*version, payload),
             V3(data) => encode_with_version(Version(3), &data.encode()),
             V4(data) => encode_with_version(Version(4), &data.encode()),
+            V5(data) => encode_with_version(Version(5), &data.encode()),
         }
     }
 }
@@ -358,7 +411,8 @@ where
         let num_bytes = ByteCount::decode(input)?;
         match version {
             Version(3) => Ok(V3(NetworkDataV3::decode(input)?)),
-            Version(4) => Ok(V4(NetworkData::decode(input)?)),
+            Version(4) => Ok(V4(NetworkDataV4::decode(input)?)),
+            Version(5) => Ok(V5(NetworkData::decode(input)?)),
             _ => {
                 if num_bytes > MAX_SYNC_MESSAGE_SIZE {
                     Err("Sync message has unknown version and is encoded as more than the maximum size.")?;
@@ -412,11 +466,11 @@ where
         data: NetworkData<B, J>,
         peer_id: Self::PeerId,
     ) -> Result<(), Self::Error> {
-        self.inner.send_to(
-            VersionedNetworkData::V3(data.clone().into()),
-            peer_id.clone(),
-        )?;
-        self.inner.send_to(VersionedNetworkData::V4(data), peer_id)
+        if let Ok(data) = data.clone().try_into() {
+            self.inner
+                .send_to(VersionedNetworkData::V4(data), peer_id.clone())?;
+        }
+        self.inner.send_to(VersionedNetworkData::V5(data), peer_id)
     }
 
     fn send_to_random(
@@ -424,18 +478,19 @@ where
         data: NetworkData<B, J>,
         peer_ids: HashSet<Self::PeerId>,
     ) -> Result<(), Self::Error> {
-        self.inner.send_to_random(
-            VersionedNetworkData::V3(data.clone().into()),
-            peer_ids.clone(),
-        )?;
+        if let Ok(data) = data.clone().try_into() {
+            self.inner
+                .send_to_random(VersionedNetworkData::V4(data), peer_ids.clone())?;
+        }
         self.inner
-            .send_to_random(VersionedNetworkData::V4(data), peer_ids)
+            .send_to_random(VersionedNetworkData::V5(data), peer_ids)
     }
 
     fn broadcast(&mut self, data: NetworkData<B, J>) -> Result<(), Self::Error> {
-        self.inner
-            .broadcast(VersionedNetworkData::V3(data.clone().into()))?;
-        self.inner.broadcast(VersionedNetworkData::V4(data))
+        if let Ok(data) = data.clone().try_into() {
+            self.inner.broadcast(VersionedNetworkData::V4(data))?;
+        }
+        self.inner.broadcast(VersionedNetworkData::V5(data))
     }
 
     /// Retrieves next message from the network.
@@ -459,7 +514,8 @@ where
                         "Received request with no header in target, this should never happen.",
                     ),
                 },
-                (VersionedNetworkData::V4(data), peer_id) => return Ok((data, peer_id)),
+                (VersionedNetworkData::V4(data), peer_id) => return Ok((data.into(), peer_id)),
+                (VersionedNetworkData::V5(data), peer_id) => return Ok((data, peer_id)),
             }
         }
     }
@@ -157,8 +157,13 @@ impl Display for Status {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         write!(
             f,
-            "Imported: {}, finalized: {}, favourite: {}.",
-            self.imported, self.finalized, self.favourite
+            "Imported: {}, finalized: {}, favourite: {}, finality lag: {}.",
+            self.imported,
+            self.finalized,
+            self.favourite,
+            self.favourite
+                .number()
+                .saturating_sub(self.finalized.number())
         )
     }
 }
@@ -6,9 +6,9 @@ use std::{
 
 use crate::{
     block::{
-        Block, BlockImport, ChainStatus, Finalizer, Header, HeaderVerifier, Justification,
-        JustificationVerifier, UnverifiedHeader, UnverifiedHeaderFor, UnverifiedJustification,
-        VerifiedHeader,
+        Block, BlockImport, BlockStatus, ChainStatus, FinalizationStatus, Finalizer, Header,
+        HeaderVerifier, Justification, JustificationVerifier, UnverifiedHeader,
+        UnverifiedHeaderFor, UnverifiedJustification, VerifiedHeader,
     },
     session::{SessionBoundaryInfo, SessionId},
     sync::{
@@ -657,6 +657,64 @@ where
         Ok((action, maybe_proof))
     }
 
+    /// Handle a request for the justification of a specific finalized block.
+    /// Returns the justification if we have one for exactly that block.
+    pub fn handle_justification_request(
+        &self,
+        id: BlockId,
+    ) -> Result<Option<J::Unverified>, <Self as HandlerTypes>::Error> {
+        Ok(
+            match self
+                .chain_status
+                .finalized_at(id.number())
+                .map_err(Error::ChainStatus)?
+            {
+                FinalizationStatus::FinalizedWithJustification(justification)
+                    if justification.header().id() == id =>
+                {
+                    Some(justification.into_unverified())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// The id of an imported block we cannot finalize only because we lack its justification.
+    /// That is the last block of the session following our top finalized block, if our favourite
+    /// branch already contains it.
+    pub fn missing_justification(&self) -> Result<Option<BlockId>, <Self as HandlerTypes>::Error> {
+        let top_finalized_number = self
+            .chain_status
+            .top_finalized()
+            .map_err(Error::ChainStatus)?
+            .header()
+            .id()
+            .number();
+        let target = self.session_info.last_block_of_session(
+            self.session_info
+                .session_id_from_block_num(top_finalized_number + 1),
+        );
+        let mut header = self.forest.favourite_block();
+        while header.id().number() > target {
+            let parent_id = match header.parent_id() {
+                Some(parent_id) => parent_id,
+                None => return Ok(None),
+            };
+            header = match self
+                .chain_status
+                .status_of(parent_id)
+                .map_err(Error::ChainStatus)?
+            {
+                BlockStatus::Present(header) => header,
+                BlockStatus::Justified(_) | BlockStatus::Unknown => return Ok(None),
+            };
+        }
+        Ok(match header.id().number() == target {
+            true => Some(header.id()),
+            false => None,
+        })
+    }
+
     /// The current state of our database.
     pub fn state(&self) -> Result<State<J>, <Self as HandlerTypes>::Error> {
         let top_justification = self
@@ -1669,6 +1727,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn responds_to_justification_requests() {
+        let (mut handler, mut backend, _keep, _genesis) = setup();
+        let headers = import_branch(&mut backend, 2);
+        for header in &headers {
+            handler
+                .block_imported(header.clone())
+                .expect("importing in order");
+        }
+        let justification = MockJustification::for_header(headers[0].clone());
+        let peer = rand::random();
+        handler
+            .handle_justification(justification.clone().into_unverified(), Some(peer))
+            .expect("correct justification");
+        assert_eq!(
+            handler
+                .handle_justification_request(headers[0].id())
+                .expect("mock backend works"),
+            Some(justification.into_unverified())
+        );
+        assert_eq!(
+            handler
+                .handle_justification_request(headers[1].id())
+                .expect("mock backend works"),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_missing_session_justification() {
+        let (mut handler, mut backend, _keep, _genesis) = setup();
+        let headers = import_branch(&mut backend, 25);
+        for header in &headers {
+            handler
+                .block_imported(header.clone())
+                .expect("importing in order");
+        }
+        // the last block of the first session has number 19
+        assert_eq!(
+            handler.missing_justification().expect("mock backend works"),
+            Some(headers[18].id())
+        );
+        let justification = MockJustification::for_header(headers[18].clone());
+        let peer = rand::random();
+        handler
+            .handle_justification(justification.into_unverified(), Some(peer))
+            .expect("correct justification");
+        assert_eq!(
+            handler.missing_justification().expect("mock backend works"),
+            None
+        );
+    }
+
     #[test]
     fn requests_missing_justifications_without_blocks() {
         let (mut handler, mut backend, _keep, _genesis) = setup();
@@ -18,6 +18,8 @@ pub enum Event {
     HandleStateResponse,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    SendJustificationRequest,
+    HandleJustificationRequest,
 }
 
 use Event::*;
@@ -39,11 +41,13 @@ impl Event {
             HandleStateResponse => "handle_state_response",
             HandleJustificationFromUser => "handle_justification_from_user",
             HandleInternalRequest => "handle_internal_request",
+            SendJustificationRequest => "send_justification_request",
+            HandleJustificationRequest => "handle_justification_request",
         }
     }
 }
 
-const ALL_EVENTS: [Event; 14] = [
+const ALL_EVENTS: [Event; 16] = [
     Broadcast,
     SendRequest,
     SendTo,
@@ -58,9 +62,11 @@ const ALL_EVENTS: [Event; 14] = [
     HandleStateResponse,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    SendJustificationRequest,
+    HandleJustificationRequest,
 ];
 
-const ERRORING_EVENTS: [Event; 11] = [
+const ERRORING_EVENTS: [Event; 13] = [
     Broadcast,
     SendRequest,
     SendTo,
@@ -72,6 +78,8 @@ const ERRORING_EVENTS: [Event; 11] = [
     HandleBlockImported,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    SendJustificationRequest,
+    HandleJustificationRequest,
 ];
 
 pub enum Metrics {
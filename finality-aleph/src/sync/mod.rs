@@ -22,7 +22,9 @@ mod ticker;
 
 pub use data::MAX_MESSAGE_SIZE;
 pub use handler::DatabaseIO;
-pub use select_chain::FavouriteSelectChainProvider;
+pub use select_chain::{
+    FavouriteSelectChainProvider, PinnedSelectChainProvider, SelectChainProvider,
+};
 pub use service::{Service, IO};
 
 const LOG_TARGET: &str = "aleph-block-sync";
@@ -1,7 +1,8 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use futures::channel::{mpsc, oneshot};
 use log::debug;
+use parking_lot::Mutex;
 use sp_consensus::{Error, SelectChain};
 use sp_runtime::traits::Block as BlockT;
 
@@ -9,6 +10,16 @@ use crate::{Block, BlockHash};
 
 const LOG_TARGET: &str = "aleph-select-chain";
 
+/// Provides the `SelectChain` used by the node, together with the favourite block requests
+/// it issues, which are answered by the sync service.
+pub trait SelectChainProvider<B: BlockT> {
+    type SelectChain: SelectChain<B> + 'static;
+
+    fn select_chain(&self) -> Self::SelectChain;
+
+    fn favourite_block_user_requests(self) -> mpsc::UnboundedReceiver<oneshot::Sender<B::Header>>;
+}
+
 #[derive(Clone)]
 pub struct FavouriteSelectChainInner<B: Block> {
     favourite_block_request: mpsc::UnboundedSender<oneshot::Sender<B::Header>>,
@@ -42,6 +53,85 @@ where
     }
 }
 
+impl<B: Block, H> SelectChainProvider<B> for FavouriteSelectChainProvider<B>
+where
+    B: BlockT<Header = H, Hash = BlockHash>,
+    B: Block<Header = H, Hash = BlockHash>,
+    H: Sync + Send + Clone + Debug + 'static,
+{
+    type SelectChain = FavouriteSelectChainInner<B>;
+
+    fn select_chain(&self) -> Self::SelectChain {
+        self.sc.clone()
+    }
+
+    fn favourite_block_user_requests(self) -> mpsc::UnboundedReceiver<oneshot::Sender<H>> {
+        self.rx
+    }
+}
+
+/// Selects the pinned block as the best chain as long as there is one, and the favourite block
+/// otherwise. Allows forcing a specific fork choice, e.g. in tests.
+pub struct PinnedSelectChainProvider<B: Block> {
+    favourite: FavouriteSelectChainProvider<B>,
+    pinned: Arc<Mutex<Option<B::Header>>>,
+}
+
+impl<B: Block, H> PinnedSelectChainProvider<B>
+where
+    B: BlockT<Header = H, Hash = BlockHash>,
+    B: Block<Header = H, Hash = BlockHash>,
+    H: Sync + Send + Clone + Debug + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            favourite: FavouriteSelectChainProvider::new(),
+            pinned: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Makes `header` the best chain until the pin is cleared or replaced.
+    pub fn pin(&self, header: H) {
+        *self.pinned.lock() = Some(header);
+    }
+
+    /// Falls back to selecting the favourite block.
+    pub fn clear(&self) {
+        *self.pinned.lock() = None;
+    }
+}
+
+impl<B: Block, H> Default for PinnedSelectChainProvider<B>
+where
+    B: BlockT<Header = H, Hash = BlockHash>,
+    B: Block<Header = H, Hash = BlockHash>,
+    H: Sync + Send + Clone + Debug + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Block, H> SelectChainProvider<B> for PinnedSelectChainProvider<B>
+where
+    B: BlockT<Header = H, Hash = BlockHash>,
+    B: Block<Header = H, Hash = BlockHash>,
+    H: Sync + Send + Clone + Debug + 'static,
+{
+    type SelectChain = PinnedSelectChain<B>;
+
+    fn select_chain(&self) -> Self::SelectChain {
+        PinnedSelectChain {
+            favourite: self.favourite.sc.clone(),
+            pinned: self.pinned.clone(),
+        }
+    }
+
+    fn favourite_block_user_requests(self) -> mpsc::UnboundedReceiver<oneshot::Sender<H>> {
+        self.favourite.rx
+    }
+}
+
 impl<B: Block, H> Default for FavouriteSelectChainProvider<B>
 where
     B: BlockT<Header = H, Hash = BlockHash>,
@@ -91,3 +181,32 @@ where
         Ok(best)
     }
 }
+
+#[derive(Clone)]
+pub struct PinnedSelectChain<B: Block> {
+    favourite: FavouriteSelectChainInner<B>,
+    pinned: Arc<Mutex<Option<B::Header>>>,
+}
+
+#[async_trait::async_trait]
+impl<B, H> SelectChain<B> for PinnedSelectChain<B>
+where
+    B: BlockT<Header = H, Hash = BlockHash>,
+    B: Block<Header = H, Hash = BlockHash>,
+    H: Sync + Send + Clone + Debug + 'static,
+{
+    async fn leaves(&self) -> Result<Vec<<B as BlockT>::Hash>, Error> {
+        self.favourite.leaves().await
+    }
+
+    async fn best_chain(&self) -> Result<<B as BlockT>::Header, Error> {
+        let pinned = self.pinned.lock().clone();
+        match pinned {
+            Some(best) => {
+                debug!(target: LOG_TARGET, "Best chain pinned to: {:?}", best);
+                Ok(best)
+            }
+            None => self.favourite.best_chain().await,
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashSet, fmt::Display, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    time::{Duration, Instant},
+};
 
 use futures::{
     channel::{mpsc, oneshot},
@@ -38,6 +42,10 @@ use crate::{
 const BROADCAST_COOLDOWN: Duration = Duration::from_millis(600);
 const CHAIN_EXTENSION_COOLDOWN: Duration = Duration::from_millis(300);
 const TICK_PERIOD: Duration = Duration::from_secs(5);
+// At most that many justification requests can wait for an answer at the same time, so that we
+// do not flood our peers with them.
+const MAX_OUTSTANDING_JUSTIFICATION_REQUESTS: usize = 16;
+const JUSTIFICATION_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct IO<B, J, N, CE, CS, F, BI>
 where
@@ -141,6 +149,7 @@ where
     chain_events: CE,
     justifications_from_user: mpsc::UnboundedReceiver<J::Unverified>,
     block_requests_from_user: mpsc::UnboundedReceiver<B::UnverifiedHeader>,
+    outstanding_justification_requests: HashMap<BlockId, Instant>,
     blocks_from_creator: mpsc::UnboundedReceiver<B>,
     major_sync_last_status: bool,
     metrics: Metrics,
@@ -215,6 +224,7 @@ where
                 justifications_from_user,
                 blocks_from_creator,
                 block_requests_from_user,
+                outstanding_justification_requests: HashMap::new(),
                 major_sync_last_status: false,
                 metrics,
                 slo_metrics,
@@ -618,6 +628,9 @@ where
             BlockFinalized(header) => {
                 trace!(target: LOG_TARGET, "Handling a new finalized block.");
                 self.metrics.report_event(Event::HandleBlockFinalized);
+                let finalized_number = header.id().number();
+                self.outstanding_justification_requests
+                    .retain(|id, _| id.number() > finalized_number);
                 self.slo_metrics.report_block_finalized(header.id())
             }
         }
@@ -629,6 +642,63 @@ where
         }
     }
 
+    fn request_missing_justification(&mut self) {
+        match self.handler.missing_justification() {
+            Ok(Some(id)) => self.send_justification_request(id),
+            Ok(None) => (),
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Error looking for a missing justification: {}.", e
+            ),
+        }
+    }
+
+    fn send_justification_request(&mut self, id: BlockId) {
+        self.metrics.report_event(Event::SendJustificationRequest);
+        let now = Instant::now();
+        self.outstanding_justification_requests
+            .retain(|_, sent| now.duration_since(*sent) < JUSTIFICATION_REQUEST_TIMEOUT);
+        if self.outstanding_justification_requests.contains_key(&id) {
+            return;
+        }
+        let outstanding = self.outstanding_justification_requests.len();
+        if outstanding >= MAX_OUTSTANDING_JUSTIFICATION_REQUESTS {
+            debug!(
+                target: LOG_TARGET,
+                "Too many outstanding justification requests, skipping {:?}.", id
+            );
+            return;
+        }
+        trace!(target: LOG_TARGET, "Requesting justification of {:?}.", id);
+        let data = NetworkData::JustificationRequest(id.clone());
+        if let Err(e) = self.network.send_to_random(data, HashSet::new()) {
+            self.metrics
+                .report_event_error(Event::SendJustificationRequest);
+            warn!(target: LOG_TARGET, "Error sending justification request: {}.", e);
+            return;
+        }
+        self.outstanding_justification_requests.insert(id, now);
+    }
+
+    fn handle_justification_request(&mut self, id: BlockId, peer: N::PeerId) {
+        self.metrics.report_event(Event::HandleJustificationRequest);
+        match self.handler.handle_justification_request(id) {
+            Ok(Some(justification)) => self.send_to(
+                NetworkData::RequestResponse(vec![ResponseItem::Justification(justification)]),
+                peer,
+            ),
+            Ok(None) => (),
+            Err(e) => {
+                self.metrics
+                    .report_event_error(Event::HandleJustificationRequest);
+                warn!(
+                    target: LOG_TARGET,
+                    "Error handling justification request from {:?}: {}.", peer, e
+                );
+            }
+        }
+    }
+
     fn handle_internal_request(&mut self, header: B::UnverifiedHeader) {
         let id = header.id();
         trace!(
@@ -710,6 +780,7 @@ where
             }
             RequestResponse(response_items) => self.handle_request_response(response_items, peer),
             ChainExtensionRequest(state) => self.handle_chain_extension_request(state, peer),
+            JustificationRequest(id) => self.handle_justification_request(id, peer),
         }
     }
 
@@ -768,7 +839,10 @@ where
 
                 _ = self.broadcast_ticker.wait_and_tick() => self.broadcast(),
 
-                force = self.chain_extension_ticker.wait_and_tick() => self.request_chain_extension(force),
+                force = self.chain_extension_ticker.wait_and_tick() => {
+                    self.request_chain_extension(force);
+                    self.request_missing_justification();
+                },
 
                 maybe_event = self.chain_events.next() => {
                     let chain_event = maybe_event.map_err(Error::ChainEvent)?;
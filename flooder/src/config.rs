@@ -1,6 +1,6 @@
 use std::{fs, path::PathBuf};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Debug, Parser)]
 #[clap(version = "1.0")]
@@ -41,11 +41,52 @@ pub struct Config {
     #[clap(long)]
     pub wait_for_ready: bool,
 
+    /// Addresses of the accounts that receive transfers, in a round-robin manner.
+    /// By default, all transfers are sent to the account given by `--phrase` or `--seed`
+    #[clap(long, value_delimiter = ',')]
+    pub dests: Vec<String>,
+
+    /// Number of deterministically derived accounts that receive transfers, in a round-robin manner
+    #[clap(long, conflicts_with_all = &["dests"])]
+    pub dests_count: Option<u32>,
+
     /// Flooder will pause sending transactions to the node, if there are more than
     /// `pool_limit` transactions in the tx pool of the node. Should
     /// be smaller than `--pool-limit` parameter of nodes.
     #[clap(long, default_value = "6144")]
     pub pool_limit: u64,
+
+    /// Kind of transactions to flood the nodes with. By default, balance transfers are sent
+    #[clap(subcommand)]
+    pub mode: Option<Mode>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Mode {
+    /// Flood the nodes with calls to a contract
+    Contract {
+        /// Address of the contract to call
+        #[clap(long)]
+        address: String,
+
+        /// Selector of the contract message to call, hex encoded
+        #[clap(long)]
+        selector: String,
+
+        /// SCALE encoded arguments of the contract message, hex encoded
+        #[clap(long, default_value = "")]
+        args: String,
+
+        /// Reference time part of the gas limit of every call.
+        /// By default, 1% of the maximal block weight
+        #[clap(long)]
+        gas_limit_ref_time: Option<u64>,
+
+        /// Proof size part of the gas limit of every call.
+        /// By default, 1% of the maximal block proof size
+        #[clap(long)]
+        gas_limit_proof_size: Option<u64>,
+    },
 }
 
 pub fn read_phrase(phrase: String) -> String {
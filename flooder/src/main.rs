@@ -1,25 +1,22 @@
-use core::cmp::min;
-
 use aleph_client::{
-    pallets::{
-        author::AuthorRpc, balances::BalanceUserApi, system::SystemApi, timestamp::TimestampApi,
-    },
+    api,
+    load::{RateScheduler, Schedule},
+    pallets::{balances::BalanceUserApi, system::SystemApi, timestamp::TimestampApi},
     raw_keypair_from_string,
+    sp_weights::weight_v2::Weight,
     utility::BlocksApi,
     AccountId, Balance, KeyPair, Nonce, SignedConnection, SignedConnectionApi,
-    SignedConnectionApiExt, TxStatus, TOKEN,
+    SignedConnectionApiExt, Ss58Codec, TxStatus, TOKEN,
 };
 use clap::Parser;
-use config::Config;
-use futures::future::join_all;
+use config::{Config, Mode};
 use log::{debug, info};
 use subxt::{
-    config::{extrinsic_params::BaseExtrinsicParamsBuilder, substrate::Era},
     ext::sp_core::{sr25519, Pair},
     tx::TxPayload,
     utils::{MultiAddress, Static},
 };
-use tokio::time::{interval, Duration, Instant};
+use tokio::time::Duration;
 
 mod config;
 
@@ -29,130 +26,26 @@ fn transfer_keep_alive(dest: AccountId, amount: Balance) -> impl TxPayload + Sen
         .transfer_keep_alive(MultiAddress::Id(Static(dest)), amount)
 }
 
+fn contract_call(
+    dest: AccountId,
+    gas_limit: Weight,
+    data: Vec<u8>,
+) -> impl TxPayload + Send + Sync {
+    aleph_client::api::tx().contracts().call(
+        MultiAddress::Id(Static(dest)),
+        0,
+        gas_limit,
+        None,
+        data,
+    )
+}
+
 fn transfer_all(dest: AccountId, keep_alive: bool) -> impl TxPayload + Send + Sync {
     aleph_client::api::tx()
         .balances()
         .transfer_all(MultiAddress::Id(Static(dest)), keep_alive)
 }
 
-struct Schedule {
-    pub intervals: u64,
-    pub interval_duration: Duration,
-    pub transactions_in_interval: u64,
-}
-
-async fn flood(
-    connections: Vec<SignedConnection>,
-    dest: AccountId,
-    transfer_amount: Balance,
-    schedule: Schedule,
-    status: TxStatus,
-    pool_limit: u64,
-) -> anyhow::Result<Vec<(SignedConnection, Nonce)>> {
-    let start = Instant::now();
-    let total_duration = schedule.interval_duration * (schedule.intervals as u32);
-
-    let start_finalized_hash = connections[0].get_finalized_block_hash().await?;
-    let start_finalized_number = connections[0]
-        .get_block_number(start_finalized_hash)
-        .await?
-        .unwrap() as u64;
-
-    // Set mortality roughly to flooding length
-    let params = BaseExtrinsicParamsBuilder::default().era(
-        Era::mortal(total_duration.as_secs() + 30, start_finalized_number),
-        start_finalized_hash,
-    );
-
-    let n_connections = connections.len();
-    let mut start_nonces = vec![0; n_connections];
-    for (conn_id, conn) in connections.iter().enumerate() {
-        start_nonces[conn_id] = conn.account_nonce(conn.account_id()).await?;
-    }
-
-    let split_per_connections = move |total, conn_id| {
-        let mut part = total / n_connections as u64;
-        if conn_id < (total as usize) % n_connections {
-            part += 1;
-        }
-        part
-    };
-
-    let handles = connections
-        .into_iter()
-        .enumerate()
-        .map(|(conn_id, conn)| {
-            let dest = dest.clone();
-            let mut nonce = start_nonces[conn_id];
-            tokio::spawn(async move {
-                let mut interval = interval(schedule.interval_duration);
-                let mut overdue_transactions = 0;
-                for i in 0..schedule.intervals {
-                    interval.tick().await;
-                    overdue_transactions += split_per_connections(schedule.transactions_in_interval, conn_id);
-
-                    let pending_in_pool = conn.pending_extrinsics_len().await?;
-                    let transactions_to_pool_limit = pool_limit.saturating_sub(pending_in_pool);
-                    let my_limit_part = split_per_connections(transactions_to_pool_limit, conn_id);
-
-                    let my_transactions = min(
-                        overdue_transactions,
-                        my_limit_part,
-                    );
-                    overdue_transactions -= my_transactions;
-                    debug!(
-                        "Interval {}, sending {my_transactions} transaction from connection {conn_id}. \
-                         In the pool, there are pending {pending_in_pool} transactions. \
-                         Overdue transactions: {overdue_transactions}.",
-                        i + 1
-                    );
-
-                    for _ in 0..my_transactions {
-                        conn.sign_with_params(
-                            transfer_keep_alive(dest.clone(), transfer_amount),
-                            params,
-                            nonce,
-                        )?
-                        .submit(status)
-                        .await?;
-                        nonce += 1;
-                        if Instant::now().saturating_duration_since(start) > total_duration {
-                            return anyhow::Ok((nonce, conn));
-                        }
-                    }
-                }
-                anyhow::Ok((nonce, conn))
-            })
-        });
-
-    let mut total_submitted = 0;
-    let mut last_error = None;
-    let mut res = vec![];
-    for (conn_id, result) in join_all(handles).await.into_iter().enumerate() {
-        match result? {
-            Ok((nonce, conn)) => {
-                total_submitted += nonce - start_nonces[conn_id];
-                res.push((conn, nonce));
-            }
-            Err(e) => {
-                info!("Sender subtask finished with an error: {e:?}");
-                last_error = Some(e);
-            }
-        }
-    }
-
-    let target_transactions = schedule.intervals * schedule.transactions_in_interval;
-    info!(
-        "Submitted {total_submitted} txns out of {target_transactions} that should be sent ({:.2}%)",
-        total_submitted as f64 / target_transactions as f64 * 100.0
-    );
-
-    match last_error {
-        Some(e) => Err(e),
-        None => Ok(res),
-    }
-}
-
 async fn return_balances(
     connections_and_nonces: &[(SignedConnection, Nonce)],
     dest: AccountId,
@@ -197,7 +90,7 @@ async fn initialize_n_accounts<F: Fn(u32) -> String>(
     }
 
     let nonce = main_connection
-        .account_nonce(main_connection.account_id())
+        .account_next_nonce(main_connection.account_id())
         .await?;
     for (i, conn) in connections.iter().enumerate() {
         let status = if i + 1 == n as usize {
@@ -219,6 +112,37 @@ async fn initialize_n_accounts<F: Fn(u32) -> String>(
     Ok(connections)
 }
 
+fn destination_accounts(
+    config: &Config,
+    main_account: &AccountId,
+) -> anyhow::Result<Vec<AccountId>> {
+    const DESTS_SEED_PREFIX: &str = "//dest";
+    if let Some(count) = config.dests_count {
+        if count == 0 {
+            return Err(anyhow::anyhow!("--dests-count should be positive"));
+        }
+        return Ok((0..count)
+            .map(|i| {
+                KeyPair::new(raw_keypair_from_string(&format!("{DESTS_SEED_PREFIX}{i}")))
+                    .account_id()
+                    .clone()
+            })
+            .collect());
+    }
+    if config.dests.is_empty() {
+        return Ok(vec![main_account.clone()]);
+    }
+
+    config
+        .dests
+        .iter()
+        .map(|address| {
+            AccountId::from_ss58check(address)
+                .map_err(|e| anyhow::anyhow!("Invalid destination address {address}: {e:?}"))
+        })
+        .collect()
+}
+
 /// Only a rough estimation, for the worst case where blocks are 75% full
 /// (it is a maximum for non-operational transactions).
 /// See https://github.com/Cardinal-Cryptography/aleph-node/blob/b6ac239809667b5c6a113c4e3c9ef9216c5b97eb/bin/runtime/src/lib.rs#L267
@@ -355,15 +279,50 @@ async fn main() -> anyhow::Result<()> {
 
     let best_block_pre_flood = main_connection.get_best_block().await.unwrap().unwrap();
 
-    let connections_and_nonces = flood(
-        connections,
-        main_connection.account_id().clone(),
-        1,
-        schedule,
-        tx_status,
-        config.pool_limit,
-    )
-    .await?;
+    let scheduler = RateScheduler::new(tx_status);
+    let connections_and_nonces = match &config.mode {
+        None => {
+            let dests = destination_accounts(&config, main_connection.account_id())?;
+            scheduler
+                .run(
+                    connections,
+                    move |sent| transfer_keep_alive(dests[sent as usize % dests.len()].clone(), 1),
+                    schedule,
+                    config.pool_limit,
+                )
+                .await?
+        }
+        Some(Mode::Contract {
+            address,
+            selector,
+            args,
+            gas_limit_ref_time,
+            gas_limit_proof_size,
+        }) => {
+            let dest = AccountId::from_ss58check(address)
+                .map_err(|e| anyhow::anyhow!("Invalid contract address {address}: {e:?}"))?;
+            let mut data = hex::decode(selector.trim_start_matches("0x"))?;
+            data.extend(hex::decode(args.trim_start_matches("0x"))?);
+            let max_block = main_connection
+                .connection
+                .as_client()
+                .constants()
+                .at(&api::constants().system().block_weights())?
+                .max_block;
+            let gas_limit = Weight {
+                ref_time: gas_limit_ref_time.unwrap_or(max_block.ref_time / 100),
+                proof_size: gas_limit_proof_size.unwrap_or(max_block.proof_size / 100),
+            };
+            scheduler
+                .run(
+                    connections,
+                    move |_| contract_call(dest.clone(), gas_limit.clone(), data.clone()),
+                    schedule,
+                    config.pool_limit,
+                )
+                .await?
+        }
+    };
 
     if !config.skip_initialization {
         return_balances(
@@ -2,13 +2,15 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use primitives::{
-    crypto::SignatureSet, AccountId, ApiError, AuthorityId, AuthoritySignature, Balance, Perbill,
-    Score, SessionAuthorityData, SessionCommittee, SessionIndex, SessionValidatorError, Version,
+    crypto::SignatureSet, AccountId, ApiError, AuthorityId, AuthoritySignature, Balance,
+    BlockCount, Perbill, Score, SessionAuthorityData, SessionCommittee, SessionCount, SessionIndex,
+    SessionRangeError, SessionValidatorError, Version, VersionChange, VersionedSessionCommittee,
 };
 pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(4)]
     pub trait AlephSessionApi {
         fn next_session_authorities() -> Result<Vec<AuthorityId>, ApiError>;
         fn authorities() -> Vec<AuthorityId>;
@@ -18,6 +20,9 @@ sp_api::decl_runtime_apis! {
         fn millisecs_per_block() -> u64;
         fn finality_version() -> Version;
         fn next_session_finality_version() -> Version;
+        /// Returns the scheduled finality version change, if it has not taken effect yet.
+        #[api_version(3)]
+        fn scheduled_finality_version_change() -> Option<VersionChange>;
         /// Predict finality committee and block producers for the given session. `session` must be
         /// within the current era (current, in the staking context).
         ///
@@ -28,6 +33,26 @@ sp_api::decl_runtime_apis! {
         fn predict_session_committee(
             session: SessionIndex
         ) -> Result<SessionCommittee<AccountId>, SessionValidatorError>;
+        /// Predict finality committee and block producers for all sessions from `from` to `to`
+        /// (inclusive). The whole range must be within the current era, as in
+        /// `predict_session_committee`.
+        #[api_version(2)]
+        fn predict_session_committee_range(
+            from: SessionIndex,
+            to: SessionIndex,
+        ) -> Result<Vec<(SessionIndex, SessionCommittee<AccountId>)>, SessionRangeError>;
+        /// Same as `predict_session_committee`, but the committee is prefixed with its version.
+        #[api_version(4)]
+        fn predict_session_committee_versioned(
+            session: SessionIndex
+        ) -> Result<VersionedSessionCommittee<AccountId>, SessionValidatorError>;
+        /// Same as `predict_session_committee_range`, but the committees are prefixed with their
+        /// version.
+        #[api_version(4)]
+        fn predict_session_committee_range_versioned(
+            from: SessionIndex,
+            to: SessionIndex,
+        ) -> Result<Vec<(SessionIndex, VersionedSessionCommittee<AccountId>)>, SessionRangeError>;
         fn next_session_aura_authorities() -> Vec<(AccountId, AuraId)>;
         /// Returns owner (`AccountId`) corresponding to an AuthorityId (in some contexts referenced
         /// also as `aleph_key` - consensus engine's part of session keys) in the current session
@@ -40,4 +65,18 @@ sp_api::decl_runtime_apis! {
         /// Submits score for a nonce in a session of performance of finality committee members.
         fn submit_abft_score(score: Score, signature: SignatureSet<AuthoritySignature>) -> Option<()>;
     }
+
+    pub trait CommitteeManagementApi {
+        /// Returns the number of sessions in which `account` underperformed as a block producer,
+        /// counted towards the ban threshold.
+        fn underperformance(account: AccountId) -> SessionCount;
+        /// Returns the number of blocks produced by `account` in the current session.
+        fn blocks_produced_this_session(account: AccountId) -> BlockCount;
+    }
+
+    pub trait OperationsApi {
+        /// Returns the difference between the expected and the current consumers counter of
+        /// `account`, i.e. how many times (and in which direction) the counter should be fixed.
+        fn consumers_diff(account: AccountId) -> i32;
+    }
 }
@@ -39,7 +39,7 @@ pub mod pallet {
         pallet_prelude::{BlockNumberFor, OriginFor},
     };
     use pallet_session::SessionManager;
-    use primitives::{Score, ScoreNonce, SessionInfoProvider, TotalIssuanceProvider};
+    use primitives::{Score, ScoreError, ScoreNonce, SessionInfoProvider, TotalIssuanceProvider};
     use sp_runtime::traits::{Hash, ValidateUnsigned};
     use sp_std::collections::btree_map::BTreeMap;
     #[cfg(feature = "std")]
@@ -69,6 +69,24 @@ pub mod pallet {
         ScheduleFinalityVersionChange(VersionChange),
         FinalityVersionChange(VersionChange),
         InflationParametersChange(Balance, u64),
+        CancelFinalityVersionChange(VersionChange),
+        /// Finality committee of the `session` has been rotated in, and consists of `count`
+        /// authorities.
+        AuthoritiesRotated {
+            session: SessionIndex,
+            count: u32,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Emergency finalizer cannot be a member of the current or the next finality committee.
+        EmergencyFinalizerInCommittee,
+        /// There is no scheduled finality version change to cancel.
+        NoScheduledFinalityVersionChange,
+        /// The scheduled finality version change starts in the next session, which nodes might
+        /// have already prepared for, so it cannot be cancelled anymore.
+        FinalityVersionChangeTooLateToCancel,
     }
 
     #[pallet::pallet]
@@ -203,8 +221,17 @@ pub mod pallet {
             }
         }
 
-        pub(crate) fn set_next_emergency_finalizer(emergency_finalizer: T::AuthorityId) {
+        pub(crate) fn set_next_emergency_finalizer(
+            emergency_finalizer: T::AuthorityId,
+        ) -> Result<(), Error<T>> {
+            if <Authorities<T>>::get().contains(&emergency_finalizer)
+                || <NextAuthorities<T>>::get().contains(&emergency_finalizer)
+            {
+                return Err(Error::<T>::EmergencyFinalizerInCommittee);
+            }
+
             <NextEmergencyFinalizer<T>>::put(emergency_finalizer);
+            Ok(())
         }
 
         pub(crate) fn current_session() -> u32 {
@@ -213,7 +240,7 @@ pub mod pallet {
 
         // If a scheduled future version change is rescheduled to a different session,
         // it is possible to reschedule it with the same version as initially.
-        // To cancel a future version change, reschedule it with the current version.
+        // To cancel a future version change, use `cancel_scheduled_finality_version_change`.
         // If a scheduled version change has moved into the past, `SessionManager` records it
         // as the current version.
         pub(crate) fn do_schedule_finality_version_change(
@@ -237,6 +264,18 @@ pub mod pallet {
             Ok(())
         }
 
+        pub(crate) fn do_cancel_finality_version_change() -> Result<VersionChange, Error<T>> {
+            let version_change = <FinalityScheduledVersionChange<T>>::get()
+                .ok_or(Error::<T>::NoScheduledFinalityVersionChange)?;
+            // The change is already reported as the next session version by
+            // `next_session_finality_version`, in the last session before it.
+            if version_change.session < Self::current_session() + 2 {
+                return Err(Error::<T>::FinalityVersionChangeTooLateToCancel);
+            }
+            <FinalityScheduledVersionChange<T>>::kill();
+            Ok(version_change)
+        }
+
         pub fn next_session_finality_version() -> Version {
             let next_session = Self::current_session() + 1;
             let scheduled_version_change = Self::finality_version_change();
@@ -250,6 +289,13 @@ pub mod pallet {
             Self::finality_version()
         }
 
+        /// Returns the scheduled finality version change, unless its session has already started,
+        /// in which case it is already reflected in the current finality version.
+        pub fn scheduled_finality_version_change() -> Option<VersionChange> {
+            Self::finality_version_change()
+                .filter(|version_change| version_change.session > Self::current_session())
+        }
+
         pub fn check_horizon_upper_bound(
             new_horizon: u64,
             current_horizon: u64,
@@ -333,6 +379,13 @@ pub mod pallet {
             score: &Score,
             signature: &SignatureSet<Signature<T>>,
         ) -> Result<(), TransactionValidityError> {
+            let committee_size = <Authorities<T>>::decode_len().unwrap_or_default() as u32;
+            score
+                .validate(Self::current_session(), committee_size)
+                .map_err(|e| match e {
+                    ScoreError::FutureSession { .. } => InvalidTransaction::Future,
+                    ScoreError::TooManyPoints { .. } => InvalidTransaction::Call,
+                })?;
             Self::check_session_id(score.session_id)?;
             Self::check_nonce(score.nonce)?;
             Self::verify_score(score, signature)?;
@@ -374,7 +427,7 @@ pub mod pallet {
             emergency_finalizer: T::AuthorityId,
         ) -> DispatchResult {
             ensure_root(origin)?;
-            Self::set_next_emergency_finalizer(emergency_finalizer.clone());
+            Self::set_next_emergency_finalizer(emergency_finalizer.clone())?;
             Self::deposit_event(Event::ChangeEmergencyFinalizer(emergency_finalizer));
             Ok(())
         }
@@ -383,8 +436,8 @@ pub mod pallet {
         /// version is already set, it is replaced with the provided one.
         /// Any rescheduling of a future version change needs to occur at least 2 sessions in
         /// advance of the provided session of the version change.
-        /// In order to cancel a scheduled version change, a new version change should be scheduled
-        /// with the same version as the current one.
+        /// In order to cancel a scheduled version change, use
+        /// `cancel_scheduled_finality_version_change`.
         #[pallet::call_index(1)]
         #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
         pub fn schedule_finality_version_change(
@@ -407,6 +460,19 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Cancels the scheduled finality version change. Fails if there is no such change, or if
+        /// it starts in the next session.
+        #[pallet::call_index(4)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn cancel_scheduled_finality_version_change(origin: OriginFor<T>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let version_change = Self::do_cancel_finality_version_change()?;
+
+            Self::deposit_event(Event::CancelFinalityVersionChange(version_change));
+            Ok(())
+        }
+
         /// Sets the values of inflation parameters.
         #[pallet::call_index(2)]
         #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
@@ -505,6 +571,10 @@ pub mod pallet {
             Self::update_emergency_finalizer();
             if changed {
                 Self::update_authorities(queued_validators.collect());
+                Self::deposit_event(Event::AuthoritiesRotated {
+                    session: Self::current_session(),
+                    count: <Authorities<T>>::decode_len().unwrap_or_default() as u32,
+                });
             }
         }
 
@@ -1,7 +1,7 @@
-use frame_support::{storage_alias, traits::OneSessionHandler};
+use frame_support::{assert_noop, assert_ok, storage_alias, traits::OneSessionHandler};
 use primitives::VersionChange;
 
-use crate::{mock::*, NextFinalityCommittee};
+use crate::{mock::*, Error, Event, NextFinalityCommittee};
 
 #[storage_alias]
 type SessionForValidatorsChange = StorageValue<Aleph, u32>;
@@ -81,6 +81,13 @@ fn test_session_rotation() {
         Aleph::on_new_session(true, new_validators, queued_validators);
         assert_eq!(Aleph::authorities(), to_authorities(&[1, 2]));
         assert_eq!(Aleph::next_authorities(), to_authorities(&[5, 6]));
+        System::assert_last_event(
+            Event::<Test>::AuthoritiesRotated {
+                session: Session::current_index(),
+                count: 2,
+            }
+            .into(),
+        );
     })
 }
 
@@ -106,14 +113,14 @@ fn test_emergency_signer() {
 
         run_session(1);
 
-        Aleph::set_next_emergency_finalizer(to_authority(&21));
+        assert_ok!(Aleph::set_next_emergency_finalizer(to_authority(&21)));
 
         assert_eq!(Aleph::emergency_finalizer(), None);
         assert_eq!(Aleph::queued_emergency_finalizer(), None);
 
         run_session(2);
 
-        Aleph::set_next_emergency_finalizer(to_authority(&37));
+        assert_ok!(Aleph::set_next_emergency_finalizer(to_authority(&37)));
 
         assert_eq!(Aleph::emergency_finalizer(), None);
         assert_eq!(Aleph::queued_emergency_finalizer(), Some(to_authority(&21)));
@@ -125,6 +132,31 @@ fn test_emergency_signer() {
     })
 }
 
+#[test]
+fn emergency_finalizer_cannot_be_in_committee() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+
+        run_session(1);
+
+        NextFinalityCommittee::<Test>::put(vec![5, 6]);
+        let new_validators = new_session_validators(&[1, 2]);
+        let queued_validators = new_session_validators(&[5, 6]);
+        Aleph::on_new_session(true, new_validators, queued_validators);
+
+        for key in [1, 5] {
+            assert_noop!(
+                Aleph::set_emergency_finalizer(RuntimeOrigin::root(), to_authority(&key)),
+                Error::<Test>::EmergencyFinalizerInCommittee
+            );
+        }
+        assert_ok!(Aleph::set_emergency_finalizer(
+            RuntimeOrigin::root(),
+            to_authority(&7)
+        ));
+    })
+}
+
 #[test]
 fn test_finality_version_scheduling() {
     new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
@@ -143,6 +175,10 @@ fn test_finality_version_scheduling() {
 
         let scheduled_version_change = Aleph::finality_version_change();
         assert_eq!(scheduled_version_change, Some(version_to_schedule.clone()));
+        assert_eq!(
+            Aleph::scheduled_finality_version_change(),
+            Some(version_to_schedule.clone())
+        );
 
         run_session(4);
 
@@ -151,6 +187,7 @@ fn test_finality_version_scheduling() {
 
         let scheduled_version_change = Aleph::finality_version_change();
         assert_eq!(scheduled_version_change, None);
+        assert_eq!(Aleph::scheduled_finality_version_change(), None);
 
         let version_to_schedule = VersionChange {
             version_incoming: 1,
@@ -161,3 +198,109 @@ fn test_finality_version_scheduling() {
         assert!(scheduling_result.is_err());
     })
 }
+
+#[test]
+fn test_finality_version_change_cancelling() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+
+        run_session(1);
+
+        assert!(Aleph::do_cancel_finality_version_change().is_err());
+
+        let version_to_schedule = VersionChange {
+            version_incoming: 1,
+            session: 4,
+        };
+        let scheduling_result =
+            Aleph::do_schedule_finality_version_change(version_to_schedule.clone());
+        assert_eq!(scheduling_result, Ok(()));
+
+        assert_eq!(
+            Aleph::do_cancel_finality_version_change().ok(),
+            Some(version_to_schedule)
+        );
+        assert_eq!(Aleph::finality_version_change(), None);
+
+        let current_version = Aleph::finality_version();
+        run_session(4);
+        assert_eq!(Aleph::finality_version(), current_version);
+    })
+}
+
+#[test]
+fn test_cancel_scheduled_finality_version_change_call() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+
+        run_session(1);
+
+        assert_noop!(
+            Aleph::cancel_scheduled_finality_version_change(RuntimeOrigin::root()),
+            Error::<Test>::NoScheduledFinalityVersionChange
+        );
+
+        let version_to_schedule = VersionChange {
+            version_incoming: 1,
+            session: 4,
+        };
+        assert_ok!(Aleph::schedule_finality_version_change(
+            RuntimeOrigin::root(),
+            version_to_schedule.version_incoming,
+            version_to_schedule.session
+        ));
+
+        assert_noop!(
+            Aleph::cancel_scheduled_finality_version_change(RuntimeOrigin::signed(1)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(Aleph::cancel_scheduled_finality_version_change(
+            RuntimeOrigin::root()
+        ));
+        System::assert_last_event(
+            Event::<Test>::CancelFinalityVersionChange(version_to_schedule).into(),
+        );
+        assert_eq!(Aleph::finality_version_change(), None);
+    })
+}
+
+#[test]
+fn test_finality_version_change_cannot_be_cancelled_in_last_session_before_it() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+
+        run_session(1);
+
+        let version_to_schedule = VersionChange {
+            version_incoming: 1,
+            session: 4,
+        };
+        assert_ok!(Aleph::schedule_finality_version_change(
+            RuntimeOrigin::root(),
+            version_to_schedule.version_incoming,
+            version_to_schedule.session
+        ));
+
+        run_session(3);
+        assert_eq!(
+            Aleph::next_session_finality_version(),
+            version_to_schedule.version_incoming
+        );
+
+        assert_noop!(
+            Aleph::cancel_scheduled_finality_version_change(RuntimeOrigin::root()),
+            Error::<Test>::FinalityVersionChangeTooLateToCancel
+        );
+        assert_eq!(
+            Aleph::finality_version_change(),
+            Some(version_to_schedule.clone())
+        );
+
+        run_session(4);
+        assert_eq!(
+            Aleph::finality_version(),
+            version_to_schedule.version_incoming
+        );
+    })
+}
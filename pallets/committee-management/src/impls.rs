@@ -2,14 +2,17 @@ use frame_support::pallet_prelude::Get;
 use log::info;
 use parity_scale_codec::Encode;
 use primitives::{
+    performance::{expected_blocks_per_validator, session_reward_ratio},
     AbftScoresProvider, BanHandler, BanInfo, BanReason, BannedValidators, CommitteeSeats,
-    EraValidators, SessionCommittee, SessionValidatorError, SessionValidators, ValidatorProvider,
+    EraValidators, SessionCommittee, SessionRangeError, SessionValidatorError, SessionValidators,
+    ValidatorProvider,
 };
 use rand::{seq::SliceRandom, SeedableRng};
 use rand_pcg::Pcg32;
 use sp_runtime::{Perbill, Perquintill};
 use sp_staking::{EraIndex, SessionIndex};
 use sp_std::{
+    cmp::max,
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
     vec,
     vec::Vec,
@@ -17,7 +20,7 @@ use sp_std::{
 
 use crate::{
     pallet::{
-        Banned, Config, CurrentAndNextSessionValidatorsStorage, Event, Pallet,
+        Banned, Config, CurrentAndNextSessionValidatorsStorage, Event, FirstSeenSession, Pallet,
         SessionValidatorBlockCount, UnderperformedFinalizerSessionCount,
         UnderperformedValidatorSessionCount, ValidatorEraTotalReward,
     },
@@ -138,17 +141,10 @@ fn calculate_adjusted_session_points(
 ) -> u32 {
     let performance =
         Perquintill::from_rational(blocks_created as u64, blocks_to_produce_per_session as u64);
+    let session_points =
+        Perquintill::from_rational(1, sessions_per_era as u64) * total_possible_reward as u64;
 
-    // when produced more than `lenient_threshold`% expected blocks, get 100% possible reward for session
-    if performance >= lenient_threshold {
-        return (Perquintill::from_rational(1, sessions_per_era as u64)
-            * total_possible_reward as u64) as u32;
-    }
-
-    (Perquintill::from_rational(
-        blocks_created as u64,
-        (blocks_to_produce_per_session * sessions_per_era) as u64,
-    ) * total_possible_reward as u64) as u32
+    (session_reward_ratio(performance, lenient_threshold) * session_points) as u32
 }
 
 pub fn compute_validator_scaled_total_rewards<V>(
@@ -231,8 +227,10 @@ impl<T: Config> Pallet<T> {
     }
 
     pub(crate) fn blocks_to_produce_per_session() -> u32 {
-        T::SessionPeriod::get()
-            .saturating_div(T::ValidatorProvider::current_era_committee_size().size())
+        expected_blocks_per_validator(
+            T::SessionPeriod::get(),
+            T::ValidatorProvider::current_era_committee_size().size(),
+        )
     }
 
     pub fn adjust_rewards_for_session() {
@@ -336,6 +334,7 @@ impl<T: Config> Pallet<T> {
         let committee = Self::select_committee(&era_validators, committee_seats, current_session);
 
         if let Some(c) = &committee {
+            Self::note_first_seen(current_session, &c.producers);
             Self::store_session_validators(
                 &c.producers,
                 &c.finalizers,
@@ -347,6 +346,36 @@ impl<T: Config> Pallet<T> {
         committee
     }
 
+    /// Records the first session in the committee for producers that were not seen before.
+    fn note_first_seen(session: SessionIndex, producers: &[T::AccountId]) {
+        for validator in producers {
+            if !FirstSeenSession::<T>::contains_key(validator) {
+                FirstSeenSession::<T>::insert(validator, session);
+            }
+        }
+    }
+
+    /// Forgets validators that are no longer part of the current era. Called once per era, so the
+    /// map holds at most the validators of the previous and the current era.
+    pub(crate) fn prune_first_seen() {
+        let era_validators = T::ValidatorProvider::current_era_validators();
+        let era_validators: BTreeSet<_> = era_validators.all().collect();
+        let gone: Vec<_> = FirstSeenSession::<T>::iter_keys()
+            .filter(|validator| !era_validators.contains(&validator))
+            .collect();
+        gone.iter().for_each(FirstSeenSession::<T>::remove);
+    }
+
+    fn in_grace_period(
+        thresholds: &ProductionBanConfigStruct,
+        validator: &T::AccountId,
+        session: SessionIndex,
+    ) -> bool {
+        FirstSeenSession::<T>::get(validator).map_or(false, |first_seen| {
+            first_seen.saturating_add(thresholds.grace_sessions) > session
+        })
+    }
+
     pub(crate) fn calculate_underperforming_finalizers(session_id: SessionIndex) {
         let CurrentAndNextSessionValidators {
             current: SessionValidators { finalizers, .. },
@@ -374,14 +403,15 @@ impl<T: Config> Pallet<T> {
                         *count
                     });
                 if counter >= underperformed_session_count_threshold {
-                    // In future, additionally ban underperforming validator
-                    Self::deposit_event(Event::ValidatorUnderperforming(validator.clone()));
+                    let reason = BanReason::InsufficientFinalityParticipation(counter);
+                    Self::ban_validator(validator, reason);
+                    UnderperformedFinalizerSessionCount::<T>::remove(validator);
                 }
             }
         }
     }
 
-    pub(crate) fn calculate_underperforming_validators() {
+    pub(crate) fn calculate_underperforming_validators(session: SessionIndex) {
         let thresholds = Self::production_ban_config();
         let CurrentAndNextSessionValidators {
             current: SessionValidators { producers, .. },
@@ -396,7 +426,7 @@ impl<T: Config> Pallet<T> {
                 }
                 Err(_) => true,
             };
-            if underperformance {
+            if underperformance && !Self::in_grace_period(&thresholds, &validator, session) {
                 Self::mark_validator_underperformance(&thresholds, &validator);
             }
         }
@@ -410,10 +440,16 @@ impl<T: Config> Pallet<T> {
             *count += 1;
             *count
         });
-        if counter >= thresholds.underperformed_session_count_threshold {
+        let threshold = thresholds.underperformed_session_count_threshold;
+        if counter >= threshold {
             let reason = BanReason::InsufficientUptime(counter);
             Self::ban_validator(validator, reason);
             UnderperformedValidatorSessionCount::<T>::remove(validator);
+        } else if counter == threshold - 1 {
+            Self::deposit_event(Event::ValidatorApproachingBan {
+                who: validator.clone(),
+                sessions_remaining: threshold - counter,
+            });
         }
     }
 
@@ -477,14 +513,8 @@ impl<T: Config> Pallet<T> {
     pub fn predict_session_committee_for_session(
         session: SessionIndex,
     ) -> Result<SessionCommittee<T::AccountId>, SessionValidatorError> {
-        let ce = T::EraInfoProvider::current_era()
-            .ok_or_else(|| SessionValidatorError::Other("No current era".encode()))?;
-
-        let current_starting_index =
-            T::EraInfoProvider::era_start_session_index(ce).ok_or_else(|| {
-                SessionValidatorError::Other("No known starting session for current era".encode())
-            })?;
-        let planned_era_end = current_starting_index + T::EraInfoProvider::sessions_per_era() - 1;
+        let (current_starting_index, planned_era_end) =
+            Self::current_era_sessions().map_err(|e| SessionValidatorError::Other(e.encode()))?;
 
         if session < current_starting_index || session > planned_era_end {
             return Err(SessionValidatorError::SessionNotWithinRange {
@@ -498,6 +528,55 @@ impl<T: Config> Pallet<T> {
         Self::select_committee(&era_validators, committee_seats, session)
             .ok_or_else(|| SessionValidatorError::Other("Internal error".encode()))
     }
+
+    /// Predict finality committee and block producers for all sessions from `from` to `to`
+    /// (inclusive). The whole range must be within the current era, see
+    /// [`Self::predict_session_committee_for_session`].
+    pub fn predict_session_committee_for_range(
+        from: SessionIndex,
+        to: SessionIndex,
+    ) -> Result<Vec<(SessionIndex, SessionCommittee<T::AccountId>)>, SessionRangeError> {
+        if from > to {
+            return Err(SessionRangeError::InvalidRange { from, to });
+        }
+
+        let (current_starting_index, planned_era_end) =
+            Self::current_era_sessions().map_err(|e| SessionRangeError::Other(e.encode()))?;
+
+        if from < current_starting_index || to > planned_era_end {
+            let session = if from < current_starting_index {
+                from
+            } else {
+                max(from, planned_era_end + 1)
+            };
+            return Err(SessionRangeError::SessionNotWithinRange {
+                session,
+                lower_limit: current_starting_index,
+                upper_limit: planned_era_end,
+            });
+        }
+
+        let era_validators = T::ValidatorProvider::current_era_validators();
+        let committee_seats = T::ValidatorProvider::current_era_committee_size();
+        (from..=to)
+            .map(|session| {
+                Self::select_committee(&era_validators, committee_seats, session)
+                    .map(|committee| (session, committee))
+                    .ok_or_else(|| SessionRangeError::Other("Internal error".encode()))
+            })
+            .collect()
+    }
+
+    /// Returns the first and the last session of the current era.
+    fn current_era_sessions() -> Result<(SessionIndex, SessionIndex), &'static str> {
+        let ce = T::EraInfoProvider::current_era().ok_or("No current era")?;
+
+        let current_starting_index = T::EraInfoProvider::era_start_session_index(ce)
+            .ok_or("No known starting session for current era")?;
+        let planned_era_end = current_starting_index + T::EraInfoProvider::sessions_per_era() - 1;
+
+        Ok((current_starting_index, planned_era_end))
+    }
 }
 
 #[cfg(test)]
@@ -508,7 +587,7 @@ mod tests {
 
     use crate::impls::{
         calculate_adjusted_session_points, compute_validator_scaled_total_rewards,
-        select_committee_inner, MAX_REWARD,
+        select_committee_inner, session_reward_ratio, MAX_REWARD,
     };
 
     const THRESHOLD: Perquintill = Perquintill::from_percent(90);
@@ -549,6 +628,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn adjusted_session_points_below_90_perc_are_scaled_linearly() {
+        assert_eq!(
+            0,
+            calculate_adjusted_session_points(5, 100, 0, 25_000, THRESHOLD)
+        );
+
+        assert_eq!(
+            2500,
+            calculate_adjusted_session_points(5, 100, 45, 25_000, THRESHOLD)
+        );
+
+        assert_eq!(
+            4000,
+            calculate_adjusted_session_points(5, 100, 72, 25_000, THRESHOLD)
+        );
+
+        assert_eq!(
+            4500,
+            calculate_adjusted_session_points(5, 100, 81, 25_000, THRESHOLD)
+        );
+    }
+
+    #[test]
+    fn session_reward_ratio_follows_linear_curve() {
+        assert_eq!(
+            Perquintill::zero(),
+            session_reward_ratio(Perquintill::zero(), THRESHOLD)
+        );
+        assert_eq!(
+            Perquintill::from_percent(50),
+            session_reward_ratio(Perquintill::from_percent(45), THRESHOLD)
+        );
+        assert_eq!(
+            Perquintill::from_percent(80),
+            session_reward_ratio(Perquintill::from_percent(72), THRESHOLD)
+        );
+        assert_eq!(
+            Perquintill::one(),
+            session_reward_ratio(Perquintill::from_percent(90), THRESHOLD)
+        );
+        assert_eq!(
+            Perquintill::one(),
+            session_reward_ratio(Perquintill::from_percent(95), THRESHOLD)
+        );
+        assert_eq!(
+            Perquintill::one(),
+            session_reward_ratio(Perquintill::zero(), Perquintill::zero())
+        );
+    }
+
     #[test]
     fn adjusted_session_points_more_than_all_blocks_created_are_calculated_correctly() {
         assert_eq!(
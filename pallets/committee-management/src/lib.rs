@@ -5,6 +5,7 @@ extern crate core;
 
 mod impls;
 mod manager;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -15,6 +16,7 @@ use frame_support::{pallet_prelude::Get, traits::StorageVersion};
 pub use manager::SessionAndEraManager;
 pub use pallet::*;
 use parity_scale_codec::{Decode, Encode};
+pub use primitives::performance::session_reward_ratio;
 use primitives::{
     BanInfo, FinalityBanConfig as FinalityBanConfigStruct,
     ProductionBanConfig as ProductionBanConfigStruct, SessionValidators, LENIENT_THRESHOLD,
@@ -51,7 +53,7 @@ impl Get<Perquintill> for DefaultLenientThreshold {
     }
 }
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 pub(crate) const LOG_TARGET: &str = "pallet-committee-management";
 
 #[frame_support::pallet]
@@ -66,7 +68,7 @@ pub mod pallet {
         SessionCount, SessionValidators, ValidatorProvider,
     };
     use sp_runtime::{Perbill, Perquintill};
-    use sp_staking::EraIndex;
+    use sp_staking::{EraIndex, SessionIndex};
     use sp_std::vec::Vec;
 
     use crate::{
@@ -93,6 +95,9 @@ pub mod pallet {
         /// Nr of blocks in the session.
         #[pallet::constant]
         type SessionPeriod: Get<u32>;
+        /// Maximal number of validators whose bans can be cancelled in a single call.
+        #[pallet::constant]
+        type MaxBanBatchSize: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -145,6 +150,11 @@ pub mod pallet {
     #[pallet::getter(fn finality_ban_config)]
     pub type FinalityBanConfig<T> = StorageValue<_, FinalityBanConfigStruct, ValueQuery>;
 
+    /// The session in which a validator from the current era first appeared in the committee.
+    #[pallet::storage]
+    pub type FirstSeenSession<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, SessionIndex, OptionQuery>;
+
     #[pallet::error]
     pub enum Error<T> {
         /// Raised in any scenario [`ProductionBanConfig`] is invalid
@@ -159,6 +169,9 @@ pub mod pallet {
 
         /// Lenient threshold not in [0-100] range
         InvalidLenientThreshold,
+
+        /// Too many validators given at once, ie more than [`Config::MaxBanBatchSize`]
+        BanBatchTooBig,
     }
 
     #[pallet::event]
@@ -173,8 +186,19 @@ pub mod pallet {
         /// Validators have been banned from the committee
         BanValidators(Vec<(T::AccountId, BanInfo)>),
 
-        /// Validator is underperforimg in finality committee
-        ValidatorUnderperforming(T::AccountId),
+        /// Validator will be banned after `sessions_remaining` more underperforming sessions in
+        /// block production
+        // Index 3 belonged to `ValidatorUnderperforming`, replaced by banning underperforming
+        // finalizers, so it must not be reused.
+        #[codec(index = 4)]
+        ValidatorApproachingBan {
+            who: T::AccountId,
+            sessions_remaining: SessionCount,
+        },
+
+        /// The ban of a validator has been cancelled
+        #[codec(index = 5)]
+        BanCancelled(T::AccountId),
     }
 
     #[pallet::call]
@@ -188,6 +212,7 @@ pub mod pallet {
             underperformed_session_count_threshold: Option<u32>,
             clean_session_counter_delay: Option<u32>,
             ban_period: Option<EraIndex>,
+            grace_sessions: Option<SessionCount>,
         ) -> DispatchResult {
             ensure_root(origin)?;
 
@@ -223,6 +248,9 @@ pub mod pallet {
                 ensure!(ban_period > 0, Error::<T>::InvalidBanConfig);
                 current_committee_ban_config.ban_period = ban_period;
             }
+            if let Some(grace_sessions) = grace_sessions {
+                current_committee_ban_config.grace_sessions = grace_sessions;
+            }
 
             ProductionBanConfig::<T>::put(current_committee_ban_config.clone());
             Self::deposit_event(Event::SetBanConfig(current_committee_ban_config));
@@ -320,6 +348,24 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Cancel the bans of multiple nodes at once. Nodes that are not banned are skipped.
+        #[pallet::call_index(6)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn cancel_ban_batch(origin: OriginFor<T>, banned: Vec<T::AccountId>) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                banned.len() <= T::MaxBanBatchSize::get() as usize,
+                Error::<T>::BanBatchTooBig
+            );
+            for validator in banned {
+                if Banned::<T>::take(&validator).is_some() {
+                    Self::deposit_event(Event::BanCancelled(validator));
+                }
+            }
+
+            Ok(())
+        }
     }
 
     #[pallet::genesis_config]
@@ -117,6 +117,7 @@ where
         T::new_session(new_index);
         if let Some(era) = Self::session_starts_next_era(new_index) {
             EM::on_new_era(era);
+            Pallet::<C>::prune_first_seen();
             Pallet::<C>::emit_fresh_bans_event();
         }
 
@@ -133,7 +134,7 @@ where
     fn end_session(end_index: SessionIndex) {
         T::end_session(end_index);
         Pallet::<C>::adjust_rewards_for_session();
-        Pallet::<C>::calculate_underperforming_validators();
+        Pallet::<C>::calculate_underperforming_validators(end_index);
         Pallet::<C>::calculate_underperforming_finalizers(end_index);
         // clear block count after calculating stats for underperforming validators, as they use
         // SessionValidatorBlockCount for that
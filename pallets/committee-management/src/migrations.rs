@@ -0,0 +1,74 @@
+pub mod v2 {
+    use frame_support::{
+        pallet_prelude::{Get, StorageVersion, Weight},
+        traits::OnRuntimeUpgrade,
+    };
+    use log::info;
+    use parity_scale_codec::Decode;
+    use primitives::{SessionCount, DEFAULT_BAN_GRACE_SESSIONS};
+    use sp_runtime::Perbill;
+    use sp_staking::EraIndex;
+    use sp_std::marker::PhantomData;
+    #[cfg(feature = "try-runtime")]
+    use {pallets_support::ensure_storage_version, sp_std::vec::Vec};
+
+    use crate::{Config, Pallet, ProductionBanConfig, ProductionBanConfigStruct, LOG_TARGET};
+
+    #[derive(Decode)]
+    struct ProductionBanConfigV1 {
+        minimal_expected_performance: Perbill,
+        underperformed_session_count_threshold: SessionCount,
+        clean_session_counter_delay: SessionCount,
+        ban_period: EraIndex,
+    }
+
+    /// Adds `grace_sessions` to the production ban config, using the default value which preserves
+    /// the previous behavior.
+    pub struct Migration<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != StorageVersion::new(1) {
+                info!(
+                    target: LOG_TARGET,
+                    "Skipping migration of the production ban config, wrong storage version"
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let result =
+                ProductionBanConfig::<T>::translate(|old: Option<ProductionBanConfigV1>| {
+                    old.map(|old| ProductionBanConfigStruct {
+                        minimal_expected_performance: old.minimal_expected_performance,
+                        underperformed_session_count_threshold: old
+                            .underperformed_session_count_threshold,
+                        clean_session_counter_delay: old.clean_session_counter_delay,
+                        ban_period: old.ban_period,
+                        grace_sessions: DEFAULT_BAN_GRACE_SESSIONS,
+                    })
+                });
+            if result.is_err() {
+                log::error!(
+                    target: LOG_TARGET,
+                    "Could not decode the production ban config, using the default one"
+                );
+                ProductionBanConfig::<T>::kill();
+            }
+            StorageVersion::new(2).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(2, 2)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            ensure_storage_version::<Pallet<T>>(1)?;
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            ensure_storage_version::<Pallet<T>>(2)?;
+            Ok(())
+        }
+    }
+}
@@ -234,6 +234,7 @@ impl pallet_elections::Config for TestRuntime {
     type ValidatorProvider = Staking;
     type MaxWinners = MaxWinners;
     type BannedValidators = CommitteeManagement;
+    type MinCommitteeSize = ConstU32<0>;
 }
 
 impl Config for TestRuntime {
@@ -246,6 +247,7 @@ impl Config for TestRuntime {
     type FinalityCommitteeManager = Aleph;
     type SessionPeriod = SessionPeriod;
     type AbftScoresProvider = Aleph;
+    type MaxBanBatchSize = ConstU32<3>;
 }
 
 pub fn active_era() -> EraIndex {
@@ -1,15 +1,19 @@
 use std::collections::BTreeSet;
 
+use frame_support::{assert_noop, assert_ok};
 use pallet_aleph::AbftScores;
-use primitives::{BanInfo, BannedValidators, Score};
+use primitives::{
+    BanInfo, BannedValidators, CommitteeSeats, Score, SessionRangeError, SessionValidatorError,
+};
 
 use crate::{
     mock::{
         active_era, advance_era, committee_management_events, start_session, AccountId,
-        CommitteeManagement, Elections, SessionPeriod, TestBuilderConfig, TestExtBuilder,
-        TestRuntime,
+        CommitteeManagement, Elections, RuntimeOrigin, Session, SessionPeriod, SessionsPerEra,
+        TestBuilderConfig, TestExtBuilder, TestRuntime,
     },
-    CurrentAndNextSessionValidatorsStorage, Event, ProductionBanConfig, SessionValidatorBlockCount,
+    Banned, CurrentAndNextSessionValidatorsStorage, Error, Event, FirstSeenSession,
+    ProductionBanConfig, SessionValidatorBlockCount, UnderperformedValidatorSessionCount,
 };
 
 fn gen_config() -> TestBuilderConfig {
@@ -153,6 +157,102 @@ fn ban_underperforming_producers() {
     })
 }
 
+#[test]
+fn underperformance_not_counted_during_grace_period() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        let grace_sessions = 2;
+        let mut ban_config = CommitteeManagement::production_ban_config();
+        ban_config.grace_sessions = grace_sessions;
+        ProductionBanConfig::<TestRuntime>::put(ban_config);
+        let reserved: BTreeSet<AccountId> = Elections::current_era_validators()
+            .reserved
+            .into_iter()
+            .collect();
+        let blocks_to_produce_per_session = SessionPeriod::get();
+        let staying: Vec<AccountId> = (10..60).collect();
+        let newcomers: Vec<AccountId> = (60..100).collect();
+
+        // Drop some validators for a few eras, so that they are forgotten and seen anew once they
+        // come back.
+        assert_ok!(Elections::change_validators(
+            RuntimeOrigin::root(),
+            None,
+            Some(staying.clone()),
+            None
+        ));
+        for _ in 0..3 {
+            advance_era();
+        }
+        assert!(newcomers
+            .iter()
+            .all(|validator| FirstSeenSession::<TestRuntime>::get(validator).is_none()));
+        assert_ok!(Elections::change_validators(
+            RuntimeOrigin::root(),
+            None,
+            Some(staying.into_iter().chain(newcomers.clone()).collect()),
+            None
+        ));
+
+        // Wait until the rotation brings one of the newcomers into the committee.
+        let last_session_to_wait_for = Session::current_index() + 4 * SessionsPerEra::get();
+        let (underperformer, first_seen) = loop {
+            let session_index = Session::current_index() + 1;
+            assert!(
+                session_index <= last_session_to_wait_for,
+                "no newcomer has been seen in the committee"
+            );
+            start_session(session_index);
+            let newcomer = CommitteeManagement::current_session_validators()
+                .current
+                .producers
+                .into_iter()
+                .find(|validator| {
+                    newcomers.contains(validator)
+                        && FirstSeenSession::<TestRuntime>::get(validator) == Some(session_index)
+                });
+            if let Some(newcomer) = newcomer {
+                break (newcomer, session_index);
+            }
+        };
+
+        let mut expected_underperf_count = 0;
+        for session_index in first_seen..first_seen + grace_sessions + 2 {
+            if session_index > first_seen {
+                start_session(session_index);
+            }
+            assert_eq!(
+                UnderperformedValidatorSessionCount::<TestRuntime>::get(underperformer),
+                expected_underperf_count
+            );
+            assert_eq!(
+                FirstSeenSession::<TestRuntime>::get(underperformer),
+                Some(first_seen)
+            );
+
+            // Make sure underperformer is a producer in every session and underperforms.
+            let producers = CurrentAndNextSessionValidatorsStorage::<TestRuntime>::mutate(|sv| {
+                add_underperformer(&mut sv.current.producers, underperformer, &reserved)
+            });
+            for producer in producers.iter() {
+                SessionValidatorBlockCount::<TestRuntime>::insert(
+                    producer,
+                    blocks_to_produce_per_session,
+                );
+            }
+            SessionValidatorBlockCount::<TestRuntime>::insert(underperformer, 0);
+
+            if session_index >= first_seen + grace_sessions {
+                expected_underperf_count += 1;
+            }
+        }
+        start_session(first_seen + grace_sessions + 2);
+        assert_eq!(
+            UnderperformedValidatorSessionCount::<TestRuntime>::get(underperformer),
+            expected_underperf_count
+        );
+    })
+}
+
 #[test]
 fn ban_underperforming_finalizers() {
     TestExtBuilder::new(gen_config()).build().execute_with(|| {
@@ -195,9 +295,158 @@ fn ban_underperforming_finalizers() {
             session_index += 1;
         }
 
+        assert_eq!(CommitteeManagement::banned(), vec![underperformer]);
+        assert_eq!(
+            Banned::<TestRuntime>::get(underperformer).map(|ban_info| ban_info.reason),
+            Some(primitives::BanReason::InsufficientFinalityParticipation(
+                underperformed_session_count_threshold
+            ))
+        );
+    })
+}
+
+#[test]
+fn predicts_committees_for_whole_range_of_sessions() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        let Err(SessionValidatorError::SessionNotWithinRange {
+            lower_limit,
+            upper_limit,
+        }) = CommitteeManagement::predict_session_committee_for_session(u32::MAX)
+        else {
+            panic!("session should not be within range");
+        };
+
+        let committees =
+            CommitteeManagement::predict_session_committee_for_range(lower_limit, upper_limit)
+                .expect("range is within the current era");
+        assert_eq!(committees.len() as u32, upper_limit - lower_limit + 1);
+        for (session, committee) in committees {
+            assert_eq!(
+                Ok(committee),
+                CommitteeManagement::predict_session_committee_for_session(session)
+            );
+        }
+
+        assert_eq!(
+            CommitteeManagement::predict_session_committee_for_range(lower_limit, upper_limit + 3),
+            Err(SessionRangeError::SessionNotWithinRange {
+                session: upper_limit + 1,
+                lower_limit,
+                upper_limit,
+            })
+        );
+    })
+}
+
+#[test]
+fn rejects_inverted_range_of_sessions() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+
+        assert_eq!(
+            CommitteeManagement::predict_session_committee_for_range(3, 2),
+            Err(SessionRangeError::InvalidRange { from: 3, to: 2 })
+        );
+    })
+}
+
+#[test]
+fn cancel_ban_batch_unbans_known_validators() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        let ban_info = BanInfo {
+            reason: primitives::BanReason::InsufficientUptime(3),
+            start: active_era() + 1,
+        };
+        for validator in [10, 11, 12] {
+            Banned::<TestRuntime>::insert(validator, ban_info.clone());
+        }
+
+        assert_noop!(
+            CommitteeManagement::cancel_ban_batch(RuntimeOrigin::root(), vec![10, 11, 12, 13]),
+            Error::<TestRuntime>::BanBatchTooBig
+        );
+
+        assert_ok!(CommitteeManagement::cancel_ban_batch(
+            RuntimeOrigin::root(),
+            vec![10, 12, 42]
+        ));
+        assert!(!Banned::<TestRuntime>::contains_key(10));
+        assert!(Banned::<TestRuntime>::contains_key(11));
+        assert!(!Banned::<TestRuntime>::contains_key(12));
+        let cancelled: Vec<_> = committee_management_events()
+            .into_iter()
+            .filter(|event| matches!(event, Event::BanCancelled(_)))
+            .collect();
+        assert_eq!(
+            cancelled,
+            vec![Event::BanCancelled(10), Event::BanCancelled(12)]
+        );
+    })
+}
+
+#[test]
+fn warns_about_validator_approaching_ban() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        let underperformer = 10;
+        let ban_config = CommitteeManagement::production_ban_config();
+        let threshold = ban_config.underperformed_session_count_threshold;
+
+        for _ in 0..threshold - 2 {
+            CommitteeManagement::mark_validator_underperformance(&ban_config, &underperformer);
+        }
+        assert!(!committee_management_events()
+            .iter()
+            .any(|event| matches!(event, Event::ValidatorApproachingBan { .. })));
+
+        CommitteeManagement::mark_validator_underperformance(&ban_config, &underperformer);
         assert_eq!(
             *committee_management_events().last().unwrap(),
-            Event::ValidatorUnderperforming(underperformer)
+            Event::ValidatorApproachingBan {
+                who: underperformer,
+                sessions_remaining: 1,
+            }
+        );
+    })
+}
+
+#[test]
+fn forced_committee_rotation_skips_banned_and_not_elected_validators() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        let reserved: Vec<AccountId> = (0..10).collect();
+        let not_elected = 1000;
+        let banned = 10;
+        Banned::<TestRuntime>::insert(
+            banned,
+            BanInfo {
+                reason: primitives::BanReason::InsufficientUptime(3),
+                start: active_era(),
+            },
         );
+        assert_ok!(Elections::change_validators(
+            RuntimeOrigin::root(),
+            Some(reserved.clone()),
+            Some((10..30).chain([not_elected]).collect()),
+            Some(CommitteeSeats {
+                reserved_seats: 10,
+                non_reserved_seats: 19,
+                non_reserved_finality_seats: 4,
+            }),
+        ));
+
+        assert_ok!(Elections::force_rotate_committee(RuntimeOrigin::root()));
+        // The committee is chosen at the next session change and takes over a session later.
+        start_session(4);
+
+        let expected: BTreeSet<AccountId> = reserved.into_iter().chain(11..30).collect();
+        let committee = CommitteeManagement::current_session_validators().current;
+        let producers: BTreeSet<AccountId> = committee.producers.into_iter().collect();
+        assert_eq!(producers, expected);
+        assert!(committee
+            .finalizers
+            .iter()
+            .all(|finalizer| expected.contains(finalizer)));
     })
 }
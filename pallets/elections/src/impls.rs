@@ -13,7 +13,9 @@ impl<T> Pallet<T>
 where
     T: Config,
 {
-    fn populate_next_era_validators_on_next_era_start(era: EraIndex) {
+    /// Validators configured for the next era that were elected by staking in `era`, shuffled
+    /// with `era` as a seed.
+    pub(crate) fn elected_next_era_validators(era: EraIndex) -> EraValidators<T::AccountId> {
         let mut rng = Pcg32::seed_from_u64(era as u64);
         let elected_committee = BTreeSet::from_iter(T::ValidatorProvider::elected_validators(era));
 
@@ -29,12 +31,17 @@ where
 
         let reserved_validators = NextEraReservedValidators::<T>::get();
         let non_reserved_validators = NextEraNonReservedValidators::<T>::get();
-        let committee_size = NextEraCommitteeSize::<T>::get();
 
-        CurrentEraValidators::<T>::put(EraValidators {
+        EraValidators {
             reserved: retain_shuffle_elected(reserved_validators),
             non_reserved: retain_shuffle_elected(non_reserved_validators),
-        });
+        }
+    }
+
+    fn populate_next_era_validators_on_next_era_start(era: EraIndex) {
+        let committee_size = NextEraCommitteeSize::<T>::get();
+
+        CurrentEraValidators::<T>::put(Self::elected_next_era_validators(era));
         CommitteeSize::<T>::put(committee_size);
     }
 }
@@ -14,12 +14,15 @@ use parity_scale_codec::{Decode, Encode};
 pub use primitives::EraValidators;
 use scale_info::TypeInfo;
 use sp_std::{
+    cmp::{min, Reverse},
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
     prelude::*,
 };
 
 pub type TotalReward = u32;
 
+pub(crate) const LOG_TARGET: &str = "pallet-elections";
+
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
 #[derive(Decode, Encode, TypeInfo)]
@@ -37,7 +40,7 @@ pub mod pallet {
         ensure_root,
         pallet_prelude::{BlockNumberFor, OriginFor},
     };
-    use primitives::{BannedValidators, CommitteeSeats, ElectionOpenness};
+    use primitives::{BannedValidators, CommitteeSeats, CommitteeSeatsError, ElectionOpenness};
 
     use super::*;
     use crate::traits::ValidatorProvider;
@@ -58,6 +61,10 @@ pub mod pallet {
         #[pallet::constant]
         type MaxWinners: Get<u32>;
         type BannedValidators: BannedValidators<AccountId = Self::AccountId>;
+        /// The minimal number of validators `elect` should return. If bans leave fewer eligible
+        /// validators, the banned ones with the highest support are elected anyway.
+        #[pallet::constant]
+        type MinCommitteeSize: Get<u32>;
     }
 
     #[pallet::event]
@@ -65,6 +72,12 @@ pub mod pallet {
     pub enum Event<T: Config> {
         /// Committee for the next era has changed
         ChangeValidators(Vec<T::AccountId>, Vec<T::AccountId>, CommitteeSeats),
+
+        /// Banned validators that would be elected otherwise, but were excluded from the elections
+        ExcludedBannedValidators(Vec<T::AccountId>),
+
+        /// Committee for the next era was put in place before the end of the current era
+        CommitteeRotationForced(Vec<T::AccountId>, Vec<T::AccountId>, CommitteeSeats),
     }
 
     #[pallet::pallet]
@@ -112,6 +125,11 @@ pub mod pallet {
     #[pallet::storage]
     pub type Openness<T> = StorageValue<_, ElectionOpenness, ValueQuery, DefaultOpenness<T>>;
 
+    /// Validators that can be elected in case of `PermissionedWithAllowlist` elections openness.
+    #[pallet::storage]
+    pub type ElectionAllowlist<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
@@ -161,6 +179,82 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Add validators to the elections allowlist
+        #[pallet::call_index(5)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn add_to_allowlist(
+            origin: OriginFor<T>,
+            validators: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            for validator in validators {
+                ElectionAllowlist::<T>::insert(validator, ());
+            }
+
+            Ok(())
+        }
+
+        /// Remove validators from the elections allowlist
+        #[pallet::call_index(6)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn remove_from_allowlist(
+            origin: OriginFor<T>,
+            validators: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            for validator in validators {
+                ElectionAllowlist::<T>::remove(validator);
+            }
+
+            Ok(())
+        }
+
+        /// Put the committee configured for the next era (`NextEraReservedValidators`,
+        /// `NextEraNonReservedValidators` and `NextEraCommitteeSize`) in place immediately,
+        /// without waiting for the end of the current era.
+        ///
+        /// As at the era change, only validators elected by staking in the active era are kept,
+        /// and banned non-reserved validators are skipped. The committee is chosen from the new
+        /// validators at the next session change and, as pallet session queues validators, it
+        /// produces and finalizes blocks one session later.
+        #[pallet::call_index(7)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn force_rotate_committee(origin: OriginFor<T>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let EraValidators {
+                reserved: reserved_validators,
+                non_reserved: mut non_reserved_validators,
+            } = Self::elected_next_era_validators(T::ValidatorProvider::active_era());
+            let banned_validators = T::BannedValidators::banned()
+                .into_iter()
+                .collect::<BTreeSet<_>>();
+            non_reserved_validators.retain(|v| !banned_validators.contains(v));
+            let committee_size = NextEraCommitteeSize::<T>::get();
+
+            Self::ensure_validators_are_ok(
+                reserved_validators.clone(),
+                non_reserved_validators.clone(),
+                committee_size,
+            )?;
+
+            CurrentEraValidators::<T>::put(EraValidators {
+                reserved: reserved_validators.clone(),
+                non_reserved: non_reserved_validators.clone(),
+            });
+            CommitteeSize::<T>::put(committee_size);
+
+            Self::deposit_event(Event::CommitteeRotationForced(
+                reserved_validators,
+                non_reserved_validators,
+                committee_size,
+            ));
+
+            Ok(())
+        }
     }
 
     #[pallet::hooks]
@@ -217,16 +311,19 @@ pub mod pallet {
                 non_reserved_seats: non_reserved,
                 non_reserved_finality_seats: non_reserved_finality,
             } = committee_size;
+            CommitteeSeats::try_new(reserved, non_reserved, non_reserved_finality).map_err(
+                |e| match e {
+                    CommitteeSeatsError::NonReservedFinalitySeatsLargerThanNonReservedSeats => {
+                        Error::<T>::NonReservedFinalitySeatsLargerThanNonReservedSeats
+                    }
+                },
+            )?;
             let reserved_len = reserved_validators.len() as u32;
             let non_reserved_len = non_reserved_validators.len() as u32;
             let validators_size = reserved_len + non_reserved_len;
 
             let committee_size_all = reserved + non_reserved;
 
-            ensure!(
-                non_reserved_finality <= non_reserved,
-                Error::<T>::NonReservedFinalitySeatsLargerThanNonReservedSeats
-            );
             ensure!(
                 committee_size_all <= validators_size,
                 Error::<T>::NotEnoughValidators
@@ -290,6 +387,8 @@ pub mod pallet {
         /// We calculate the supports for each validator. The external validators are chosen as:
         /// 1) "`NextEraNonReservedValidators` that are staking and are not banned" in case of Permissioned ElectionOpenness
         /// 2) "All staking and not banned validators" in case of Permissionless ElectionOpenness
+        /// 3) "All staking, not banned and allowlisted validators" in case of PermissionedWithAllowlist
+        /// ElectionOpenness
         fn elect() -> Result<BoundedSupportsOf<Self>, Self::Error> {
             let staking_validators =
                 Self::DataProvider::electable_targets(DataProviderBounds::default())
@@ -304,20 +403,65 @@ pub mod pallet {
                 .into_iter()
                 .collect::<BTreeSet<_>>();
             let old_non_reserved_validators = NextEraNonReservedValidators::<T>::get().into_iter();
+            let is_non_reserved_candidate = |v: &T::AccountId| {
+                staking_validators.contains(v) && !staking_reserved_validators.contains(v)
+            };
 
-            let eligible_non_reserved = staking_validators
-                .into_iter()
-                .filter(|v| {
-                    !banned_validators.contains(v) && !staking_reserved_validators.contains(v)
-                })
-                .collect::<BTreeSet<_>>();
-
-            let new_non_reserved_validators: Vec<_> = match Openness::<T>::get() {
+            let non_reserved_candidates: Vec<_> = match Openness::<T>::get() {
+                // Only already chosen validators can be elected, so there is no need to go
+                // through all the staking validators.
                 ElectionOpenness::Permissioned => old_non_reserved_validators
-                    .filter(|v| eligible_non_reserved.contains(v))
+                    .filter(is_non_reserved_candidate)
+                    .collect(),
+                ElectionOpenness::Permissionless => staking_validators
+                    .iter()
+                    .filter(|v| is_non_reserved_candidate(v))
+                    .cloned()
+                    .collect(),
+                ElectionOpenness::PermissionedWithAllowlist => staking_validators
+                    .iter()
+                    .filter(|v| {
+                        is_non_reserved_candidate(v) && ElectionAllowlist::<T>::contains_key(v)
+                    })
+                    .cloned()
                     .collect(),
-                ElectionOpenness::Permissionless => eligible_non_reserved.into_iter().collect(),
             };
+            let (mut excluded_banned_validators, mut new_non_reserved_validators): (
+                Vec<_>,
+                Vec<_>,
+            ) = non_reserved_candidates
+                .into_iter()
+                .partition(|v| banned_validators.contains(v));
+
+            let voters = Self::DataProvider::electing_voters(DataProviderBounds::default())
+                .map_err(Self::Error::DataProvider)?;
+
+            let eligible_count =
+                staking_reserved_validators.len() + new_non_reserved_validators.len();
+            let missing = (T::MinCommitteeSize::get() as usize).saturating_sub(eligible_count);
+            if missing > 0 && !excluded_banned_validators.is_empty() {
+                let mut votes = BTreeMap::<&T::AccountId, u128>::new();
+                for (_, vote, targets) in voters.iter() {
+                    if let Some(target) = targets.first() {
+                        *votes.entry(target).or_default() += *vote as u128;
+                    }
+                }
+                excluded_banned_validators
+                    .sort_by_key(|v| Reverse(votes.get(v).copied().unwrap_or_default()));
+                let backfill_len = min(missing, excluded_banned_validators.len());
+                let backfill: Vec<_> = excluded_banned_validators.drain(..backfill_len).collect();
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Only {} validators are eligible, less than the minimal committee size {}. Electing banned validators {:?} anyway.",
+                    eligible_count,
+                    T::MinCommitteeSize::get(),
+                    backfill,
+                );
+                new_non_reserved_validators.extend(backfill);
+            }
+            if !excluded_banned_validators.is_empty() {
+                Self::deposit_event(Event::ExcludedBannedValidators(excluded_banned_validators));
+            }
             // We store new list here to ensure that validators that end up in the result of the elect
             // method are a disjoint union of NextEraReservedValidators and NextEraNonReservedValidators.
             // This condition is important since results of elect ends up in pallet staking while the above lists
@@ -342,11 +486,9 @@ pub mod pallet {
                 })
                 .collect::<BTreeMap<_, _>>();
 
-            let voters = Self::DataProvider::electing_voters(DataProviderBounds::default())
-                .map_err(Self::Error::DataProvider)?;
             for (voter, vote, targets) in voters {
                 // The parameter `Staking::MAX_NOMINATIONS` is set to 1 which guarantees that
-                // `len(targets) == 1`.
+                // `len(targets) == 1`, so voters of validators that are not elected can be skipped.
                 let member = &targets[0];
                 if let Some(support) = supports.get_mut(member) {
                     support.total += vote as u128;
@@ -354,6 +496,19 @@ pub mod pallet {
                 }
             }
 
+            // Self-vote is usually among the voters, but if it is not, the validator's own bond
+            // is counted in, so that the support reflects its real stake.
+            for (validator, support) in supports.iter_mut() {
+                if support.voters.iter().any(|(voter, _)| voter == validator) {
+                    continue;
+                }
+                let self_stake = T::ValidatorProvider::self_stake(validator);
+                if self_stake > 0 {
+                    support.total += self_stake;
+                    support.voters.push((validator.clone(), self_stake));
+                }
+            }
+
             supports
                 .into_iter()
                 .collect::<Supports<_>>()
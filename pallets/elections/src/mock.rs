@@ -99,6 +99,7 @@ where
 parameter_types! {
     pub const SessionPeriod: u32 = 5;
     pub const SessionsPerEra: u32 = 5;
+    pub static MinCommitteeSize: u32 = 0;
 }
 
 pub struct MockProvider;
@@ -108,14 +109,23 @@ thread_local! {
     static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(Default::default());
     static ELECTED_VALIDATORS: RefCell<BTreeMap<EraIndex, Vec<AccountId>>> = RefCell::new(Default::default());
     static BANNNED_VALIDATORS: RefCell<Vec<AccountId>> = RefCell::new(Default::default());
+    static SELF_STAKES: RefCell<BTreeMap<AccountId, u128>> = RefCell::new(Default::default());
 }
 
 impl ValidatorProvider for MockProvider {
     type AccountId = AccountId;
 
+    fn active_era() -> EraIndex {
+        ACTIVE_ERA.with(|ae| *ae.borrow())
+    }
+
     fn elected_validators(era: EraIndex) -> Vec<Self::AccountId> {
         ELECTED_VALIDATORS.with(|ev| ev.borrow().get(&era).unwrap().clone())
     }
+
+    fn self_stake(validator: &Self::AccountId) -> u128 {
+        SELF_STAKES.with(|ss| ss.borrow().get(validator).copied().unwrap_or_default())
+    }
 }
 
 impl BannedValidators for MockProvider {
@@ -132,6 +142,7 @@ impl Config for Test {
     type ValidatorProvider = MockProvider;
     type MaxWinners = ConstU32<DEFAULT_MAX_WINNERS>;
     type BannedValidators = MockProvider;
+    type MinCommitteeSize = MinCommitteeSize;
 }
 
 type MaxVotesPerVoter = ConstU32<1>;
@@ -143,6 +154,22 @@ thread_local! {
     static ELECTING_VOTERS: RefCell<Vec<Vote>> = RefCell::new(Default::default());
 }
 
+pub fn with_active_era(era: EraIndex) {
+    ACTIVE_ERA.with(|ae| *ae.borrow_mut() = era);
+}
+
+pub fn with_elected_validators(era: EraIndex, elected: Vec<AccountId>) {
+    ELECTED_VALIDATORS.with(|ev| ev.borrow_mut().insert(era, elected));
+}
+
+pub fn with_banned_validators(banned: Vec<AccountId>) {
+    BANNNED_VALIDATORS.with(|b| *b.borrow_mut() = banned);
+}
+
+pub fn with_self_stakes(self_stakes: Vec<(AccountId, u128)>) {
+    SELF_STAKES.with(|ss| *ss.borrow_mut() = self_stakes.into_iter().collect());
+}
+
 pub fn with_electable_targets(targets: Vec<AccountId>) {
     ELECTABLE_TARGETS.with(|et| *et.borrow_mut() = targets);
 }
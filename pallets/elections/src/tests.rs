@@ -1,14 +1,20 @@
-use frame_election_provider_support::{ElectionProvider, Support};
-use primitives::CommitteeSeats;
+use std::collections::{BTreeMap, BTreeSet};
+
+use frame_election_provider_support::{
+    DataProviderBounds, ElectionDataProvider, ElectionProvider, Support,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{CommitteeSeats, ElectionOpenness};
 use sp_core::bounded_vec;
 
 use crate::{
     mock::{
-        with_electable_targets, with_electing_voters, AccountId, Balance, Elections, Test,
-        TestExtBuilder,
+        with_active_era, with_banned_validators, with_electable_targets, with_elected_validators,
+        with_electing_voters, with_self_stakes, AccountId, Balance, Elections, MinCommitteeSize,
+        RuntimeEvent, RuntimeOrigin, StakingMock, System, Test, TestExtBuilder,
     },
-    CommitteeSize, CurrentEraValidators, NextEraCommitteeSize, NextEraNonReservedValidators,
-    NextEraReservedValidators,
+    CommitteeSize, CurrentEraValidators, ElectionAllowlist, Error, Event, NextEraCommitteeSize,
+    NextEraNonReservedValidators, NextEraReservedValidators, Openness,
 };
 
 fn no_support() -> Support<AccountId> {
@@ -80,3 +86,275 @@ fn validators_are_elected_only_when_staking() {
             );
         });
 }
+
+#[test]
+fn self_stake_is_counted_in_support_once() {
+    TestExtBuilder::new(vec![1, 2], vec![3, 4])
+        .build()
+        .execute_with(|| {
+            with_electable_targets(vec![1, 2, 3, 4]);
+            with_electing_voters(vec![
+                (1, 10, bounded_vec![1]),
+                (5, 10, bounded_vec![2]),
+                (6, 20, bounded_vec![3]),
+            ]);
+            with_self_stakes(vec![(1, 10), (2, 15), (3, 5)]);
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[
+                    (1, support(10, vec![(1, 10)])),
+                    (2, support(25, vec![(5, 10), (2, 15)])),
+                    (3, support(25, vec![(6, 20), (3, 5)])),
+                    (4, no_support()),
+                ]
+            );
+        });
+}
+
+#[test]
+fn excluded_banned_validators_are_reported() {
+    TestExtBuilder::new(vec![1, 2], vec![3, 4, 5])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            with_electable_targets(vec![1, 2, 3, 4, 5]);
+            // Banned reserved validators and banned validators that are not candidates anyway
+            // are not reported.
+            with_banned_validators(vec![1, 3, 5, 6]);
+
+            <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            System::assert_last_event(RuntimeEvent::Elections(Event::ExcludedBannedValidators(
+                vec![3, 5],
+            )));
+        });
+}
+
+#[test]
+fn nothing_is_reported_when_no_banned_validator_is_excluded() {
+    TestExtBuilder::new(vec![1, 2], vec![3, 4])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            with_electable_targets(vec![1, 2, 3, 4]);
+            with_banned_validators(vec![1, 5]);
+
+            <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert!(System::events().is_empty());
+        });
+}
+
+#[test]
+fn banned_validators_with_highest_support_backfill_too_small_committee() {
+    TestExtBuilder::new(vec![1], vec![2, 3, 4, 5])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            MinCommitteeSize::set(4);
+            with_electable_targets(vec![1, 2, 3, 4, 5]);
+            with_electing_voters(vec![
+                (3, 10, bounded_vec![3]),
+                (4, 30, bounded_vec![4]),
+                (5, 20, bounded_vec![5]),
+            ]);
+            // Mass ban leaves only 2 eligible validators.
+            with_banned_validators(vec![3, 4, 5]);
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[
+                    (1, no_support()),
+                    (2, no_support()),
+                    (4, support(30, vec![(4, 30)])),
+                    (5, support(20, vec![(5, 20)])),
+                ]
+            );
+            assert_eq!(NextEraNonReservedValidators::<Test>::get(), vec![2, 4, 5]);
+            System::assert_last_event(RuntimeEvent::Elections(Event::ExcludedBannedValidators(
+                vec![3],
+            )));
+        });
+}
+
+/// Straightforward version of `elect()`, which goes through all the staking validators
+/// regardless of the elections openness.
+fn naive_elect(
+    reserved: Vec<AccountId>,
+    non_reserved: Vec<AccountId>,
+    banned: Vec<AccountId>,
+    allowlist: Vec<AccountId>,
+    openness: ElectionOpenness,
+) -> Vec<(AccountId, Support<AccountId>)> {
+    let staking_validators = StakingMock::electable_targets(DataProviderBounds::default())
+        .unwrap()
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    let staking_reserved = reserved
+        .into_iter()
+        .filter(|v| staking_validators.contains(v))
+        .collect::<BTreeSet<_>>();
+    let eligible_non_reserved = staking_validators
+        .into_iter()
+        .filter(|v| !banned.contains(v) && !staking_reserved.contains(v))
+        .collect::<BTreeSet<_>>();
+    let new_non_reserved: Vec<_> = match openness {
+        ElectionOpenness::Permissioned => non_reserved
+            .into_iter()
+            .filter(|v| eligible_non_reserved.contains(v))
+            .collect(),
+        ElectionOpenness::Permissionless => eligible_non_reserved.into_iter().collect(),
+        ElectionOpenness::PermissionedWithAllowlist => eligible_non_reserved
+            .into_iter()
+            .filter(|v| allowlist.contains(v))
+            .collect(),
+    };
+
+    let mut supports = staking_reserved
+        .into_iter()
+        .chain(new_non_reserved)
+        .map(|id| (id, no_support()))
+        .collect::<BTreeMap<_, _>>();
+    for (voter, vote, targets) in
+        StakingMock::electing_voters(DataProviderBounds::default()).unwrap()
+    {
+        if let Some(support) = supports.get_mut(&targets[0]) {
+            support.total += vote as u128;
+            support.voters.push((voter, vote as u128));
+        }
+    }
+
+    supports.into_iter().collect()
+}
+
+#[test]
+fn elect_matches_naive_implementation() {
+    const RESERVED: [AccountId; 3] = [1, 2, 3];
+    const NON_RESERVED: [AccountId; 5] = [4, 5, 6, 7, 8];
+    const BANNED: [AccountId; 2] = [5, 9];
+    const ALLOWLIST: [AccountId; 4] = [2, 5, 6, 10];
+
+    for openness in [
+        ElectionOpenness::Permissioned,
+        ElectionOpenness::Permissionless,
+        ElectionOpenness::PermissionedWithAllowlist,
+    ] {
+        TestExtBuilder::new(RESERVED.to_vec(), NON_RESERVED.to_vec())
+            .build()
+            .execute_with(|| {
+                Openness::<Test>::put(openness.clone());
+                with_banned_validators(BANNED.to_vec());
+                ALLOWLIST
+                    .iter()
+                    .for_each(|v| ElectionAllowlist::<Test>::insert(v, ()));
+                with_electable_targets(vec![1, 2, 4, 5, 6, 8, 9, 10]);
+                with_electing_voters(vec![
+                    (1, 10, bounded_vec![1]),
+                    (3, 10, bounded_vec![3]),
+                    (4, 20, bounded_vec![4]),
+                    (5, 10, bounded_vec![5]),
+                    (6, 10, bounded_vec![6]),
+                    (9, 10, bounded_vec![9]),
+                    (10, 30, bounded_vec![10]),
+                    (11, 5, bounded_vec![4]),
+                    (12, 5, bounded_vec![7]),
+                    (13, 5, bounded_vec![10]),
+                ]);
+
+                let expected = naive_elect(
+                    RESERVED.to_vec(),
+                    NON_RESERVED.to_vec(),
+                    BANNED.to_vec(),
+                    ALLOWLIST.to_vec(),
+                    openness.clone(),
+                );
+                let elected =
+                    <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+                assert_eq!(elected.into_inner(), expected);
+            });
+    }
+}
+
+#[test]
+fn allowlist_is_managed_by_root() {
+    TestExtBuilder::new(vec![1, 2], vec![3, 4])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                Elections::add_to_allowlist(RuntimeOrigin::signed(3), vec![3]),
+                sp_runtime::DispatchError::BadOrigin
+            );
+
+            assert_ok!(Elections::add_to_allowlist(
+                RuntimeOrigin::root(),
+                vec![3, 4]
+            ));
+            assert!(ElectionAllowlist::<Test>::contains_key(3));
+            assert!(ElectionAllowlist::<Test>::contains_key(4));
+
+            assert_ok!(Elections::remove_from_allowlist(
+                RuntimeOrigin::root(),
+                vec![3]
+            ));
+            assert!(!ElectionAllowlist::<Test>::contains_key(3));
+            assert!(ElectionAllowlist::<Test>::contains_key(4));
+        });
+}
+
+#[test]
+fn force_rotate_committee_puts_next_era_committee_in_place() {
+    const COMMITTEE_SEATS: CommitteeSeats = CommitteeSeats {
+        reserved_seats: 1,
+        non_reserved_seats: 2,
+        non_reserved_finality_seats: 1,
+    };
+
+    TestExtBuilder::new(vec![1, 2], vec![3, 4])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            with_active_era(3);
+            with_elected_validators(3, vec![1, 2, 3, 4, 5, 6, 7]);
+            assert_ok!(Elections::change_validators(
+                RuntimeOrigin::root(),
+                Some(vec![1]),
+                Some(vec![5, 6, 7, 8]),
+                Some(COMMITTEE_SEATS),
+            ));
+            assert_eq!(CurrentEraValidators::<Test>::get().reserved, vec![1, 2]);
+
+            assert_noop!(
+                Elections::force_rotate_committee(RuntimeOrigin::signed(1)),
+                sp_runtime::DispatchError::BadOrigin
+            );
+
+            // Validator 8 was not elected and 6 and 7 are banned, too few are left.
+            with_banned_validators(vec![6, 7]);
+            assert_noop!(
+                Elections::force_rotate_committee(RuntimeOrigin::root()),
+                Error::<Test>::NotEnoughValidators
+            );
+
+            with_banned_validators(vec![7]);
+            assert_ok!(Elections::force_rotate_committee(RuntimeOrigin::root()));
+
+            let mut non_reserved = CurrentEraValidators::<Test>::get().non_reserved;
+            assert_eq!(CurrentEraValidators::<Test>::get().reserved, vec![1]);
+            non_reserved.sort();
+            assert_eq!(non_reserved, vec![5, 6]);
+            assert_eq!(CommitteeSize::<Test>::get(), COMMITTEE_SEATS);
+            System::assert_last_event(RuntimeEvent::Elections(Event::CommitteeRotationForced(
+                vec![1],
+                CurrentEraValidators::<Test>::get().non_reserved,
+                COMMITTEE_SEATS,
+            )));
+        });
+}
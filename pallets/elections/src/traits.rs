@@ -1,14 +1,25 @@
-use sp_staking::EraIndex;
+use sp_runtime::traits::UniqueSaturatedInto;
+use sp_staking::{EraIndex, StakingInterface};
 use sp_std::vec::Vec;
 
 pub trait ValidatorProvider {
     type AccountId;
+    /// The currently active era, `0` before the first era starts.
+    fn active_era() -> EraIndex;
     fn elected_validators(era: EraIndex) -> Vec<Self::AccountId>;
+    /// Amount bonded by the validator itself, `0` if it is not bonded.
+    fn self_stake(validator: &Self::AccountId) -> u128;
 }
 
 impl<T: pallet_staking::Config> ValidatorProvider for pallet_staking::Pallet<T> {
     type AccountId = T::AccountId;
 
+    fn active_era() -> EraIndex {
+        pallet_staking::ActiveEra::<T>::get()
+            .map(|active_era| active_era.index)
+            .unwrap_or_default()
+    }
+
     fn elected_validators(era: EraIndex) -> Vec<Self::AccountId> {
         pallet_staking::ErasStakers::<T>::iter_key_prefix(era)
             .chain(pallet_staking::ErasStakersOverview::<T>::iter_key_prefix(
@@ -16,4 +27,10 @@ impl<T: pallet_staking::Config> ValidatorProvider for pallet_staking::Pallet<T>
             ))
             .collect()
     }
+
+    fn self_stake(validator: &Self::AccountId) -> u128 {
+        <pallet_staking::Pallet<T> as StakingInterface>::stake(validator)
+            .map(|stake| stake.active.unique_saturated_into())
+            .unwrap_or(0)
+    }
 }
@@ -18,39 +18,76 @@ impl<T: Config> Pallet<T> {
     /// Calculate expected consumers counter for a `who` account, and if actual
     /// counter is not as expected, increment or decrement current counter
     pub fn fix_consumer_counter(who: T::AccountId) -> DispatchResult {
-        let current_consumers = T::AccountInfoProvider::get_consumers(&who);
+        let (current_consumers, expected_consumers) = Self::diagnose_consumers(&who);
+
+        #[allow(clippy::comparison_chain)]
+        if current_consumers < expected_consumers {
+            log::debug!(
+                target: LOG_TARGET,
+                "Account {:?} has consumers underflow: current({}) < expected ({}), incrementing ",
+                HexDisplay::from(&who.encode()), current_consumers, expected_consumers);
+            Self::increment_consumers(&who)?;
+        } else if current_consumers > expected_consumers {
+            log::debug!(
+                target: LOG_TARGET,
+                "Account {:?} has consumers overflow: current({}) > expected ({}), decrementing ",
+                HexDisplay::from(&who.encode()), current_consumers, expected_consumers);
+            Self::decrement_consumers(&who);
+        } else {
+            log::trace!(
+                target: LOG_TARGET,
+                "Account {:?} neither has underflow nor overflow of consumers counter.",
+                HexDisplay::from(&who.encode())
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns current and expected consumers counter for a `who` account, without changing
+    /// anything
+    pub fn diagnose_consumers(who: &T::AccountId) -> (u32, u32) {
+        let current_consumers = T::AccountInfoProvider::get_consumers(who);
         let mut expected_consumers: u32 = 0;
 
-        if Self::reserved_or_frozen_non_zero(&who) {
+        if Self::reserved_or_frozen_non_zero(who) {
             expected_consumers += 1;
         }
-        if Self::is_contract_account(&who) {
+        if Self::is_contract_account(who) {
             expected_consumers += 1;
         }
-        if Self::is_bonded(&who) {
+        if Self::is_bonded(who) {
             expected_consumers += 1;
         }
-        if Self::has_next_session_keys_and_account_is_controller(&who) {
+        if Self::has_next_session_keys_and_account_is_controller(who) {
             expected_consumers += 1;
         }
 
+        (current_consumers, expected_consumers)
+    }
+
+    /// Calculate expected providers counter for a `who` account, and if actual
+    /// counter is not as expected, increment or decrement current counter
+    pub fn fix_provider_counter(who: T::AccountId) -> DispatchResult {
+        let (current_providers, expected_providers) = Self::diagnose_providers(&who);
+
         #[allow(clippy::comparison_chain)]
-        if current_consumers < expected_consumers {
+        if current_providers < expected_providers {
             log::debug!(
                 target: LOG_TARGET,
-                "Account {:?} has consumers underflow: current({}) < expected ({}), incrementing ",
-                HexDisplay::from(&who.encode()), current_consumers, expected_consumers);
-            Self::increment_consumers(&who)?;
-        } else if current_consumers > expected_consumers {
+                "Account {:?} has providers underflow: current({}) < expected ({}), incrementing ",
+                HexDisplay::from(&who.encode()), current_providers, expected_providers);
+            Self::increment_providers(&who);
+        } else if current_providers > expected_providers {
             log::debug!(
                 target: LOG_TARGET,
-                "Account {:?} has consumers overflow: current({}) > expected ({}), decrementing ",
-                HexDisplay::from(&who.encode()), current_consumers, expected_consumers);
-            Self::decrement_consumers(&who);
+                "Account {:?} has providers overflow: current({}) > expected ({}), decrementing ",
+                HexDisplay::from(&who.encode()), current_providers, expected_providers);
+            Self::decrement_providers(&who)?;
         } else {
             log::trace!(
                 target: LOG_TARGET,
-                "Account {:?} neither has underflow nor overflow of consumers counter.",
+                "Account {:?} neither has underflow nor overflow of providers counter.",
                 HexDisplay::from(&who.encode())
             );
         }
@@ -58,6 +95,24 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Returns current and expected providers counter for a `who` account, without changing
+    /// anything
+    pub fn diagnose_providers(who: &T::AccountId) -> (u32, u32) {
+        let current_providers = T::AccountInfoProvider::get_providers(who);
+        let mut expected_providers: u32 = 0;
+
+        // balances pallet provides for an account as long as it holds any funds
+        if Self::free_or_reserved_non_zero(who) {
+            expected_providers += 1;
+        }
+
+        (current_providers, expected_providers)
+    }
+
+    fn free_or_reserved_non_zero(who: &T::AccountId) -> bool {
+        !T::BalancesProvider::is_free_zero(who) || !T::BalancesProvider::is_reserved_zero(who)
+    }
+
     fn reserved_or_frozen_non_zero(who: &T::AccountId) -> bool {
         !T::BalancesProvider::is_reserved_zero(who) || !T::BalancesProvider::is_frozen_zero(who)
     }
@@ -101,4 +156,21 @@ impl<T: Config> Pallet<T> {
             Self::deposit_event(Event::ConsumersCounterDecremented { who: who.clone() });
         }
     }
+
+    fn increment_providers(who: &T::AccountId) {
+        frame_system::Pallet::<T>::inc_providers(who);
+        Self::deposit_event(Event::ProvidersCounterIncremented { who: who.clone() });
+    }
+
+    fn decrement_providers(who: &T::AccountId) -> Result<(), DispatchError> {
+        // an account with neither providers nor sufficients left is reaped by the system
+        // pallet, so never remove the last reference this way
+        let providers = T::AccountInfoProvider::get_providers(who);
+        let sufficients = T::AccountInfoProvider::get_sufficients(who);
+        if providers > 1 || (providers == 1 && sufficients > 0) {
+            frame_system::Pallet::<T>::dec_providers(who)?;
+            Self::deposit_event(Event::ProvidersCounterDecremented { who: who.clone() });
+        }
+        Ok(())
+    }
 }
@@ -5,6 +5,7 @@ extern crate core;
 
 mod impls;
 mod traits;
+pub mod weights;
 
 #[cfg(test)]
 mod tests;
@@ -22,15 +23,18 @@ pub use pallet::*;
 #[frame_support::pallet]
 #[pallet_doc("../README.md")]
 pub mod pallet {
-    use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_REF_TIME_PER_MILLIS};
-    use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+    use frame_support::pallet_prelude::*;
+    use frame_system::{ensure_root, ensure_signed, pallet_prelude::OriginFor};
+    use sp_core::hexdisplay::HexDisplay;
+    use sp_std::vec::Vec;
 
     use crate::{
         traits::{
             AccountInfoProvider, BalancesProvider, BondedStashProvider, ContractInfoProvider,
             NextKeysSessionProvider,
         },
-        STORAGE_VERSION,
+        weights::WeightInfo,
+        LOG_TARGET, STORAGE_VERSION,
     };
 
     #[pallet::config]
@@ -46,6 +50,11 @@ pub mod pallet {
         type BondedStashProvider: BondedStashProvider<AccountId = Self::AccountId>;
         /// Something that tells whether an account is contract one
         type ContractInfoProvider: ContractInfoProvider<AccountId = Self::AccountId>;
+        /// The maximal number of accounts fixed in a single batch
+        #[pallet::constant]
+        type MaxFixBatch: Get<u32>;
+        /// Weights of the extrinsics of this pallet
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -61,6 +70,24 @@ pub mod pallet {
 
         /// A consumers counter was decremented for an account
         ConsumersCounterDecremented { who: T::AccountId },
+
+        /// A providers counter was incremented for an account
+        ProvidersCounterIncremented { who: T::AccountId },
+
+        /// A providers counter was decremented for an account
+        ProvidersCounterDecremented { who: T::AccountId },
+
+        /// A consumers counter of an account in a batch could not be fixed
+        ConsumersCounterFixFailed {
+            who: T::AccountId,
+            error: DispatchError,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Too many accounts in a single batch
+        BatchTooBig,
     }
 
     #[pallet::call]
@@ -84,9 +111,7 @@ pub mod pallet {
         /// - `who`: An account to be fixed
         ///
         #[pallet::call_index(0)]
-        #[pallet::weight(
-        Weight::from_parts(WEIGHT_REF_TIME_PER_MILLIS.saturating_mul(8), 0)
-        )]
+        #[pallet::weight(T::WeightInfo::fix_accounts_consumers_counter())]
         pub fn fix_accounts_consumers_counter(
             origin: OriginFor<T>,
             who: T::AccountId,
@@ -95,5 +120,62 @@ pub mod pallet {
             Self::fix_consumer_counter(who)?;
             Ok(())
         }
+
+        /// Same as `fix_accounts_consumers_counter`, but for many accounts at once. Accounts
+        /// with a valid consumers counter are left untouched. An account that cannot be fixed
+        /// does not fail the whole batch, `ConsumersCounterFixFailed` is emitted for it instead.
+        ///
+        /// - `origin`: Must be `Signed`.
+        /// - `who`: Accounts to be fixed, at most `MaxFixBatch` of them
+        ///
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::fix_accounts_consumers_counter_batch(who.len() as u32))]
+        pub fn fix_accounts_consumers_counter_batch(
+            origin: OriginFor<T>,
+            who: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(
+                who.len() as u32 <= T::MaxFixBatch::get(),
+                Error::<T>::BatchTooBig
+            );
+            for account in who {
+                if let Err(error) = Self::fix_consumer_counter(account.clone()) {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "Failed to fix consumers counter of account {:?}: {:?}",
+                        HexDisplay::from(&account.encode()),
+                        error
+                    );
+                    Self::deposit_event(Event::ConsumersCounterFixFailed {
+                        who: account,
+                        error,
+                    });
+                }
+            }
+            Ok(())
+        }
+
+        /// An account can have an underflow or an overflow of a `providers` counter, e.g. after
+        /// a faulty migration. This extrinsic recalculates the expected counter from the account
+        /// data and increments or decrements the current one by 1 if needed.
+        ///
+        /// The expected counter only accounts for the balances pallet, so a provider added
+        /// legitimately by another pallet would be removed. Hence the call is restricted to root,
+        /// who has to check the account first.
+        ///
+        /// - `origin`: Must be `Root`.
+        /// - `who`: An account to be fixed
+        ///
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::fix_accounts_providers_counter())]
+        pub fn fix_accounts_providers_counter(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::fix_provider_counter(who)?;
+            Ok(())
+        }
     }
 }
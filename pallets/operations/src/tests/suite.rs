@@ -1,7 +1,7 @@
 use std::{env::var, path::PathBuf};
 
 use frame_support::{
-    assert_ok,
+    assert_noop, assert_ok,
     traits::{Currency, LockableCurrency, ReservableCurrency, WithdrawReasons},
     weights::Weight,
 };
@@ -589,3 +589,193 @@ fn given_nominator_account_with_staking_lock_and_consumer_overflow_when_fixing_c
         assert_eq!(consumers(authority_id), 3);
     });
 }
+
+#[test]
+fn given_account_with_consumers_underflow_when_diagnosing_consumers_then_nothing_changes() {
+    let authority_id = 1_u64;
+    let non_authority_id = 2_u64;
+    new_test_ext(&[
+        (authority_id, true, 1000_u128),
+        (non_authority_id, false, 999_u128),
+    ])
+    .execute_with(|| {
+        assert_ok!(pallet_balances::Pallet::<TestRuntime>::reserve(
+            &non_authority_id,
+            3_u128
+        ));
+        frame_system::Pallet::<TestRuntime>::dec_consumers(&non_authority_id);
+        assert_eq!(consumers(non_authority_id), 0);
+
+        assert_eq!(
+            crate::Pallet::<TestRuntime>::diagnose_consumers(&non_authority_id),
+            (0, 1)
+        );
+        assert_eq!(consumers(non_authority_id), 0);
+        assert_eq!(
+            crate::Pallet::<TestRuntime>::diagnose_consumers(&authority_id),
+            (3, 3)
+        );
+        assert_eq!(pallet_operations_events().len(), 0);
+    });
+}
+
+#[test]
+fn given_accounts_with_consumers_underflow_when_fixing_consumers_in_batch_then_counters_are_valid()
+{
+    let authority_id = 1_u64;
+    let non_authority_id = 2_u64;
+    let other_non_authority_id = 3_u64;
+    new_test_ext(&[
+        (authority_id, true, 1000_u128),
+        (non_authority_id, false, 999_u128),
+        (other_non_authority_id, false, 999_u128),
+    ])
+    .execute_with(|| {
+        for account in [non_authority_id, other_non_authority_id] {
+            assert_ok!(pallet_balances::Pallet::<TestRuntime>::reserve(
+                &account, 3_u128
+            ));
+            frame_system::Pallet::<TestRuntime>::dec_consumers(&account);
+            assert_eq!(consumers(account), 0);
+        }
+
+        assert_ok!(
+            crate::Pallet::<TestRuntime>::fix_accounts_consumers_counter_batch(
+                RuntimeOrigin::signed(authority_id),
+                vec![authority_id, non_authority_id, other_non_authority_id]
+            )
+        );
+
+        assert_eq!(consumers(authority_id), 3);
+        assert_eq!(consumers(non_authority_id), 1);
+        assert_eq!(consumers(other_non_authority_id), 1);
+        assert_eq!(
+            pallet_operations_events(),
+            [
+                crate::Event::ConsumersCounterIncremented {
+                    who: non_authority_id
+                },
+                crate::Event::ConsumersCounterIncremented {
+                    who: other_non_authority_id
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn given_too_many_accounts_when_fixing_consumers_in_batch_then_fails() {
+    let authority_id = 1_u64;
+    new_test_ext(&[(authority_id, true, 1000_u128)]).execute_with(|| {
+        assert_noop!(
+            crate::Pallet::<TestRuntime>::fix_accounts_consumers_counter_batch(
+                RuntimeOrigin::signed(authority_id),
+                vec![authority_id; 4]
+            ),
+            crate::Error::<TestRuntime>::BatchTooBig
+        );
+    });
+}
+
+#[test]
+fn given_account_with_providers_overflow_when_fixing_providers_then_providers_counter_is_decremented(
+) {
+    let authority_id = 1_u64;
+    let non_authority_id = 2_u64;
+    new_test_ext(&[
+        (authority_id, true, 1000_u128),
+        (non_authority_id, false, 999_u128),
+    ])
+    .execute_with(|| {
+        frame_system::Pallet::<TestRuntime>::inc_providers(&non_authority_id);
+        assert_eq!(providers(non_authority_id), 2);
+
+        assert_noop!(
+            crate::Pallet::<TestRuntime>::fix_accounts_providers_counter(
+                RuntimeOrigin::signed(authority_id),
+                non_authority_id
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(
+            crate::Pallet::<TestRuntime>::fix_accounts_providers_counter(
+                RuntimeOrigin::root(),
+                non_authority_id
+            )
+        );
+
+        assert_eq!(providers(non_authority_id), 1);
+        assert_eq!(
+            pallet_operations_events(),
+            [crate::Event::ProvidersCounterDecremented {
+                who: non_authority_id
+            }]
+        );
+    });
+}
+
+#[test]
+fn given_account_with_valid_providers_when_fixing_providers_then_nothing_changes() {
+    let authority_id = 1_u64;
+    new_test_ext(&[(authority_id, true, 1000_u128)]).execute_with(|| {
+        assert_eq!(providers(authority_id), 1);
+
+        assert_ok!(
+            crate::Pallet::<TestRuntime>::fix_accounts_providers_counter(
+                RuntimeOrigin::root(),
+                authority_id
+            )
+        );
+
+        assert_eq!(providers(authority_id), 1);
+        assert_eq!(pallet_operations_events(), []);
+    });
+}
+
+#[test]
+fn given_account_that_cannot_be_fixed_when_fixing_consumers_in_batch_then_other_accounts_are_fixed()
+{
+    let authority_id = 1_u64;
+    let non_authority_id = 2_u64;
+    let account_without_providers = 3_u64;
+    new_test_ext(&[
+        (authority_id, true, 1000_u128),
+        (non_authority_id, false, 999_u128),
+    ])
+    .execute_with(|| {
+        assert_ok!(pallet_balances::Pallet::<TestRuntime>::reserve(
+            &non_authority_id,
+            3_u128
+        ));
+        frame_system::Pallet::<TestRuntime>::dec_consumers(&non_authority_id);
+        // A bonded account is expected to have a consumer, which cannot be added without providers.
+        pallet_staking::Bonded::<TestRuntime>::insert(
+            account_without_providers,
+            account_without_providers,
+        );
+        assert_eq!(providers(account_without_providers), 0);
+
+        assert_ok!(
+            crate::Pallet::<TestRuntime>::fix_accounts_consumers_counter_batch(
+                RuntimeOrigin::signed(authority_id),
+                vec![account_without_providers, non_authority_id]
+            )
+        );
+
+        assert_eq!(consumers(account_without_providers), 0);
+        assert_eq!(consumers(non_authority_id), 1);
+        assert_eq!(
+            pallet_operations_events(),
+            [
+                crate::Event::ConsumersCounterFixFailed {
+                    who: account_without_providers,
+                    error: sp_runtime::DispatchError::NoProviders,
+                },
+                crate::Event::ConsumersCounterIncremented {
+                    who: non_authority_id
+                },
+            ]
+        );
+    });
+}
@@ -10,6 +10,12 @@ pub trait AccountInfoProvider {
 
     /// Retrieves account's consumer counter
     fn get_consumers(who: &Self::AccountId) -> Self::RefCount;
+
+    /// Retrieves account's provider counter
+    fn get_providers(who: &Self::AccountId) -> Self::RefCount;
+
+    /// Retrieves account's sufficient counter
+    fn get_sufficients(who: &Self::AccountId) -> Self::RefCount;
 }
 
 impl<T> AccountInfoProvider for frame_system::Pallet<T>
@@ -22,6 +28,14 @@ where
     fn get_consumers(who: &Self::AccountId) -> Self::RefCount {
         frame_system::Pallet::<T>::consumers(who)
     }
+
+    fn get_providers(who: &Self::AccountId) -> Self::RefCount {
+        frame_system::Pallet::<T>::providers(who)
+    }
+
+    fn get_sufficients(who: &Self::AccountId) -> Self::RefCount {
+        frame_system::Pallet::<T>::sufficients(who)
+    }
 }
 
 pub trait BalancesProvider {
@@ -30,6 +44,9 @@ pub trait BalancesProvider {
     /// Balance type used by runtime
     type Balance;
 
+    /// Returns free funds of an account
+    fn is_free_zero(who: &Self::AccountId) -> bool;
+
     /// Returns reserved funds of an account
     fn is_reserved_zero(who: &Self::AccountId) -> bool;
 
@@ -41,6 +58,10 @@ impl<T: pallet_balances::Config<I>, I: 'static> BalancesProvider for pallet_bala
     type AccountId = T::AccountId;
     type Balance = T::Balance;
 
+    fn is_free_zero(who: &Self::AccountId) -> bool {
+        T::AccountStore::get(who).free.is_zero()
+    }
+
     fn is_reserved_zero(who: &Self::AccountId) -> bool {
         T::AccountStore::get(who).reserved.is_zero()
     }
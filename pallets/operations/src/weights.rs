@@ -0,0 +1,25 @@
+use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, Weight};
+
+/// Weight functions needed for pallet-operations.
+pub trait WeightInfo {
+    fn fix_accounts_consumers_counter() -> Weight;
+    fn fix_accounts_consumers_counter_batch(n: u32) -> Weight;
+    fn fix_accounts_providers_counter() -> Weight;
+}
+
+/// Hand-picked upper bounds, as the pallet is not benchmarked. Fixing a single account takes at
+/// most 8ms, and a batch pays that for every account on top of a base weight of 1ms.
+impl WeightInfo for () {
+    fn fix_accounts_consumers_counter() -> Weight {
+        Weight::from_parts(WEIGHT_REF_TIME_PER_MILLIS.saturating_mul(8), 0)
+    }
+
+    fn fix_accounts_consumers_counter_batch(n: u32) -> Weight {
+        Weight::from_parts(WEIGHT_REF_TIME_PER_MILLIS, 0)
+            .saturating_add(Self::fix_accounts_consumers_counter().saturating_mul(n.into()))
+    }
+
+    fn fix_accounts_providers_counter() -> Weight {
+        Weight::from_parts(WEIGHT_REF_TIME_PER_MILLIS.saturating_mul(8), 0)
+    }
+}
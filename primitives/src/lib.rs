@@ -139,6 +139,12 @@ pub const ADDRESSES_ENCODING: u8 = 42;
 /// ABFT unit creation delay (in ms)
 pub const DEFAULT_UNIT_CREATION_DELAY: u64 = 200;
 
+/// Lower bound of the adaptive ABFT unit creation delay (in ms)
+pub const DEFAULT_MIN_UNIT_CREATION_DELAY: u64 = 200;
+
+/// Upper bound of the adaptive ABFT unit creation delay (in ms)
+pub const DEFAULT_MAX_UNIT_CREATION_DELAY: u64 = 1000;
+
 /// Committee Size for new chains
 pub const DEFAULT_COMMITTEE_SIZE: u32 = 4;
 
@@ -160,6 +166,12 @@ pub const LENIENT_THRESHOLD: Perquintill = Perquintill::from_percent(90);
 /// Number of non-finalized blocks that halts block production
 pub const DEFAULT_MAX_NON_FINALIZED_BLOCKS: u32 = 20;
 
+/// Number of blocks for which signatures are aggregated at the same time
+pub const DEFAULT_MAX_IN_FLIGHT_AGGREGATIONS: u32 = 128;
+
+/// Number of sessions, including the current one, for which ABFT backups are kept
+pub const DEFAULT_BACKUP_RETAINED_SESSIONS: u32 = 2;
+
 /// A relative folder where to store ABFT backups
 pub const DEFAULT_BACKUP_FOLDER: &str = "backup-stash";
 
@@ -171,11 +183,38 @@ pub struct SessionCommittee<T> {
     pub producers: Vec<T>,
 }
 
+/// [`SessionCommittee`] prefixed with the version of its shape, so that clients can decode
+/// committees returned by runtimes both older and newer than themselves. Changes to the shape of
+/// the committee should be introduced as a new variant with the next index, never by modifying
+/// the existing ones.
+#[derive(Decode, Encode, TypeInfo, Debug, Clone, PartialEq, Eq)]
+pub enum VersionedSessionCommittee<T> {
+    #[codec(index = 0)]
+    V0(SessionCommittee<T>),
+}
+
+impl<T> VersionedSessionCommittee<T> {
+    /// The committee in its latest shape.
+    pub fn into_committee(self) -> SessionCommittee<T> {
+        match self {
+            VersionedSessionCommittee::V0(committee) => committee,
+        }
+    }
+}
+
+impl<T> From<SessionCommittee<T>> for VersionedSessionCommittee<T> {
+    fn from(committee: SessionCommittee<T>) -> Self {
+        VersionedSessionCommittee::V0(committee)
+    }
+}
+
 /// Openness of the process of the elections
 #[derive(Decode, Encode, TypeInfo, Debug, Clone, PartialEq, Eq)]
 pub enum ElectionOpenness {
     Permissioned,
     Permissionless,
+    /// Like `Permissionless`, but only validators from the allowlist can be chosen.
+    PermissionedWithAllowlist,
 }
 
 /// Represent desirable size of a committee in a session
@@ -190,7 +229,29 @@ pub struct CommitteeSeats {
     pub non_reserved_finality_seats: u32,
 }
 
+/// Reasons why a committee size is malformed.
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Debug)]
+pub enum CommitteeSeatsError {
+    NonReservedFinalitySeatsLargerThanNonReservedSeats,
+}
+
 impl CommitteeSeats {
+    /// Create a committee size, making sure the finality seats are a subset of the non reserved seats.
+    pub fn try_new(
+        reserved_seats: u32,
+        non_reserved_seats: u32,
+        non_reserved_finality_seats: u32,
+    ) -> Result<Self, CommitteeSeatsError> {
+        if non_reserved_finality_seats > non_reserved_seats {
+            return Err(CommitteeSeatsError::NonReservedFinalitySeatsLargerThanNonReservedSeats);
+        }
+        Ok(CommitteeSeats {
+            reserved_seats,
+            non_reserved_seats,
+            non_reserved_finality_seats,
+        })
+    }
+
     pub fn size(&self) -> u32 {
         self.reserved_seats.saturating_add(self.non_reserved_seats)
     }
@@ -253,12 +314,18 @@ pub struct ProductionBanConfig {
     pub clean_session_counter_delay: SessionCount,
     /// how many eras a validator is banned for
     pub ban_period: EraIndex,
+    /// for how many sessions after first appearing in the committee a validator is not marked as
+    /// underperforming
+    pub grace_sessions: SessionCount,
 }
 
 pub const DEFAULT_BAN_MINIMAL_EXPECTED_PERFORMANCE: Perbill = Perbill::from_percent(0);
 pub const DEFAULT_BAN_SESSION_COUNT_THRESHOLD: SessionCount = 3;
+pub const DEFAULT_BAN_GRACE_SESSIONS: SessionCount = 0;
 pub const DEFAULT_BAN_REASON_LENGTH: u32 = 300;
 pub const DEFAULT_MAX_WINNERS: u32 = u32::MAX;
+/// The smallest committee that tolerates a single faulty member.
+pub const DEFAULT_MIN_COMMITTEE_SIZE: u32 = 4;
 
 impl Default for ProductionBanConfig {
     fn default() -> Self {
@@ -267,6 +334,7 @@ impl Default for ProductionBanConfig {
             underperformed_session_count_threshold: DEFAULT_BAN_SESSION_COUNT_THRESHOLD,
             clean_session_counter_delay: DEFAULT_CLEAN_SESSION_COUNTER_DELAY,
             ban_period: DEFAULT_BAN_PERIOD,
+            grace_sessions: DEFAULT_BAN_GRACE_SESSIONS,
         }
     }
 }
@@ -280,6 +348,10 @@ pub enum BanReason {
 
     /// Any arbitrary reason
     OtherReason(BoundedVec<u8, ConstU32<DEFAULT_BAN_REASON_LENGTH>>),
+
+    /// Validator has been removed from the committee due to insufficient participation in the
+    /// finality in a given number of sessions
+    InsufficientFinalityParticipation(u32),
 }
 
 /// Details of why and for how long a validator is removed from the committee
@@ -300,6 +372,25 @@ pub struct EraValidators<AccountId> {
     pub non_reserved: Vec<AccountId>,
 }
 
+impl<AccountId> EraValidators<AccountId> {
+    /// All validators of the era, reserved first.
+    pub fn all(&self) -> impl Iterator<Item = &AccountId> {
+        self.reserved.iter().chain(self.non_reserved.iter())
+    }
+}
+
+impl<AccountId: PartialEq> EraValidators<AccountId> {
+    /// Whether `who` is a validator in the era, either reserved or not.
+    pub fn contains(&self, who: &AccountId) -> bool {
+        self.all().any(|validator| validator == who)
+    }
+
+    /// Whether `who` is a reserved validator in the era.
+    pub fn is_reserved(&self, who: &AccountId) -> bool {
+        self.reserved.contains(who)
+    }
+}
+
 impl<AccountId> Default for EraValidators<AccountId> {
     fn default() -> Self {
         Self {
@@ -323,6 +414,24 @@ pub enum SessionValidatorError {
     Other(Vec<u8>),
 }
 
+/// Error of predicting committees for a range of sessions. Kept apart from
+/// [`SessionValidatorError`] so that the encoding of the latter stays unchanged.
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Debug)]
+pub enum SessionRangeError {
+    /// The first session of the range is greater than the last one.
+    InvalidRange {
+        from: SessionIndex,
+        to: SessionIndex,
+    },
+    /// `session` is the first session of a requested range that is not within the limits.
+    SessionNotWithinRange {
+        session: SessionIndex,
+        lower_limit: SessionIndex,
+        upper_limit: SessionIndex,
+    },
+    Other(Vec<u8>),
+}
+
 /// All the data needed to verify block finality justifications.
 #[derive(Clone, Debug, TypeInfo, Encode, Decode, PartialEq, Eq)]
 pub struct SessionAuthorityData {
@@ -446,6 +555,53 @@ pub mod staking {
     }
 }
 
+pub mod performance {
+    use sp_runtime::Perquintill;
+
+    /// Number of blocks a single member of a committee of `committee_size` validators is expected
+    /// to produce in a session of `session_period` blocks. The result is rounded down, so when the
+    /// committee size does not divide the session period, the remaining blocks are not expected
+    /// from anyone. Returns 0 for an empty committee.
+    pub fn expected_blocks_per_validator(session_period: u32, committee_size: u32) -> u32 {
+        session_period.checked_div(committee_size).unwrap_or(0)
+    }
+
+    /// Part of the possible session reward that a validator gets for a given `performance`.
+    /// Validators performing at least at the `lenient_threshold` get the whole reward, below it
+    /// the reward decreases linearly down to nothing for no performance at all.
+    pub fn session_reward_ratio(
+        performance: Perquintill,
+        lenient_threshold: Perquintill,
+    ) -> Perquintill {
+        if performance >= lenient_threshold {
+            return Perquintill::one();
+        }
+
+        Perquintill::from_rational(performance.deconstruct(), lenient_threshold.deconstruct())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::expected_blocks_per_validator;
+
+        #[test]
+        fn divides_session_evenly() {
+            assert_eq!(expected_blocks_per_validator(900, 10), 90);
+        }
+
+        #[test]
+        fn rounds_down_when_committee_does_not_divide_session() {
+            assert_eq!(expected_blocks_per_validator(900, 7), 128);
+            assert_eq!(expected_blocks_per_validator(5, 10), 0);
+        }
+
+        #[test]
+        fn empty_committee_is_expected_nothing() {
+            assert_eq!(expected_blocks_per_validator(900, 0), 0);
+        }
+    }
+}
+
 pub type ScoreNonce = u32;
 
 pub type RawScore = Vec<u16>;
@@ -457,6 +613,43 @@ pub struct Score {
     pub points: RawScore,
 }
 
+/// Reasons why a score cannot be acted upon.
+#[derive(PartialEq, Eq, Decode, Encode, TypeInfo, Debug, Clone)]
+pub enum ScoreError {
+    /// The score is for a session that has not started yet.
+    FutureSession {
+        session_id: SessionIndex,
+        current_session: SessionIndex,
+    },
+    /// The score has points for more members than there are in the committee.
+    TooManyPoints { points: u32, committee_size: u32 },
+}
+
+impl Score {
+    /// Check that the score could have been produced by a committee of `committee_size` members
+    /// in a session not later than `current_session`.
+    pub fn validate(
+        &self,
+        current_session: SessionIndex,
+        committee_size: u32,
+    ) -> Result<(), ScoreError> {
+        if self.session_id > current_session {
+            return Err(ScoreError::FutureSession {
+                session_id: self.session_id,
+                current_session,
+            });
+        }
+        let points = self.points.len() as u32;
+        if points > committee_size {
+            return Err(ScoreError::TooManyPoints {
+                points,
+                committee_size,
+            });
+        }
+        Ok(())
+    }
+}
+
 pub mod crypto {
     use core::marker::PhantomData;
 
@@ -544,3 +737,76 @@ pub mod crypto {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{EraValidators, Score, ScoreError};
+
+    fn era_validators() -> EraValidators<u64> {
+        EraValidators {
+            reserved: vec![1, 2],
+            non_reserved: vec![3, 4, 5],
+        }
+    }
+
+    #[test]
+    fn iterates_over_all_validators() {
+        assert_eq!(
+            era_validators().all().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn recognizes_members() {
+        let era_validators = era_validators();
+        assert!(era_validators.contains(&1));
+        assert!(era_validators.is_reserved(&1));
+        assert!(era_validators.contains(&5));
+        assert!(!era_validators.is_reserved(&5));
+    }
+
+    #[test]
+    fn rejects_validator_in_neither_list() {
+        let era_validators = era_validators();
+        assert!(!era_validators.contains(&6));
+        assert!(!era_validators.is_reserved(&6));
+        assert!(!EraValidators::default().contains(&1));
+    }
+
+    fn score(session_id: u32, members: usize) -> Score {
+        Score {
+            session_id,
+            nonce: 1,
+            points: vec![0; members],
+        }
+    }
+
+    #[test]
+    fn accepts_score_within_bounds() {
+        assert_eq!(score(5, 4).validate(5, 4), Ok(()));
+        assert_eq!(score(3, 2).validate(5, 4), Ok(()));
+    }
+
+    #[test]
+    fn rejects_score_from_future_session() {
+        assert_eq!(
+            score(6, 4).validate(5, 4),
+            Err(ScoreError::FutureSession {
+                session_id: 6,
+                current_session: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_score_with_too_many_points() {
+        assert_eq!(
+            score(5, 5).validate(5, 4),
+            Err(ScoreError::TooManyPoints {
+                points: 5,
+                committee_size: 4,
+            })
+        );
+    }
+}
@@ -5,9 +5,15 @@ use std::num::{NonZeroU64, TryFromIntError};
 
 use futures::{future::BoxFuture, ready, FutureExt};
 use rate_limiter::RateLimiterFacade;
-use tokio::io::AsyncRead;
+use tokio::io::{AsyncRead, AsyncWrite};
 
-pub use crate::{rate_limiter::SharedRateLimiter, token_bucket::SharedTokenBucket};
+pub use crate::{
+    rate_limiter::{
+        HierarchicalRateLimiter, RateLimiterStats, RateLimiterStatsHandle, RateOfBlockedLimiter,
+        SharedRateLimiter,
+    },
+    token_bucket::SharedTokenBucket,
+};
 
 const LOG_TARGET: &str = "rate-limiter";
 
@@ -42,7 +48,7 @@ impl TryFrom<u64> for NonZeroRatePerSecond {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum RatePerSecond {
     Block,
     Rate(NonZeroRatePerSecond),
@@ -74,12 +80,14 @@ impl From<NonZeroRatePerSecond> for RatePerSecond {
 
 pub struct RateLimitedAsyncRead<Read> {
     rate_limiter: BoxFuture<'static, RateLimiterFacade>,
+    stats: RateLimiterStatsHandle,
     inner: Read,
 }
 
 impl<Read> RateLimitedAsyncRead<Read> {
     pub fn new(read: Read, rate_limiter: RateLimiterFacade) -> Self {
         Self {
+            stats: rate_limiter.stats_handle(),
             rate_limiter: Box::pin(rate_limiter.rate_limit(0)),
             inner: read,
         }
@@ -89,6 +97,10 @@ impl<Read> RateLimitedAsyncRead<Read> {
         &self.inner
     }
 
+    pub fn stats(&self) -> RateLimiterStats {
+        self.stats.stats()
+    }
+
     /// Helper method for the use of the [AsyncRead](tokio::io::AsyncRead) implementation.
     fn rate_limit(
         self: std::pin::Pin<&mut Self>,
@@ -128,19 +140,110 @@ where
     }
 }
 
+pub struct RateLimitedAsyncWrite<Write> {
+    rate_limiter: BoxFuture<'static, RateLimiterFacade>,
+    stats: RateLimiterStatsHandle,
+    inner: Write,
+}
+
+impl<Write> RateLimitedAsyncWrite<Write> {
+    pub fn new(write: Write, rate_limiter: RateLimiterFacade) -> Self {
+        Self {
+            stats: rate_limiter.stats_handle(),
+            rate_limiter: Box::pin(rate_limiter.rate_limit(0)),
+            inner: write,
+        }
+    }
+
+    pub fn inner(&self) -> &Write {
+        &self.inner
+    }
+
+    pub fn stats(&self) -> RateLimiterStats {
+        self.stats.stats()
+    }
+
+    fn get_inner(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut Write>
+    where
+        Write: Unpin,
+    {
+        let this = self.get_mut();
+        std::pin::Pin::new(&mut this.inner)
+    }
+
+    /// Helper method for the use of the [AsyncWrite](tokio::io::AsyncWrite) implementation.
+    /// Only the bytes actually written are accounted, so short writes are charged correctly.
+    fn rate_limit(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>>
+    where
+        Write: AsyncWrite + Unpin,
+    {
+        let this = self.get_mut();
+        let write = std::pin::Pin::new(&mut this.inner);
+
+        let sleeping_rate_limiter = ready!(this.rate_limiter.poll_unpin(cx));
+
+        let result = write.poll_write(cx, buf);
+        let last_write_size = match &result {
+            std::task::Poll::Ready(Ok(write_size)) => write_size.saturating_mul(8),
+            _ => 0,
+        };
+
+        this.rate_limiter = sleeping_rate_limiter.rate_limit(last_write_size).boxed();
+
+        result
+    }
+}
+
+impl<Write> AsyncWrite for RateLimitedAsyncWrite<Write>
+where
+    Write: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        self.rate_limit(cx, buf)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.get_inner().poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.get_inner().poll_shutdown(cx)
+    }
+}
+
 pub struct FuturesRateLimitedAsyncReadWrite<ReadWrite> {
     rate_limiter: BoxFuture<'static, RateLimiterFacade>,
+    stats: RateLimiterStatsHandle,
     inner: ReadWrite,
 }
 
 impl<ReadWrite> FuturesRateLimitedAsyncReadWrite<ReadWrite> {
     pub fn new(wrapped: ReadWrite, rate_limiter: RateLimiterFacade) -> Self {
         Self {
+            stats: rate_limiter.stats_handle(),
             rate_limiter: Box::pin(rate_limiter.rate_limit(0)),
             inner: wrapped,
         }
     }
 
+    pub fn stats(&self) -> RateLimiterStats {
+        self.stats.stats()
+    }
+
     fn get_inner(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut ReadWrite>
     where
         ReadWrite: Unpin,
@@ -214,3 +317,108 @@ where
         self.get_inner().poll_close(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+        time::{Duration, Instant},
+    };
+
+    use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+    use crate::{RateLimitedAsyncWrite, RateLimiterFacade};
+
+    /// Accepts at most `max_chunk` bytes per write, or fails the next write if asked to.
+    #[derive(Default)]
+    struct ChunkedWriter {
+        written: Vec<u8>,
+        max_chunk: usize,
+        fail_next: bool,
+    }
+
+    impl AsyncWrite for ChunkedWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.fail_next {
+                this.fail_next = false;
+                return Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into()));
+            }
+            let len = buf.len().min(this.max_chunk);
+            this.written.extend_from_slice(&buf[..len]);
+            Poll::Ready(Ok(len))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn writer(max_chunk: usize, rate: u64) -> RateLimitedAsyncWrite<ChunkedWriter> {
+        RateLimitedAsyncWrite::new(
+            ChunkedWriter {
+                max_chunk,
+                ..Default::default()
+            },
+            RateLimiterFacade::new(rate.into()),
+        )
+    }
+
+    #[tokio::test]
+    async fn partial_writes_are_charged_for_written_bytes_only() {
+        let mut writer = writer(10, 1_000_000);
+        let data = [43u8; 100];
+
+        assert_eq!(writer.write(&data).await.expect("should write"), 10);
+        // Charges are settled before the next write.
+        assert_eq!(writer.stats().granted_bytes, 0);
+        assert_eq!(writer.write(&data[10..]).await.expect("should write"), 10);
+        assert_eq!(writer.stats().granted_bytes, 10);
+        assert_eq!(writer.inner().written, data[..20]);
+    }
+
+    #[tokio::test]
+    async fn write_all_passes_all_data_through_partial_writes() {
+        let mut writer = writer(7, 1_000_000);
+        let data: Vec<u8> = (0..100).collect();
+
+        writer.write_all(&data).await.expect("should write");
+        assert_eq!(writer.inner().written, data);
+        // Everything but the last chunk has been settled.
+        assert_eq!(writer.stats().granted_bytes, 98);
+    }
+
+    #[tokio::test]
+    async fn failed_writes_are_not_charged() {
+        let mut writer = writer(100, 1_000_000);
+        let data = [43u8; 10];
+        writer.inner.fail_next = true;
+
+        assert!(writer.write(&data).await.is_err());
+        assert_eq!(writer.write(&data).await.expect("should write"), 10);
+        assert_eq!(writer.stats().granted_bytes, 0);
+        assert_eq!(writer.inner().written, data);
+    }
+
+    #[tokio::test]
+    async fn writes_are_delayed_according_to_charged_data() {
+        // 1000 bytes per second.
+        let mut writer = writer(100, 8000);
+        let data = [43u8; 100];
+
+        let start = Instant::now();
+        writer.write_all(&data).await.expect("should write");
+        writer.write_all(&data).await.expect("should write");
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        assert_eq!(writer.stats().granted_bytes, 100);
+    }
+}
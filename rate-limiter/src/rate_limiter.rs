@@ -1,8 +1,16 @@
-use std::time::Instant;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use futures::future::pending;
+use futures::{future::pending, join};
+use tokio::time::Instant as TokioInstant;
 
-use crate::{token_bucket::SharedTokenBucket, RatePerSecond};
+use crate::{token_bucket::SharedTokenBucket, NonZeroRatePerSecond, RatePerSecond};
 
 pub type SharedRateLimiter = RateLimiterFacade;
 
@@ -21,36 +29,270 @@ impl From<Deadline> for Option<Instant> {
     }
 }
 
-pub enum RateLimiterFacade {
+/// Statistics of a single rate limiter, useful for telling whether it is the limiter that slows
+/// down a connection.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct RateLimiterStats {
+    /// Total amount of data let through by the rate limiter.
+    pub granted_bytes: u64,
+    /// Total time spent waiting for the rate limiter.
+    pub sleeping_time_total: Duration,
+}
+
+#[derive(Default)]
+struct StatsCounters {
+    granted_bits: AtomicU64,
+    sleeping_time_nanos: AtomicU64,
+}
+
+impl StatsCounters {
+    fn report(&self, requested: u64, sleeping_time: Duration) {
+        self.granted_bits.fetch_add(requested, Ordering::Relaxed);
+        self.sleeping_time_nanos.fetch_add(
+            sleeping_time.as_nanos().try_into().unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn stats(&self) -> RateLimiterStats {
+        RateLimiterStats {
+            granted_bytes: self.granted_bits.load(Ordering::Relaxed) / 8,
+            sleeping_time_total: Duration::from_nanos(
+                self.sleeping_time_nanos.load(Ordering::Relaxed),
+            ),
+        }
+    }
+}
+
+/// Returned when trying to change the rate of a limiter created with [RatePerSecond::Block].
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct RateOfBlockedLimiter;
+
+impl Display for RateOfBlockedLimiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "the rate of a limiter not letting any traffic through cannot change"
+        )
+    }
+}
+
+/// Cheap handle allowing to read [RateLimiterStats] of a [RateLimiterFacade] while it is in use.
+#[derive(Clone)]
+pub struct RateLimiterStatsHandle(Arc<StatsCounters>);
+
+impl RateLimiterStatsHandle {
+    pub fn stats(&self) -> RateLimiterStats {
+        self.0.stats()
+    }
+}
+
+enum RateLimiter {
     NoTraffic,
-    RateLimiter(SharedTokenBucket),
+    Shared(SharedTokenBucket),
+    Hierarchical {
+        parent: SharedTokenBucket,
+        child: SharedTokenBucket,
+    },
+}
+
+pub struct RateLimiterFacade {
+    rate_limiter: RateLimiter,
+    stats: Arc<StatsCounters>,
 }
 
 impl RateLimiterFacade {
     pub fn new(rate: RatePerSecond) -> Self {
         match rate {
-            RatePerSecond::Block => Self::NoTraffic,
-            RatePerSecond::Rate(rate) => Self::RateLimiter(SharedTokenBucket::new(rate)),
+            RatePerSecond::Block => Self::with_rate_limiter(RateLimiter::NoTraffic),
+            RatePerSecond::Rate(rate) => {
+                Self::with_rate_limiter(RateLimiter::Shared(SharedTokenBucket::new(rate)))
+            }
+        }
+    }
+
+    fn with_rate_limiter(rate_limiter: RateLimiter) -> Self {
+        Self {
+            rate_limiter,
+            stats: Arc::new(StatsCounters::default()),
         }
     }
 
+    /// `read_size` is expected in bits, in the same manner as all rates of this crate.
     pub async fn rate_limit(self, read_size: usize) -> Self {
-        match self {
-            RateLimiterFacade::NoTraffic => pending().await,
-            RateLimiterFacade::RateLimiter(rate_limiter) => RateLimiterFacade::RateLimiter(
-                rate_limiter
-                    .rate_limit(read_size.try_into().unwrap_or(u64::MAX))
-                    .await,
-            ),
+        let requested = read_size.try_into().unwrap_or(u64::MAX);
+        let start = TokioInstant::now();
+        let rate_limiter = match self.rate_limiter {
+            RateLimiter::NoTraffic => pending().await,
+            RateLimiter::Shared(rate_limiter) => {
+                RateLimiter::Shared(rate_limiter.rate_limit(requested).await)
+            }
+            RateLimiter::Hierarchical { parent, child } => {
+                // Both limits are awaited concurrently, so a child that is throttled by its own
+                // limit does not occupy its share of the parent for longer than necessary.
+                let (parent, child) =
+                    join!(parent.rate_limit(requested), child.rate_limit(requested));
+                RateLimiter::Hierarchical { parent, child }
+            }
+        };
+        self.stats.report(requested, start.elapsed());
+        Self {
+            rate_limiter,
+            stats: self.stats,
         }
     }
 
+    /// Returns statistics gathered by this instance since it was created.
+    pub fn stats(&self) -> RateLimiterStats {
+        self.stats.stats()
+    }
+
+    /// Returns a handle allowing to read statistics of this instance, e.g. after it was moved
+    /// into one of the rate-limited wrappers.
+    pub fn stats_handle(&self) -> RateLimiterStatsHandle {
+        RateLimiterStatsHandle(self.stats.clone())
+    }
+
+    /// Changes the rate of this limiter and all limiters it was shared with. For children of
+    /// a [HierarchicalRateLimiter] only their own limit is changed. Limiters created with
+    /// [RatePerSecond::Block] never let any traffic through, so changing their rate fails.
+    pub fn set_rate(&self, rate: RatePerSecond) -> Result<(), RateOfBlockedLimiter> {
+        match &self.rate_limiter {
+            RateLimiter::NoTraffic => return Err(RateOfBlockedLimiter),
+            RateLimiter::Shared(shared_token_bucket) => shared_token_bucket.set_rate(rate),
+            RateLimiter::Hierarchical { child, .. } => child.set_rate(rate),
+        }
+        Ok(())
+    }
+
+    /// Shares the rate with a new instance. Statistics are not shared.
     pub fn share(&self) -> Self {
-        match self {
-            RateLimiterFacade::NoTraffic => RateLimiterFacade::NoTraffic,
-            RateLimiterFacade::RateLimiter(shared_token_bucket) => {
-                RateLimiterFacade::RateLimiter(shared_token_bucket.share())
+        let rate_limiter = match &self.rate_limiter {
+            RateLimiter::NoTraffic => RateLimiter::NoTraffic,
+            RateLimiter::Shared(shared_token_bucket) => {
+                RateLimiter::Shared(shared_token_bucket.share())
             }
+            RateLimiter::Hierarchical { parent, child } => RateLimiter::Hierarchical {
+                parent: parent.share(),
+                child: child.share(),
+            },
+        };
+        Self::with_rate_limiter(rate_limiter)
+    }
+}
+
+/// Rate limiter enforcing a global limit shared by all of its children, on top of a separate limit
+/// for each child. Data passing through a child needs to fit into both of these limits. When the
+/// global limit is the bottleneck, it is divided equally between all children that are actively
+/// using it, while idle or stalled children do not hold any part of it.
+pub struct HierarchicalRateLimiter {
+    parent: SharedTokenBucket,
+    child_rate: NonZeroRatePerSecond,
+}
+
+impl HierarchicalRateLimiter {
+    /// Constructs a new instance of [HierarchicalRateLimiter] where all children share the
+    /// `global_rate` and each of them is additionally limited by `child_rate`.
+    pub fn new(global_rate: NonZeroRatePerSecond, child_rate: NonZeroRatePerSecond) -> Self {
+        Self {
+            parent: SharedTokenBucket::new(global_rate),
+            child_rate,
         }
     }
+
+    /// Creates a new child, e.g. for a single connection.
+    pub fn child(&self) -> RateLimiterFacade {
+        RateLimiterFacade::with_rate_limiter(RateLimiter::Hierarchical {
+            parent: self.parent.share(),
+            child: SharedTokenBucket::new(self.child_rate),
+        })
+    }
+
+    /// Changes the global rate shared by all children.
+    pub fn set_rate(&self, rate: RatePerSecond) {
+        self.parent.set_rate(rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use tokio::time::timeout;
+
+    use super::{HierarchicalRateLimiter, RateLimiterFacade, RateOfBlockedLimiter};
+    use crate::RatePerSecond;
+
+    #[tokio::test]
+    async fn child_is_limited_by_global_rate() {
+        let rate_limiter = HierarchicalRateLimiter::new(
+            1000.try_into().expect("1000 > 0 qed"),
+            1_000_000.try_into().expect("1_000_000 > 0 qed"),
+        );
+        let child = rate_limiter.child();
+
+        let start = Instant::now();
+        child.rate_limit(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn child_is_limited_by_its_own_rate() {
+        let rate_limiter = HierarchicalRateLimiter::new(
+            1_000_000.try_into().expect("1_000_000 > 0 qed"),
+            1000.try_into().expect("1000 > 0 qed"),
+        );
+        let child = rate_limiter.child();
+
+        let start = Instant::now();
+        child.rate_limit(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn stalled_child_does_not_hold_global_rate() {
+        let rate_limiter = HierarchicalRateLimiter::new(
+            1000.try_into().expect("1000 > 0 qed"),
+            1000.try_into().expect("1000 > 0 qed"),
+        );
+        let stalled_child = rate_limiter.child();
+        let stalled_child = stalled_child.rate_limit(10).await;
+        let child = rate_limiter.child();
+
+        let result = timeout(Duration::from_secs(1), child.rate_limit(100)).await;
+        assert!(result.is_ok());
+        drop(stalled_child);
+    }
+
+    #[tokio::test]
+    async fn stats_account_granted_data_and_sleeping_time() {
+        let rate_limiter = RateLimiterFacade::new(8000.into());
+        let stats_handle = rate_limiter.stats_handle();
+        assert_eq!(rate_limiter.stats().granted_bytes, 0);
+
+        let rate_limiter = rate_limiter.rate_limit(800).await;
+        let stats = rate_limiter.stats();
+        assert_eq!(stats.granted_bytes, 100);
+        assert!(stats.sleeping_time_total >= Duration::from_millis(90));
+        assert_eq!(stats_handle.stats(), stats);
+
+        let shared = rate_limiter.share();
+        assert_eq!(shared.stats().granted_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn rate_of_blocked_limiter_cannot_change() {
+        let rate_limiter = RateLimiterFacade::new(RatePerSecond::Block);
+        assert_eq!(
+            rate_limiter.set_rate(1000.into()),
+            Err(RateOfBlockedLimiter)
+        );
+        assert_eq!(
+            rate_limiter.share().set_rate(1000.into()),
+            Err(RateOfBlockedLimiter)
+        );
+
+        let rate_limiter = RateLimiterFacade::new(1000.into());
+        assert_eq!(rate_limiter.set_rate(RatePerSecond::Block), Ok(()));
+    }
 }
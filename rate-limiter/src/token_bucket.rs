@@ -8,11 +8,11 @@ use std::{
     time::{Duration, Instant},
 };
 
-use futures::{future::pending, Future, FutureExt};
+use futures::{future::pending, pin_mut, Future, FutureExt};
 use log::trace;
-use tokio::time::sleep;
+use tokio::{sync::Notify, time::sleep};
 
-use crate::{NonZeroRatePerSecond, LOG_TARGET, MIN};
+use crate::{NonZeroRatePerSecond, RatePerSecond, LOG_TARGET, MIN};
 
 /// Returns a non-decreasing values of type [std::time::Instant].
 pub trait TimeProvider {
@@ -53,6 +53,7 @@ impl SleepUntil for TokioSleepUntil {
 struct TokenBucket<T = TokioTimeProvider> {
     last_update: Instant,
     rate_per_second: NonZeroU64,
+    max_burst: Option<NonZeroU64>,
     requested: u64,
     time_provider: T,
 }
@@ -62,6 +63,7 @@ impl<T> std::fmt::Debug for TokenBucket<T> {
         f.debug_struct("TokenBucket")
             .field("last_update", &self.last_update)
             .field("rate_per_second", &self.rate_per_second)
+            .field("max_burst", &self.max_burst)
             .field("requested", &self.requested)
             .finish()
     }
@@ -84,12 +86,15 @@ where
             time_provider,
             last_update: now,
             rate_per_second: rate_per_second.into(),
+            max_burst: None,
             requested: NonZeroU64::from(rate_per_second).into(),
         }
     }
 
+    /// Maximal number of tokens that can accumulate while idle. Unless capped explicitly, it is
+    /// equal to one second's worth of tokens.
     fn upper_bound_of_tokens(&self) -> u64 {
-        self.rate_per_second.into()
+        self.max_burst.unwrap_or(self.rate_per_second).into()
     }
 
     fn available(&self) -> Option<u64> {
@@ -141,18 +146,30 @@ where
 
     /// Sets a rate in bits-per-second.
     pub fn set_rate(&mut self, rate_per_second: NonZeroRatePerSecond) {
-        // We need to update our tokens till now using previous rate.
+        self.change_upper_bound_of_tokens(|token_bucket| {
+            token_bucket.rate_per_second = rate_per_second.into()
+        });
+    }
+
+    /// Caps the number of tokens that can accumulate while idle, i.e. the size of a burst
+    /// allowed after a period of inactivity.
+    pub fn set_max_burst(&mut self, max_burst: NonZeroU64) {
+        self.change_upper_bound_of_tokens(|token_bucket| token_bucket.max_burst = Some(max_burst));
+    }
+
+    fn change_upper_bound_of_tokens(&mut self, change: impl FnOnce(&mut Self)) {
+        // We need to update our tokens till now using previous settings.
         self.update_tokens();
-        // We need to convert all left tokens to format compatible with the new rate.
+        // We need to convert all left tokens to format compatible with the new upper bound.
         let available = self.available();
-        let previous_rate_per_second = self.rate_per_second.get();
-        self.rate_per_second = rate_per_second.into();
+        let previous_upper_bound = self.upper_bound_of_tokens();
+        change(self);
+        let upper_bound = self.upper_bound_of_tokens();
         if let Some(available) = available {
-            let max_for_available = self.upper_bound_of_tokens();
-            let available_after_rate_update = min(available, max_for_available);
-            self.requested = self.rate_per_second.get() - available_after_rate_update;
+            let available_after_update = min(available, upper_bound);
+            self.requested = upper_bound - available_after_update;
         } else {
-            self.requested = self.requested - previous_rate_per_second + self.rate_per_second.get();
+            self.requested = self.requested - previous_upper_bound + upper_bound;
         }
     }
 
@@ -183,9 +200,10 @@ const BANDWIDTH_CHECK_INTERVAL: Duration = Duration::from_millis(250);
 /// Implementation of the bandwidth sharing strategy that attempts to assign equal portion of the total bandwidth to all active
 /// consumers of that bandwidth.
 pub struct SharedBandwidthManager {
-    max_rate: NonZeroRatePerSecond,
+    max_rate: Arc<AtomicU64>,
+    max_rate_changed: Arc<Notify>,
     peers_count: Arc<AtomicU64>,
-    already_requested: Option<NonZeroRatePerSecond>,
+    already_requested: Option<RatePerSecond>,
 }
 
 impl SharedBandwidthManager {
@@ -193,7 +211,8 @@ impl SharedBandwidthManager {
     /// calling consumers (clones of this instance).
     pub fn new(max_rate: NonZeroRatePerSecond) -> Self {
         Self {
-            max_rate,
+            max_rate: Arc::new(AtomicU64::new(max_rate.into())),
+            max_rate_changed: Arc::new(Notify::new()),
             peers_count: Arc::new(AtomicU64::new(0)),
             already_requested: None,
         }
@@ -201,23 +220,37 @@ impl SharedBandwidthManager {
 
     pub fn share(&self) -> Self {
         Self {
-            max_rate: self.max_rate,
+            max_rate: self.max_rate.clone(),
+            max_rate_changed: self.max_rate_changed.clone(),
             peers_count: self.peers_count.clone(),
             already_requested: None,
         }
     }
 
-    fn calculate_bandwidth(&mut self, active_children: Option<u64>) -> NonZeroRatePerSecond {
+    /// Changes the rate shared by all consumers. Unlike changes in the number of active consumers,
+    /// which are rather frequent, this wakes up all consumers waiting in
+    /// [SharedBandwidthManager::bandwidth_changed] immediately.
+    pub fn set_max_rate(&self, max_rate: RatePerSecond) {
+        self.max_rate.store(max_rate.into(), Ordering::SeqCst);
+        self.max_rate_changed.notify_waiters();
+    }
+
+    fn calculate_bandwidth(&mut self, active_children: Option<u64>) -> RatePerSecond {
         let active_children =
             active_children.unwrap_or_else(|| self.peers_count.load(Ordering::SeqCst));
-        let rate = u64::from(self.max_rate) / active_children;
+        let max_rate = self.max_rate.load(Ordering::SeqCst);
+        if max_rate == 0 {
+            return RatePerSecond::Block;
+        }
+        let rate = max_rate / active_children;
         NonZeroU64::try_from(rate)
             .map(NonZeroRatePerSecond::from)
             .unwrap_or(MIN)
+            .into()
     }
 
     /// Allocate part of the shared bandwidth.
-    pub fn request_bandwidth(&mut self) -> NonZeroRatePerSecond {
+    pub fn request_bandwidth(&mut self) -> RatePerSecond {
         let active_children = (self.already_requested.is_none())
             .then(|| 1 + self.peers_count.fetch_add(1, Ordering::SeqCst));
         let rate = self.calculate_bandwidth(active_children);
@@ -236,18 +269,26 @@ impl SharedBandwidthManager {
     /// Awaits for a notification about some change to previously allocated rate. For performance reasons, it simply actively
     /// queries for all active peers in a looped manner on every interval of [BANDWIDTH_CHECK_INTERVAL]. Alternative solutions
     /// could use a mechanism similar to [tokio::sync::watch], but our tests showed that such solutions perform rather poorly
-    /// compared to this approach.
-    pub async fn bandwidth_changed(&mut self) -> NonZeroRatePerSecond {
+    /// compared to this approach. Changes of the shared rate are rare, so these are notified about directly.
+    pub async fn bandwidth_changed(&mut self) -> RatePerSecond {
         let Some(previous_rate) = self.already_requested else {
             return pending().await;
         };
-        let mut rate = self.calculate_bandwidth(None);
-        while rate == previous_rate {
-            sleep(BANDWIDTH_CHECK_INTERVAL).await;
-            rate = self.calculate_bandwidth(None);
+        loop {
+            // Registered before checking the rate, so that a change right after the check is not missed.
+            let max_rate_changed = self.max_rate_changed.notified();
+            pin_mut!(max_rate_changed);
+            max_rate_changed.as_mut().enable();
+            let rate = self.calculate_bandwidth(None);
+            if rate != previous_rate {
+                self.already_requested = Some(rate);
+                return rate;
+            }
+            tokio::select! {
+                _ = sleep(BANDWIDTH_CHECK_INTERVAL) => {},
+                _ = max_rate_changed => {},
+            }
         }
-        self.already_requested = Some(rate);
-        rate
     }
 }
 
@@ -258,6 +299,7 @@ impl SharedBandwidthManager {
 struct AsyncTokenBucket<TP = TokioTimeProvider, SU = TokioSleepUntil> {
     token_bucket: TokenBucket<TP>,
     next_deadline: Option<Instant>,
+    blocked: bool,
     sleep_until: SU,
 }
 
@@ -271,6 +313,7 @@ where
         Self {
             token_bucket,
             next_deadline: None,
+            blocked: false,
             sleep_until,
         }
     }
@@ -281,8 +324,17 @@ where
         self.next_deadline = TokenBucket::rate_limit(&mut self.token_bucket, requested);
     }
 
-    /// Sets rate of this limiter and updates the required delay accordingly.
-    pub fn set_rate(&mut self, rate: NonZeroRatePerSecond) {
+    /// Sets rate of this limiter and updates the required delay accordingly. While the rate is
+    /// [RatePerSecond::Block], [AsyncTokenBucket::wait] never finishes.
+    pub fn set_rate(&mut self, rate: RatePerSecond) {
+        let rate = match rate {
+            RatePerSecond::Block => {
+                self.blocked = true;
+                return;
+            }
+            RatePerSecond::Rate(rate) => rate,
+        };
+        self.blocked = false;
         if self.token_bucket.rate() != rate {
             self.token_bucket.set_rate(rate);
             self.next_deadline = self.token_bucket.rate_limit(0);
@@ -295,6 +347,9 @@ where
         TP: TimeProvider + Send,
         SU: SleepUntil + Send,
     {
+        if self.blocked {
+            return pending().await;
+        }
         if let Some(deadline) = self.next_deadline {
             self.sleep_until.sleep_until(deadline).await;
             self.next_deadline = None;
@@ -339,6 +394,18 @@ impl<TP, SU> SharedTokenBucket<TP, SU> {
         }
     }
 
+    /// Caps the number of tokens that can accumulate while idle, so that a connection can use
+    /// at most `tokens` at once after a long period of inactivity. By default, one second's worth
+    /// of tokens can accumulate.
+    pub fn with_max_burst(mut self, tokens: u64) -> Self
+    where
+        TP: TimeProvider,
+    {
+        let tokens = NonZeroU64::new(tokens).unwrap_or(NonZeroU64::MIN);
+        self.rate_limiter.token_bucket.set_max_burst(tokens);
+        self
+    }
+
     pub fn share(&self) -> Self
     where
         TP: Clone,
@@ -351,7 +418,14 @@ impl<TP, SU> SharedTokenBucket<TP, SU> {
         }
     }
 
-    fn request_bandwidth(&mut self) -> NonZeroRatePerSecond {
+    /// Changes the rate shared by this instance and all instances it was shared with. Instances
+    /// currently waiting recompute their delays according to the new rate. Setting it to
+    /// [RatePerSecond::Block] suspends all of them until the rate is raised again.
+    pub fn set_rate(&self, rate: RatePerSecond) {
+        self.shared_bandwidth.set_max_rate(rate);
+    }
+
+    fn request_bandwidth(&mut self) -> RatePerSecond {
         self.need_to_notify_parent = true;
         self.shared_bandwidth.request_bandwidth()
     }
@@ -413,8 +487,13 @@ mod tests {
     };
     use parking_lot::Mutex;
 
-    use super::{SharedBandwidthManager, SleepUntil, TimeProvider, TokenBucket};
-    use crate::token_bucket::{AsyncTokenBucket, NonZeroRatePerSecond, SharedTokenBucket};
+    use super::{
+        SharedBandwidthManager, SleepUntil, TimeProvider, TokenBucket, BANDWIDTH_CHECK_INTERVAL,
+    };
+    use crate::{
+        token_bucket::{AsyncTokenBucket, NonZeroRatePerSecond, SharedTokenBucket},
+        RatePerSecond,
+    };
 
     impl<F> TimeProvider for F
     where
@@ -439,7 +518,10 @@ mod tests {
         let mut another_cloned_bandwidth_share = cloned_bandwidth_share.share();
 
         // only one consumer, so it should get whole bandwidth
-        assert_eq!(bandwidth_share.request_bandwidth(), rate);
+        assert_eq!(
+            bandwidth_share.request_bandwidth(),
+            RatePerSecond::from(rate)
+        );
 
         // since other instances did not request for bandwidth, they should not receive notification that it has changed
         let poll_result = poll_fn(|cx| {
@@ -459,7 +541,7 @@ mod tests {
         assert_eq!(poll_result, Poll::Pending);
 
         // two consumers should equally divide the bandwidth
-        let rate = 5.try_into().expect("5 > 0 qed");
+        let rate = RatePerSecond::from(5);
         assert_eq!(cloned_bandwidth_share.request_bandwidth(), rate);
         assert_eq!(bandwidth_share.bandwidth_changed().await, rate);
 
@@ -475,12 +557,61 @@ mod tests {
         assert!((9..10).contains(&(bandwidth + another_bandwidth + yet_another_bandwidth)));
 
         // all consumers should be notified after one of them become idle
-        let rate = 5.try_into().expect("5 > 0 qed");
+        let rate = RatePerSecond::from(5);
         another_cloned_bandwidth_share.notify_idle();
         assert_eq!(cloned_bandwidth_share.bandwidth_changed().await, rate);
         assert_eq!(bandwidth_share.bandwidth_changed().await, rate);
     }
 
+    #[tokio::test]
+    async fn shared_bandwidth_follows_changes_of_max_rate() {
+        let mut bandwidth_share = SharedBandwidthManager::new(10.try_into().expect("10 > 0 qed"));
+        let cloned_bandwidth_share = bandwidth_share.share();
+
+        assert_eq!(bandwidth_share.request_bandwidth(), RatePerSecond::from(10));
+
+        cloned_bandwidth_share.set_max_rate(RatePerSecond::Block);
+        assert_eq!(
+            bandwidth_share.bandwidth_changed().await,
+            RatePerSecond::Block
+        );
+
+        cloned_bandwidth_share.set_max_rate(RatePerSecond::from(20));
+        assert_eq!(
+            bandwidth_share.bandwidth_changed().await,
+            RatePerSecond::from(20)
+        );
+    }
+
+    #[tokio::test]
+    async fn change_of_max_rate_is_noticed_without_polling() {
+        let mut bandwidth_share = SharedBandwidthManager::new(10.try_into().expect("10 > 0 qed"));
+        let cloned_bandwidth_share = bandwidth_share.share();
+        assert_eq!(bandwidth_share.request_bandwidth(), RatePerSecond::from(10));
+
+        let start = Instant::now();
+        let (rate, _) = futures::join!(bandwidth_share.bandwidth_changed(), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            cloned_bandwidth_share.set_max_rate(RatePerSecond::from(20));
+        });
+        assert_eq!(rate, RatePerSecond::from(20));
+        assert!(start.elapsed() < BANDWIDTH_CHECK_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn sleeping_shared_token_bucket_wakes_up_early_after_rate_increase() {
+        let token_bucket = SharedTokenBucket::new(1000.try_into().expect("1000 > 0 qed"));
+        let shared_token_bucket = token_bucket.share();
+
+        // At the initial rate this would take ten seconds.
+        let start = Instant::now();
+        futures::join!(token_bucket.rate_limit(10_000), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            shared_token_bucket.set_rate(RatePerSecond::from(10_000_000));
+        });
+        assert!(start.elapsed() < Duration::from_millis(50) + BANDWIDTH_CHECK_INTERVAL);
+    }
+
     /// Allows to treat [TokenBucket] and [SharedTokenBucket] in similar fashion in our tests.
     trait RateLimiter: Sized {
         async fn rate_limit(self, requested: u64) -> (Self, Option<Instant>);
@@ -726,6 +857,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn accumulated_tokens_are_capped_by_max_burst() {
+        let limit_per_second = 1_000_000.try_into().expect("1_000_000 > 0 qed");
+        let now = Instant::now();
+        let time_to_return = Arc::new(parking_lot::RwLock::new(now));
+        let time_provider = time_to_return.clone();
+        let time_provider: Arc<Box<dyn TimeProvider + Send + Sync>> =
+            Arc::new(Box::new(move || *time_provider.read()));
+        let mut default_token_bucket =
+            TokenBucket::new_internal(limit_per_second, time_provider.clone());
+        let mut capped_token_bucket = TokenBucket::new_internal(limit_per_second, time_provider);
+        capped_token_bucket.set_max_burst(2_000_000.try_into().expect("2_000_000 > 0 qed"));
+
+        let after_idle = now + Duration::from_secs(10);
+        *time_to_return.write() = after_idle;
+        assert_eq!(capped_token_bucket.rate_limit(2_000_000), None);
+        assert_eq!(
+            capped_token_bucket.rate_limit(1_000_000),
+            Some(after_idle + Duration::from_secs(1))
+        );
+
+        // by default only one second's worth of tokens accumulates
+        assert_eq!(
+            default_token_bucket.rate_limit(2_000_000),
+            Some(after_idle + Duration::from_secs(1))
+        );
+    }
+
     #[tokio::test]
     async fn multiple_calls_buildup_wait_time() {
         multiple_calls_buildup_wait_time_test::<TokenBucket<_>>().await;
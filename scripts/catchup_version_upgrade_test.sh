@@ -28,7 +28,7 @@ function set_upgrade_session {
     local seed=$5
     local status=$6
 
-    docker run --rm --network container:$validator cliain:latest --node ws://127.0.0.1:$port --seed $seed version-upgrade-schedule --version $version --session $session --expected-state $status
+    docker run --rm --network container:$validator cliain:latest --node ws://127.0.0.1:$port --seed $seed finality-version schedule --version $version --session $session --expected-state $status
 }
 
 function check_if_disconnected() {
@@ -1,6 +1,11 @@
-use std::ops::RangeInclusive;
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+};
 
 use anyhow::bail;
+use log::warn;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -42,6 +47,80 @@ impl Default for SyntheticNetwork {
     }
 }
 
+impl SyntheticNetwork {
+    /// Sets `latency` in milliseconds for both incoming and outgoing traffic of the default link.
+    pub fn with_symmetric_latency(mut self, latency: u64) -> Self {
+        self.default_link.ingress.latency = latency;
+        self.default_link.egress.latency = latency;
+        self
+    }
+
+    /// Sets `latency` in milliseconds for outgoing traffic of the default link.
+    pub fn with_out_latency(mut self, latency: u64) -> Self {
+        self.default_link.egress.latency = latency;
+        self
+    }
+
+    /// Sets packet `loss` for both incoming and outgoing traffic of the default link.
+    pub fn with_loss(mut self, loss: StrengthParam) -> Self {
+        self.default_link.ingress.loss = loss.clone();
+        self.default_link.egress.loss = loss;
+        self
+    }
+
+    /// Adds a flow labeled `label` that drops all traffic matching `flow`.
+    pub fn with_partition_between(mut self, label: NonEmptyString, flow: Flow) -> Self {
+        let mut partition = SyntheticFlow::new(label);
+        partition.flow = flow;
+        partition.link.ingress.loss = StrengthParam::one();
+        partition.link.egress.loss = StrengthParam::one();
+        self.flows.push(partition);
+        self
+    }
+
+    /// Checks that labels of all flows are unique. Warns about flows with a rate exceeding the
+    /// rate of the default link, as such flows are effectively limited by the default link.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut labels = HashSet::new();
+        let mut duplicated_labels = Vec::new();
+        for flow in &self.flows {
+            let label = flow.label.as_ref();
+            if !labels.insert(label) && !duplicated_labels.contains(label) {
+                duplicated_labels.push(label.clone());
+            }
+            if flow.link.ingress.rate > self.default_link.ingress.rate
+                || flow.link.egress.rate > self.default_link.egress.rate
+            {
+                warn!("rate of the flow `{label}` exceeds rate of the default link");
+            }
+        }
+
+        if !duplicated_labels.is_empty() {
+            return Err(ConfigError::DuplicatedLabels(duplicated_labels));
+        }
+        Ok(())
+    }
+}
+
+/// Reasons for which a [`SyntheticNetwork`] config is rejected.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Labels shared by more than one flow.
+    DuplicatedLabels(Vec<String>),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::DuplicatedLabels(labels) => {
+                write!(f, "flow labels are not unique: {}", labels.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SyntheticLink {
     pub ingress: QualityOfService,
@@ -146,6 +225,10 @@ impl StrengthParam {
     const fn zero() -> Self {
         Self(0.0)
     }
+
+    const fn one() -> Self {
+        Self(1.0)
+    }
 }
 
 impl AsRef<f64> for StrengthParam {
@@ -260,7 +343,10 @@ impl SyntheticNetworkClient {
         }
     }
 
+    /// Validates `config` and sends it to the synthetic-network. Invalid configs are rejected
+    /// with a [`ConfigError`] without being sent.
     pub async fn commit_config(&mut self, config: &SyntheticNetwork) -> anyhow::Result<()> {
+        config.validate()?;
         let result = self.client.post(&self.url).json(config).send().await;
         Ok(result.map(|_| ())?)
     }
@@ -270,3 +356,84 @@ impl SyntheticNetworkClient {
         Ok(result.json::<SyntheticNetwork>().await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn flow_serializes_to_format_of_synthetic_network() {
+        let flow = Flow {
+            ip: IpPattern::Ip(0x0a000001),
+            protocol: Protocol::Udp,
+            port_range: PortRange::new(30333, 30343).unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&flow).unwrap(),
+            json!({
+                "ip": 0x0a000001,
+                "protocol": 17,
+                "port_min": 30333,
+                "port_max": 30343,
+            })
+        );
+    }
+
+    #[test]
+    fn latency_builders_set_default_link() {
+        let network = SyntheticNetwork::default()
+            .with_symmetric_latency(100)
+            .with_out_latency(200);
+
+        assert_eq!(network.default_link.ingress.latency, 100);
+        assert_eq!(network.default_link.egress.latency, 200);
+        assert!(network.flows.is_empty());
+    }
+
+    #[test]
+    fn loss_builder_sets_both_directions() {
+        let network = SyntheticNetwork::default().with_loss(StrengthParam::new(0.25).unwrap());
+
+        assert_eq!(*network.default_link.ingress.loss.as_ref(), 0.25);
+        assert_eq!(*network.default_link.egress.loss.as_ref(), 0.25);
+    }
+
+    #[test]
+    fn partition_drops_all_packets_of_flow() {
+        let flow = Flow {
+            ip: IpPattern::Ip(0x0a000001),
+            protocol: Protocol::Tcp,
+            port_range: PortRange::from(30343),
+        };
+        let network = SyntheticNetwork::default()
+            .with_partition_between(NonEmptyString::new("partition".into()).unwrap(), flow);
+
+        let serialized = serde_json::to_value(&network).unwrap();
+        let partition = &serialized["flows"][0];
+        assert_eq!(partition["label"], "partition");
+        assert_eq!(partition["flow"]["port_min"], 30343);
+        assert_eq!(partition["link"]["ingress"]["loss"], 1.0);
+        assert_eq!(partition["link"]["egress"]["loss"], 1.0);
+        assert_eq!(
+            partition["link"]["egress"]["rate"],
+            QualityOfService::default().rate
+        );
+        assert!(network.validate().is_ok());
+    }
+
+    #[test]
+    fn default_flow_matches_all_traffic() {
+        assert_eq!(
+            serde_json::to_value(Flow::default()).unwrap(),
+            json!({
+                "ip": 0,
+                "protocol": 0,
+                "port_min": 0,
+                "port_max": u16::MAX,
+            })
+        );
+    }
+}